@@ -0,0 +1,100 @@
+//! Adoption of memory blocks from another allocator.
+
+use super::{AllocError, AllocRef, Global, Layout};
+use core::ptr::{self, NonNull};
+
+/// An allocator which can take over memory blocks allocated by `A` without copying them.
+///
+/// The `transfer_in` methods of the collections use this to move their buffers into another
+/// allocator. If the allocator does not implement `AdoptAlloc` for the source allocator, or
+/// [`adopt`] fails, the contents are copied into a new block instead.
+///
+/// # Safety
+///
+/// On success, the returned block must be owned by `self`, must fit `layout` and must contain
+/// the contents of the adopted block. On error, the block must still be owned by `from`.
+///
+/// [`adopt`]: AdoptAlloc::adopt
+pub unsafe trait AdoptAlloc<A: AllocRef>: AllocRef {
+    /// Takes ownership of `ptr`, which is *currently allocated* by `from` with `layout`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must denote a block of memory currently allocated by `from`, and `layout` must fit
+    /// that block. On success, the block must only be freed with `self`.
+    unsafe fn adopt(
+        &self,
+        ptr: NonNull<u8>,
+        layout: Layout,
+        from: &A,
+    ) -> Result<NonNull<[u8]>, AllocError>;
+}
+
+unsafe impl AdoptAlloc<Global> for Global {
+    #[inline]
+    unsafe fn adopt(
+        &self,
+        ptr: NonNull<u8>,
+        layout: Layout,
+        _from: &Self,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        Ok(NonNull::new_unchecked(ptr::slice_from_raw_parts_mut(
+            ptr.as_ptr(),
+            layout.size(),
+        )))
+    }
+}
+
+/// A reference adopts memory from another reference if both point to the same allocator.
+unsafe impl<A: AllocRef> AdoptAlloc<&A> for &A {
+    #[inline]
+    unsafe fn adopt(
+        &self,
+        ptr: NonNull<u8>,
+        layout: Layout,
+        from: &&A,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        if ptr::eq(*self, *from) {
+            Ok(NonNull::new_unchecked(ptr::slice_from_raw_parts_mut(
+                ptr.as_ptr(),
+                layout.size(),
+            )))
+        } else {
+            Err(AllocError)
+        }
+    }
+}
+
+/// Calls [`AdoptAlloc::adopt`] if it is implemented and fails otherwise.
+pub(crate) trait SpecAdopt<A: AllocRef>: AllocRef {
+    unsafe fn spec_adopt(
+        &self,
+        ptr: NonNull<u8>,
+        layout: Layout,
+        from: &A,
+    ) -> Result<NonNull<[u8]>, AllocError>;
+}
+
+impl<A: AllocRef, B: AllocRef> SpecAdopt<A> for B {
+    #[inline]
+    default unsafe fn spec_adopt(
+        &self,
+        _ptr: NonNull<u8>,
+        _layout: Layout,
+        _from: &A,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        Err(AllocError)
+    }
+}
+
+impl<A: AllocRef, B: AdoptAlloc<A>> SpecAdopt<A> for B {
+    #[inline]
+    unsafe fn spec_adopt(
+        &self,
+        ptr: NonNull<u8>,
+        layout: Layout,
+        from: &A,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        self.adopt(ptr, layout, from)
+    }
+}
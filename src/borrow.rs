@@ -0,0 +1,414 @@
+//! A module for working with borrowed data.
+//!
+//! In addition to the traits of `liballoc`, this module provides [`ToOwnedIn`] and an
+//! allocator-aware [`Cow`], whose owned variant is allocated in `A`.
+
+pub use liballoc::borrow::{Borrow, BorrowMut, ToOwned};
+
+use crate::{
+    alloc::{AllocRef, Global},
+    collections::TryReserveError,
+    handle_reserve_error,
+    string::String,
+    vec::Vec,
+};
+use core::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    ops::Deref,
+};
+
+/// A generalization of `Clone` to borrowed data, which creates the owned data in the
+/// allocator `A`.
+///
+/// This is the allocator-aware counterpart of [`ToOwned`].
+pub trait ToOwnedIn<A: AllocRef> {
+    /// The resulting type after obtaining ownership.
+    type Owned: Borrow<Self>;
+
+    /// Creates owned data from borrowed data in the allocator `a`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the allocation fails.
+    fn to_owned_in(&self, a: A) -> Self::Owned {
+        handle_reserve_error(self.try_to_owned_in(a))
+    }
+
+    /// Same as `to_owned_in` but returns errors instead of panicking.
+    fn try_to_owned_in(&self, a: A) -> Result<Self::Owned, TryReserveError>;
+}
+
+impl<A: AllocRef> ToOwnedIn<A> for str {
+    type Owned = String<A>;
+
+    #[inline]
+    fn try_to_owned_in(&self, a: A) -> Result<String<A>, TryReserveError> {
+        String::try_from_str_in(self, a)
+    }
+}
+
+impl<T: Clone, A: AllocRef> ToOwnedIn<A> for [T] {
+    type Owned = Vec<T, A>;
+
+    fn try_to_owned_in(&self, a: A) -> Result<Vec<T, A>, TryReserveError> {
+        let mut vec = Vec::try_with_capacity_in(self.len(), a)?;
+        vec.try_extend_from_slice(self)?;
+        Ok(vec)
+    }
+}
+
+/// A clone-on-write smart pointer, whose owned data is allocated in `A`.
+///
+/// Unlike `liballoc`'s `Cow`, a borrowed `Cow` does not know which allocator to use, so the
+/// methods which have to allocate take an allocator, or require `A: Default`.
+///
+/// # Examples
+///
+/// ```
+/// use alloc_wg::{alloc::Global, borrow::Cow};
+///
+/// let mut cow: Cow<'_, [i32], Global> = Cow::Borrowed(&[1, 2, 3]);
+/// assert!(cow.is_borrowed());
+/// cow.to_mut().push(4);
+/// assert!(cow.is_owned());
+/// assert_eq!(&*cow, &[1, 2, 3, 4]);
+/// ```
+pub enum Cow<'a, B, A = Global>
+where
+    B: 'a + ToOwnedIn<A> + ?Sized,
+    A: AllocRef,
+{
+    /// Borrowed data.
+    Borrowed(&'a B),
+
+    /// Owned data.
+    Owned(<B as ToOwnedIn<A>>::Owned),
+}
+
+impl<B, A> Cow<'_, B, A>
+where
+    B: ToOwnedIn<A> + ?Sized,
+    A: AllocRef,
+{
+    /// Returns true if the data is borrowed.
+    #[inline]
+    pub fn is_borrowed(&self) -> bool {
+        match self {
+            Self::Borrowed(_) => true,
+            Self::Owned(_) => false,
+        }
+    }
+
+    /// Returns true if the data is owned.
+    #[inline]
+    pub fn is_owned(&self) -> bool {
+        !self.is_borrowed()
+    }
+
+    /// Acquires a mutable reference to the owned form of the data, cloning borrowed data into
+    /// `a`. The allocator is dropped if the data is already owned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the allocation fails.
+    pub fn to_mut_in(&mut self, a: A) -> &mut <B as ToOwnedIn<A>>::Owned {
+        handle_reserve_error(self.try_to_mut_in(a))
+    }
+
+    /// Same as `to_mut_in` but returns errors instead of panicking.
+    pub fn try_to_mut_in(
+        &mut self,
+        a: A,
+    ) -> Result<&mut <B as ToOwnedIn<A>>::Owned, TryReserveError> {
+        if let Self::Borrowed(borrowed) = *self {
+            *self = Self::Owned(borrowed.try_to_owned_in(a)?);
+        }
+        match self {
+            Self::Borrowed(_) => unreachable!(),
+            Self::Owned(owned) => Ok(owned),
+        }
+    }
+
+    /// Extracts the owned data, cloning borrowed data into `a`. The allocator is dropped if
+    /// the data is already owned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the allocation fails.
+    pub fn into_owned_in(self, a: A) -> <B as ToOwnedIn<A>>::Owned {
+        handle_reserve_error(self.try_into_owned_in(a))
+    }
+
+    /// Same as `into_owned_in` but returns errors instead of panicking.
+    pub fn try_into_owned_in(self, a: A) -> Result<<B as ToOwnedIn<A>>::Owned, TryReserveError> {
+        match self {
+            Self::Borrowed(borrowed) => borrowed.try_to_owned_in(a),
+            Self::Owned(owned) => Ok(owned),
+        }
+    }
+}
+
+impl<B, A> Cow<'_, B, A>
+where
+    B: ToOwnedIn<A> + ?Sized,
+    A: AllocRef + Default,
+{
+    /// Acquires a mutable reference to the owned form of the data, cloning borrowed data into
+    /// `A::default()`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the allocation fails.
+    #[inline]
+    pub fn to_mut(&mut self) -> &mut <B as ToOwnedIn<A>>::Owned {
+        handle_reserve_error(self.try_to_mut())
+    }
+
+    /// Same as `to_mut` but returns errors instead of panicking.
+    pub fn try_to_mut(&mut self) -> Result<&mut <B as ToOwnedIn<A>>::Owned, TryReserveError> {
+        if self.is_borrowed() {
+            self.try_to_mut_in(A::default())
+        } else {
+            match self {
+                Self::Borrowed(_) => unreachable!(),
+                Self::Owned(owned) => Ok(owned),
+            }
+        }
+    }
+
+    /// Extracts the owned data, cloning borrowed data into `A::default()`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the allocation fails.
+    #[inline]
+    pub fn into_owned(self) -> <B as ToOwnedIn<A>>::Owned {
+        handle_reserve_error(self.try_into_owned())
+    }
+
+    /// Same as `into_owned` but returns errors instead of panicking.
+    pub fn try_into_owned(self) -> Result<<B as ToOwnedIn<A>>::Owned, TryReserveError> {
+        match self {
+            Self::Borrowed(borrowed) => borrowed.try_to_owned_in(A::default()),
+            Self::Owned(owned) => Ok(owned),
+        }
+    }
+}
+
+impl<B, A> Deref for Cow<'_, B, A>
+where
+    B: ToOwnedIn<A> + ?Sized,
+    A: AllocRef,
+{
+    type Target = B;
+
+    fn deref(&self) -> &B {
+        match self {
+            Self::Borrowed(borrowed) => borrowed,
+            Self::Owned(owned) => owned.borrow(),
+        }
+    }
+}
+
+impl<B, A> AsRef<B> for Cow<'_, B, A>
+where
+    B: ToOwnedIn<A> + ?Sized,
+    A: AllocRef,
+{
+    fn as_ref(&self) -> &B {
+        self
+    }
+}
+
+impl<'a, B, A> Borrow<B> for Cow<'a, B, A>
+where
+    B: ToOwnedIn<A> + ?Sized,
+    A: AllocRef,
+{
+    fn borrow(&self) -> &B {
+        self
+    }
+}
+
+impl<B, A> Clone for Cow<'_, B, A>
+where
+    B: ToOwnedIn<A> + ?Sized,
+    B::Owned: Clone,
+    A: AllocRef,
+{
+    fn clone(&self) -> Self {
+        match self {
+            Self::Borrowed(borrowed) => Self::Borrowed(borrowed),
+            Self::Owned(owned) => Self::Owned(owned.clone()),
+        }
+    }
+}
+
+impl<B, A> fmt::Debug for Cow<'_, B, A>
+where
+    B: fmt::Debug + ToOwnedIn<A> + ?Sized,
+    B::Owned: fmt::Debug,
+    A: AllocRef,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Borrowed(borrowed) => fmt::Debug::fmt(borrowed, f),
+            Self::Owned(owned) => fmt::Debug::fmt(owned, f),
+        }
+    }
+}
+
+impl<B, A> fmt::Display for Cow<'_, B, A>
+where
+    B: fmt::Display + ToOwnedIn<A> + ?Sized,
+    B::Owned: fmt::Display,
+    A: AllocRef,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Borrowed(borrowed) => fmt::Display::fmt(borrowed, f),
+            Self::Owned(owned) => fmt::Display::fmt(owned, f),
+        }
+    }
+}
+
+impl<'a, 'b, B, C, A1, A2> PartialEq<Cow<'b, C, A2>> for Cow<'a, B, A1>
+where
+    B: PartialEq<C> + ToOwnedIn<A1> + ?Sized,
+    C: ToOwnedIn<A2> + ?Sized,
+    A1: AllocRef,
+    A2: AllocRef,
+{
+    #[inline]
+    fn eq(&self, other: &Cow<'b, C, A2>) -> bool {
+        PartialEq::eq(&**self, &**other)
+    }
+}
+
+impl<B, A> Eq for Cow<'_, B, A>
+where
+    B: Eq + ToOwnedIn<A> + ?Sized,
+    A: AllocRef,
+{
+}
+
+impl<'a, B, A> PartialOrd for Cow<'a, B, A>
+where
+    B: PartialOrd + ToOwnedIn<A> + ?Sized,
+    A: AllocRef,
+{
+    #[inline]
+    fn partial_cmp(&self, other: &Cow<'a, B, A>) -> Option<Ordering> {
+        PartialOrd::partial_cmp(&**self, &**other)
+    }
+}
+
+impl<B, A> Ord for Cow<'_, B, A>
+where
+    B: Ord + ToOwnedIn<A> + ?Sized,
+    A: AllocRef,
+{
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        Ord::cmp(&**self, &**other)
+    }
+}
+
+impl<B, A> Hash for Cow<'_, B, A>
+where
+    B: Hash + ToOwnedIn<A> + ?Sized,
+    A: AllocRef,
+{
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Hash::hash(&**self, state)
+    }
+}
+
+impl<'a, A: AllocRef> PartialEq<str> for Cow<'a, str, A> {
+    #[inline]
+    fn eq(&self, other: &str) -> bool {
+        &**self == other
+    }
+}
+
+impl<'a, 'b, A: AllocRef> PartialEq<&'b str> for Cow<'a, str, A> {
+    #[inline]
+    fn eq(&self, other: &&'b str) -> bool {
+        &**self == *other
+    }
+}
+
+impl<'a, T: Clone + PartialEq<U>, U, A: AllocRef> PartialEq<[U]> for Cow<'a, [T], A> {
+    #[inline]
+    fn eq(&self, other: &[U]) -> bool {
+        &**self == other
+    }
+}
+
+impl<'a, 'b, T: Clone + PartialEq<U>, U, A: AllocRef> PartialEq<&'b [U]> for Cow<'a, [T], A> {
+    #[inline]
+    fn eq(&self, other: &&'b [U]) -> bool {
+        &**self == *other
+    }
+}
+
+impl<'a, A: AllocRef> From<&'a str> for Cow<'a, str, A> {
+    #[inline]
+    fn from(s: &'a str) -> Self {
+        Cow::Borrowed(s)
+    }
+}
+
+impl<'a, A: AllocRef> From<String<A>> for Cow<'a, str, A> {
+    #[inline]
+    fn from(s: String<A>) -> Self {
+        Cow::Owned(s)
+    }
+}
+
+impl<'a, A: AllocRef> From<&'a String<A>> for Cow<'a, str, A> {
+    #[inline]
+    fn from(s: &'a String<A>) -> Self {
+        Cow::Borrowed(s.as_str())
+    }
+}
+
+impl<'a, T: Clone, A: AllocRef> From<&'a [T]> for Cow<'a, [T], A> {
+    #[inline]
+    fn from(s: &'a [T]) -> Self {
+        Cow::Borrowed(s)
+    }
+}
+
+impl<'a, T: Clone, A: AllocRef> From<Vec<T, A>> for Cow<'a, [T], A> {
+    #[inline]
+    fn from(v: Vec<T, A>) -> Self {
+        Cow::Owned(v)
+    }
+}
+
+impl<'a, T: Clone, A: AllocRef> From<&'a Vec<T, A>> for Cow<'a, [T], A> {
+    #[inline]
+    fn from(v: &'a Vec<T, A>) -> Self {
+        Cow::Borrowed(v.as_slice())
+    }
+}
+
+impl<'a, A: AllocRef + Default> From<Cow<'a, str, A>> for String<A> {
+    /// Converts the `Cow` into an owned `String`, copying borrowed data into `A::default()`.
+    #[inline]
+    fn from(s: Cow<'a, str, A>) -> Self {
+        s.into_owned()
+    }
+}
+
+impl<'a, T: Clone, A: AllocRef + Default> From<Cow<'a, [T], A>> for Vec<T, A> {
+    /// Converts the `Cow` into an owned `Vec`, cloning borrowed data into `A::default()`.
+    #[inline]
+    fn from(s: Cow<'a, [T], A>) -> Self {
+        s.into_owned()
+    }
+}
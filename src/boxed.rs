@@ -79,20 +79,24 @@
 //! [`NonZeroLayout::for_value(&*value)`]: crate::alloc::NonZeroLayout::for_value
 
 use crate::{
-    alloc::{handle_alloc_error, AllocError, AllocRef, Global, Layout},
-    clone::CloneIn,
-    collections::TryReserveError,
+    alloc::{handle_alloc_error, AllocError, AllocRef, Global, Layout, SpecAdopt},
+    clone::{CloneIn, TryClone},
+    collections::{TryPushError, TryReserveError},
     handle_reserve_error,
+    iter::{FromIteratorIn, TryExtend},
     raw_vec::RawVec,
+    string::String,
+    vec::Vec,
 };
 use core::{
     any::Any,
     borrow,
     cmp::Ordering,
+    convert::TryFrom,
     fmt,
     future::Future,
     hash::{Hash, Hasher},
-    iter::FusedIterator,
+    iter::{FromIterator, FusedIterator},
     mem,
     mem::MaybeUninit,
     ops::{Deref, DerefMut},
@@ -100,6 +104,8 @@ use core::{
     ptr::{self, NonNull, Unique},
     task::{Context, Poll},
 };
+#[cfg(feature = "std")]
+use std::error::Error;
 
 /// A pointer type for heap allocation.
 ///
@@ -155,6 +161,30 @@ impl<T> Box<T> {
         Self::new_uninit_in(Global)
     }
 
+    /// Constructs a new box with uninitialized contents, with the memory being filled with `0`
+    /// bytes.
+    ///
+    /// See [`MaybeUninit::zeroed`] for examples of correct and incorrect usage of this method.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use alloc_wg::boxed::Box;
+    ///
+    /// let zero = Box::<u32>::new_zeroed();
+    /// let zero = unsafe { zero.assume_init() };
+    ///
+    /// assert_eq!(*zero, 0)
+    /// ```
+    ///
+    /// [`MaybeUninit::zeroed`]: core::mem::MaybeUninit::zeroed
+    #[allow(clippy::inline_always)]
+    #[inline(always)]
+    #[must_use]
+    pub fn new_zeroed() -> Box<MaybeUninit<T>> {
+        Self::new_zeroed_in(Global)
+    }
+
     /// Constructs a new `Pin<Box<T>>`. If `T` does not implement `Unpin`, then
     /// `x` will be pinned in memory and unable to be moved.
     #[allow(clippy::inline_always)]
@@ -200,10 +230,20 @@ impl<T, A: AllocRef> Box<T, A> {
     ///
     /// # #[allow(unused_variables)]
     /// let five = Box::try_new_in(5, Global)?;
-    /// # Ok::<_, alloc_wg::alloc::AllocError>(())
+    /// # Ok::<_, alloc_wg::collections::TryPushError<i32>>(())
     /// ```
-    pub fn try_new_in(x: T, alloc: A) -> Result<Self, AllocError> {
-        let mut boxed = Self::try_new_uninit_in(alloc)?;
+    ///
+    /// On failure, `x` is handed back inside the [`TryPushError`].
+    ///
+    /// [`TryPushError`]: crate::collections::TryPushError
+    pub fn try_new_in(x: T, alloc: A) -> Result<Self, TryPushError<T>> {
+        let mut boxed = match Self::try_new_uninit_in(alloc) {
+            Ok(boxed) => boxed,
+            Err(err) => {
+                let error = TryReserveError::alloc_error(Layout::new::<T>()).with_cause(err);
+                return Err(TryPushError::new(x, error));
+            }
+        };
         unsafe {
             boxed.ptr.as_mut().write(x);
             Ok(boxed.assume_init())
@@ -264,6 +304,54 @@ impl<T, A: AllocRef> Box<T, A> {
         unsafe { Ok(Box::from_raw_in(ptr.cast(), alloc)) }
     }
 
+    /// Constructs a new box with uninitialized contents in a specified allocator, with the memory
+    /// being filled with `0` bytes.
+    ///
+    /// See [`MaybeUninit::zeroed`] for examples of correct and incorrect usage of this method.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use alloc_wg::{alloc::Global, boxed::Box};
+    ///
+    /// let zero = Box::<u32, _>::new_zeroed_in(Global);
+    /// let zero = unsafe { zero.assume_init() };
+    ///
+    /// assert_eq!(*zero, 0)
+    /// ```
+    ///
+    /// [`MaybeUninit::zeroed`]: core::mem::MaybeUninit::zeroed
+    #[allow(clippy::inline_always)]
+    #[inline(always)]
+    pub fn new_zeroed_in(a: A) -> Box<MaybeUninit<T>, A> {
+        handle_reserve_error(Self::try_new_zeroed_in(a))
+    }
+
+    /// Tries to construct a new box with uninitialized contents in a specified allocator, with
+    /// the memory being filled with `0` bytes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use alloc_wg::{alloc::Global, boxed::Box};
+    ///
+    /// let zero = Box::<u32, Global>::try_new_zeroed_in(Global)?;
+    /// let zero = unsafe { zero.assume_init() };
+    ///
+    /// assert_eq!(*zero, 0);
+    /// # Ok::<_, alloc_wg::collections::TryReserveError>(())
+    /// ```
+    pub fn try_new_zeroed_in(alloc: A) -> Result<Box<MaybeUninit<T>, A>, TryReserveError> {
+        let layout = Layout::new::<MaybeUninit<T>>();
+        let memory = alloc
+            .alloc_zeroed(layout)
+            .map_err(|err| TryReserveError::alloc_error(layout).with_cause(err))?;
+        let ptr = memory.as_mut_ptr();
+        unsafe { Ok(Box::from_raw_in(ptr.cast(), alloc)) }
+    }
+
     /// Constructs a new `Pin<Box<T, A>>` with the specified allocator. If `T` does not implement
     /// `Unpin`, then `x` will be pinned in memory and unable to be moved.
     #[allow(clippy::inline_always)]
@@ -275,9 +363,310 @@ impl<T, A: AllocRef> Box<T, A> {
     /// Constructs a new `Pin<Box<T, A>>` with the specified allocator. If `T` does not implement
     /// `Unpin`, then `x` will be pinned in memory and unable to be moved.
     #[inline]
-    pub fn try_pin_in(x: T, a: A) -> Result<Pin<Self>, AllocError> {
+    pub fn try_pin_in(x: T, a: A) -> Result<Pin<Self>, TryPushError<T>> {
         Self::try_new_in(x, a).map(Pin::from)
     }
+
+    /// Allocates memory with the given allocator and then places the value returned by `f` into
+    /// it.
+    ///
+    /// The memory is allocated before `f` is called, so `f` is never called if the allocation
+    /// fails. The value is still returned by `f`, so whether it is built on the stack first is
+    /// up to the optimizer. Use [`init_in`] to write a large value directly into the box.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use alloc_wg::{alloc::Global, boxed::Box};
+    ///
+    /// let b = Box::new_with_in(|| [1_u8; 4096], Global);
+    /// assert_eq!(b[4095], 1);
+    /// ```
+    ///
+    /// [`init_in`]: Self::init_in
+    #[inline]
+    pub fn new_with_in<F: FnOnce() -> T>(f: F, a: A) -> Self {
+        handle_reserve_error(Self::try_new_with_in(f, a))
+    }
+
+    /// Same as `new_with_in` but returns errors instead of aborting.
+    ///
+    /// `f` is not called if the allocation fails.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use alloc_wg::{alloc::Global, boxed::Box};
+    ///
+    /// let b = Box::try_new_with_in(|| [1_u8; 4096], Global)?;
+    /// assert_eq!(b[4095], 1);
+    /// # Ok::<_, alloc_wg::collections::TryReserveError>(())
+    /// ```
+    pub fn try_new_with_in<F: FnOnce() -> T>(f: F, a: A) -> Result<Self, TryReserveError> {
+        let mut boxed = Self::try_new_uninit_in(a)
+            .map_err(|err| TryReserveError::alloc_error(Layout::new::<T>()).with_cause(err))?;
+        unsafe {
+            boxed.as_mut_ptr().write(f());
+            Ok(boxed.assume_init())
+        }
+    }
+
+    /// Allocates memory with the given allocator and initializes it in place with `init`.
+    ///
+    /// `init` receives the uninitialized slot and has to return it as initialized reference,
+    /// e.g. by calling [`MaybeUninit::assume_init_mut`] after writing all fields. If `init` panics,
+    /// the memory is freed without dropping the slot.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `init` returns a reference to anything else than the slot.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// #![feature(allocator_api, maybe_uninit_ref)]
+    /// use alloc_wg::{alloc::Global, boxed::Box};
+    /// use core::ptr;
+    ///
+    /// let b = Box::<[u64; 1 << 16], _>::init_in(Global, |slot| unsafe {
+    ///     ptr::write_bytes(slot.as_mut_ptr(), 0, 1);
+    ///     slot.assume_init_mut()
+    /// });
+    /// assert!(b.iter().all(|&x| x == 0));
+    /// ```
+    ///
+    /// [`MaybeUninit::assume_init_mut`]: core::mem::MaybeUninit::assume_init_mut
+    #[inline]
+    pub fn init_in<F>(a: A, init: F) -> Self
+    where
+        F: for<'a> FnOnce(&'a mut MaybeUninit<T>) -> &'a mut T,
+    {
+        handle_reserve_error(Self::try_init_in(a, init))
+    }
+
+    /// Same as `init_in` but returns errors instead of aborting.
+    ///
+    /// `init` is not called if the allocation fails.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `init` returns a reference to anything else than the slot.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// #![feature(allocator_api, maybe_uninit_extra)]
+    /// use alloc_wg::{alloc::Global, boxed::Box};
+    ///
+    /// let b = Box::try_init_in(Global, |slot| slot.write(5_u32))?;
+    /// assert_eq!(*b, 5);
+    /// # Ok::<_, alloc_wg::collections::TryReserveError>(())
+    /// ```
+    pub fn try_init_in<F>(a: A, init: F) -> Result<Self, TryReserveError>
+    where
+        F: for<'a> FnOnce(&'a mut MaybeUninit<T>) -> &'a mut T,
+    {
+        let mut boxed = Self::try_new_uninit_in(a)
+            .map_err(|err| TryReserveError::alloc_error(Layout::new::<T>()).with_cause(err))?;
+        let slot = boxed.as_mut_ptr();
+        assert!(
+            ptr::eq(init(&mut *boxed), slot),
+            "`init` has to return the initialized slot"
+        );
+        unsafe { Ok(boxed.assume_init()) }
+    }
+
+    /// Constructs a new `Pin<Box<T, A>>` by initializing the value in place with `init`.
+    ///
+    /// `init` receives the pinned, uninitialized slot at the final address of the value, so the
+    /// value can refer to itself and is never moved after construction.
+    ///
+    /// # Safety
+    ///
+    /// `init` has to fully initialize the slot. If `init` panics, the memory is freed without
+    /// dropping the slot.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use alloc_wg::{alloc::Global, boxed::Box};
+    /// use core::{marker::PhantomPinned, mem::MaybeUninit, pin::Pin, ptr};
+    ///
+    /// struct SelfRef {
+    ///     value: u32,
+    ///     ptr: *const u32,
+    ///     _pin: PhantomPinned,
+    /// }
+    ///
+    /// let b = unsafe {
+    ///     Box::new_pinned_with(Global, |slot: Pin<&mut MaybeUninit<SelfRef>>| {
+    ///         let this = slot.get_unchecked_mut().as_mut_ptr();
+    ///         ptr::write(this, SelfRef {
+    ///             value: 5,
+    ///             ptr: ptr::null(),
+    ///             _pin: PhantomPinned,
+    ///         });
+    ///         (*this).ptr = &(*this).value;
+    ///     })
+    /// };
+    ///
+    /// assert_eq!(b.ptr, &b.value as *const u32);
+    /// ```
+    #[inline]
+    pub unsafe fn new_pinned_with<F>(alloc: A, init: F) -> Pin<Self>
+    where
+        F: FnOnce(Pin<&mut MaybeUninit<T>>),
+    {
+        match Self::try_new_pinned_with(alloc, init) {
+            Ok(b) => b,
+            Err(_) => handle_alloc_error(Layout::new::<T>()),
+        }
+    }
+
+    /// Same as `new_pinned_with` but returns errors instead of aborting.
+    ///
+    /// `init` is not called if the allocation fails.
+    ///
+    /// # Safety
+    ///
+    /// See [`new_pinned_with`](Box::new_pinned_with).
+    pub unsafe fn try_new_pinned_with<F>(alloc: A, init: F) -> Result<Pin<Self>, TryReserveError>
+    where
+        F: FnOnce(Pin<&mut MaybeUninit<T>>),
+    {
+        let mut boxed = Self::try_new_uninit_in(alloc)
+            .map_err(|err| TryReserveError::alloc_error(Layout::new::<T>()).with_cause(err))?;
+        init(Pin::new_unchecked(&mut *boxed));
+        Ok(Box::into_pin(boxed.assume_init()))
+    }
+
+    /// Consumes the box and returns the boxed value.
+    ///
+    /// Note: this is an associated function, which means that you have to call it as
+    /// `Box::into_inner(b)` instead of `b.into_inner()`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use alloc_wg::{boxed::Box, string::String};
+    ///
+    /// let b = Box::new(String::from("hello"));
+    /// assert_eq!(Box::into_inner(b), "hello");
+    /// ```
+    #[inline]
+    pub fn into_inner(b: Self) -> T {
+        Self::into_inner_alloc(b).0
+    }
+
+    /// Consumes the box and returns the boxed value together with the allocator.
+    ///
+    /// Note: this is an associated function, which means that you have to call it as
+    /// `Box::into_inner_alloc(b)` instead of `b.into_inner_alloc()`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use alloc_wg::{alloc::Global, boxed::Box};
+    ///
+    /// let b = Box::new_in(5, Global);
+    /// let (five, alloc) = Box::into_inner_alloc(b);
+    /// assert_eq!(five, 5);
+    /// # let _ = Box::new_in(five, alloc);
+    /// ```
+    pub fn into_inner_alloc(b: Self) -> (T, A) {
+        let (ptr, alloc) = Self::into_raw_non_null_alloc(b);
+        let guard = DeallocOnDrop {
+            ptr: ptr.cast(),
+            layout: Layout::new::<T>(),
+            alloc,
+        };
+        let value = unsafe { ptr::read(ptr.as_ptr()) };
+        (value, guard.dealloc())
+    }
+
+    /// Maps the boxed value with `f` and stores the result in the same allocation if possible.
+    ///
+    /// Note: this is an associated function, which means that you have to call it as
+    /// `Box::map_in_place(b, f)` instead of `b.map_in_place(f)`.
+    ///
+    /// If `T` and `U` have the same layout, the allocation is reused as is. If they only share
+    /// the alignment, the allocation is resized with `A`, otherwise a new allocation is made
+    /// and the old one is freed. The allocation is freed as well if `f` panics.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use alloc_wg::boxed::Box;
+    ///
+    /// let b = Box::new(5_u32);
+    /// let ptr = &*b as *const u32 as *const u8;
+    ///
+    /// let b = Box::map_in_place(b, |x| x as f32 * 1.5);
+    /// assert_eq!(*b, 7.5);
+    /// assert_eq!(&*b as *const f32 as *const u8, ptr);
+    /// ```
+    pub fn map_in_place<U, F: FnOnce(T) -> U>(b: Self, f: F) -> Box<U, A> {
+        match Self::try_map_in_place(b, f) {
+            Ok(b) => b,
+            Err(_) => handle_alloc_error(Layout::new::<U>()),
+        }
+    }
+
+    /// Same as `map_in_place` but returns errors instead of aborting.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the allocation cannot be resized or a new allocation fails. The mapped
+    /// value is handed back inside the [`TryPushError`], the old allocation is freed.
+    ///
+    /// [`TryPushError`]: crate::collections::TryPushError
+    pub fn try_map_in_place<U, F: FnOnce(T) -> U>(
+        b: Self,
+        f: F,
+    ) -> Result<Box<U, A>, TryPushError<U>> {
+        let old_layout = Layout::new::<T>();
+        let new_layout = Layout::new::<U>();
+        let (ptr, alloc) = Self::into_raw_non_null_alloc(b);
+        let guard = DeallocOnDrop {
+            ptr: ptr.cast(),
+            layout: old_layout,
+            alloc,
+        };
+        let value = f(unsafe { ptr::read(ptr.as_ptr()) });
+
+        let in_place = new_layout.align() == old_layout.align();
+        let result = if new_layout == old_layout {
+            Ok(guard.ptr)
+        } else if in_place {
+            unsafe {
+                if new_layout.size() > old_layout.size() {
+                    guard.alloc.grow(guard.ptr, old_layout, new_layout)
+                } else {
+                    guard.alloc.shrink(guard.ptr, old_layout, new_layout)
+                }
+            }
+            .map(NonNull::as_non_null_ptr)
+        } else {
+            guard.alloc.alloc(new_layout).map(NonNull::as_non_null_ptr)
+        };
+        let (memory, alloc) = match result {
+            Ok(memory) if in_place => (memory, guard.into_alloc()),
+            Ok(memory) => (memory, guard.dealloc()),
+            Err(err) => {
+                let error = TryReserveError::alloc_error(new_layout).with_cause(err);
+                return Err(TryPushError::new(value, error));
+            }
+        };
+        unsafe {
+            let ptr = memory.cast::<U>().as_ptr();
+            ptr.write(value);
+            Ok(Box::from_raw_in(ptr, alloc))
+        }
+    }
 }
 
 #[allow(clippy::use_self)]
@@ -308,6 +697,30 @@ impl<T> Box<[T]> {
     pub fn new_uninit_slice(len: usize) -> Box<[MaybeUninit<T>]> {
         Self::new_uninit_slice_in(len, Global)
     }
+
+    /// Construct a new boxed slice with uninitialized contents, with the memory being filled with
+    /// `0` bytes.
+    ///
+    /// See [`MaybeUninit::zeroed`] for examples of correct and incorrect usage of this method.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use alloc_wg::boxed::Box;
+    ///
+    /// let values = Box::<[u32]>::new_zeroed_slice(3);
+    /// let values = unsafe { values.assume_init() };
+    ///
+    /// assert_eq!(*values, [0, 0, 0])
+    /// ```
+    ///
+    /// [`MaybeUninit::zeroed`]: core::mem::MaybeUninit::zeroed
+    #[allow(clippy::inline_always)]
+    #[inline(always)]
+    #[must_use]
+    pub fn new_zeroed_slice(len: usize) -> Box<[MaybeUninit<T>]> {
+        Self::new_zeroed_slice_in(len, Global)
+    }
 }
 
 #[allow(clippy::use_self)]
@@ -366,7 +779,53 @@ impl<T, A: AllocRef> Box<[T], A> {
         len: usize,
         a: A,
     ) -> Result<Box<[MaybeUninit<T>], A>, TryReserveError> {
-        unsafe { Ok(RawVec::try_with_capacity_in(len, a)?.into_box(len)) }
+        unsafe { Ok(RawVec::<T, A>::try_with_capacity_in(len, a)?.into_box(len)) }
+    }
+
+    /// Construct a new boxed slice with uninitialized contents with the specified allocator, with
+    /// the memory being filled with `0` bytes.
+    ///
+    /// See [`MaybeUninit::zeroed`] for examples of correct and incorrect usage of this method.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use alloc_wg::{alloc::Global, boxed::Box};
+    ///
+    /// let values = Box::<[u32], _>::new_zeroed_slice_in(3, Global);
+    /// let values = unsafe { values.assume_init() };
+    ///
+    /// assert_eq!(*values, [0, 0, 0])
+    /// ```
+    ///
+    /// [`MaybeUninit::zeroed`]: core::mem::MaybeUninit::zeroed
+    #[allow(clippy::inline_always)]
+    #[inline(always)]
+    pub fn new_zeroed_slice_in(len: usize, a: A) -> Box<[MaybeUninit<T>], A> {
+        handle_reserve_error(Self::try_new_zeroed_slice_in(len, a))
+    }
+
+    /// Tries to construct a new boxed slice with uninitialized contents with the specified
+    /// allocator, with the memory being filled with `0` bytes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use alloc_wg::{alloc::Global, boxed::Box};
+    ///
+    /// let values = Box::<[u32], Global>::try_new_zeroed_slice_in(3, Global)?;
+    /// let values = unsafe { values.assume_init() };
+    ///
+    /// assert_eq!(*values, [0, 0, 0]);
+    /// # Ok::<_, alloc_wg::collections::TryReserveError>(())
+    /// ```
+    pub fn try_new_zeroed_slice_in(
+        len: usize,
+        a: A,
+    ) -> Result<Box<[MaybeUninit<T>], A>, TryReserveError> {
+        unsafe { Ok(RawVec::<T, A>::try_with_capacity_zeroed_in(len, a)?.into_box(len)) }
     }
 }
 
@@ -706,6 +1165,133 @@ impl<T: ?Sized, A: AllocRef> Box<T, A> {
         // additional requirements.
         unsafe { Pin::new_unchecked(boxed) }
     }
+
+    /// Moves the boxed value into the allocator `alloc` without cloning it.
+    ///
+    /// Note: this is an associated function, which means that you have to call it as
+    /// `Box::transfer_in(b, alloc)` instead of `b.transfer_in(alloc)`.
+    ///
+    /// If `alloc` can adopt memory from `A` (see [`AdoptAlloc`]), the allocation is handed over
+    /// as is. Otherwise, the value is moved bitwise into a new allocation and the old one is
+    /// freed with `A`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the new allocation fails. The box is handed back unchanged together
+    /// with the error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use alloc_wg::{alloc::Global, boxed::Box};
+    ///
+    /// let b: Box<[u8]> = Box::from(&[1, 2, 3][..]);
+    /// assert_eq!(&*Box::transfer_in(b, Global)?, &[1, 2, 3]);
+    /// # Ok::<(), alloc_wg::collections::TryReserveError>(())
+    /// ```
+    ///
+    /// [`AdoptAlloc`]: crate::alloc::AdoptAlloc
+    pub fn transfer_in<B: AllocRef>(b: Self, alloc: B) -> Result<Box<T, B>, TryPushError<Self>> {
+        let layout = Layout::for_value::<T>(&*b);
+        let (raw, old) = Self::into_raw_alloc(b);
+        unsafe {
+            let ptr = NonNull::new_unchecked(raw).cast::<u8>();
+            let memory = match alloc.spec_adopt(ptr, layout, &old) {
+                Ok(memory) => memory,
+                Err(_) => match alloc.alloc(layout) {
+                    Ok(memory) => {
+                        ptr::copy_nonoverlapping(ptr.as_ptr(), memory.as_mut_ptr(), layout.size());
+                        old.dealloc(ptr, layout);
+                        memory
+                    }
+                    Err(err) => {
+                        let err = TryReserveError::alloc_error(layout).with_cause(err);
+                        return Err(TryPushError::new(Self::from_raw_in(raw, old), err));
+                    }
+                },
+            };
+            Ok(Box::from_raw_in(
+                raw.set_ptr_value(memory.as_mut_ptr()),
+                alloc,
+            ))
+        }
+    }
+
+    /// Calls `f` with a [`BoxedDyn`] owning the boxed value and frees the allocation afterwards.
+    ///
+    /// Note: this is an associated function, which means that you have to call it as
+    /// `Box::dispatch(b, f)` instead of `b.dispatch(f)`.
+    ///
+    /// Methods taking `self: Box<Self, A>` can only be called on trait objects if `A` is a
+    /// zero-sized allocator. `BoxedDyn` does not store the allocator, so `self: BoxedDyn<'_, Self>`
+    /// methods can be called on `Box<dyn Trait, A>` for any allocator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(allocator_api, arbitrary_self_types)]
+    ///
+    /// use alloc_wg::{
+    ///     alloc::Global,
+    ///     boxed::{Box, BoxedDyn},
+    ///     string::String,
+    /// };
+    ///
+    /// trait Greeting {
+    ///     fn into_string(self: BoxedDyn<'_, Self>) -> String;
+    /// }
+    ///
+    /// impl Greeting for String {
+    ///     fn into_string(self: BoxedDyn<'_, Self>) -> String {
+    ///         BoxedDyn::into_inner(self)
+    ///     }
+    /// }
+    ///
+    /// let b: Box<dyn Greeting, _> = Box::new_in(String::from("hello"), &Global);
+    /// assert_eq!(Box::dispatch(b, |g| g.into_string()), "hello");
+    /// ```
+    pub fn dispatch<R, F>(b: Self, f: F) -> R
+    where
+        F: for<'a> FnOnce(BoxedDyn<'a, T>) -> R,
+    {
+        let layout = Layout::for_value::<T>(&*b);
+        let (ptr, alloc) = Self::into_raw_non_null_alloc(b);
+        let _guard = DeallocOnDrop {
+            ptr: ptr.cast(),
+            layout,
+            alloc,
+        };
+        f(BoxedDyn {
+            ptr: unsafe { &mut *ptr.as_ptr() },
+        })
+    }
+}
+
+/// Frees the memory of a box whose value was moved out, unless it is reused.
+struct DeallocOnDrop<A: AllocRef> {
+    ptr: NonNull<u8>,
+    layout: Layout,
+    alloc: A,
+}
+
+impl<A: AllocRef> DeallocOnDrop<A> {
+    /// Returns the allocator without freeing the memory.
+    fn into_alloc(self) -> A {
+        let this = mem::ManuallyDrop::new(self);
+        unsafe { ptr::read(&this.alloc) }
+    }
+
+    /// Frees the memory and returns the allocator.
+    fn dealloc(self) -> A {
+        unsafe { self.alloc.dealloc(self.ptr, self.layout) };
+        self.into_alloc()
+    }
+}
+
+impl<A: AllocRef> Drop for DeallocOnDrop<A> {
+    fn drop(&mut self) {
+        unsafe { self.alloc.dealloc(self.ptr, self.layout) }
+    }
 }
 
 fn drop_box<T: ?Sized, A: AllocRef>(boxed: &mut Box<T, A>) {
@@ -810,6 +1396,24 @@ impl<T: Clone, A: AllocRef + Clone> Clone for Box<T, A> {
     }
 }
 
+impl<T: Clone, A: AllocRef + Clone> TryClone for Box<T, A> {
+    /// Same as `clone` but returns errors instead of aborting.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use alloc_wg::{boxed::Box, clone::TryClone};
+    ///
+    /// let x = Box::new(5);
+    /// assert_eq!(x.try_clone()?, x);
+    /// # Ok::<(), alloc_wg::collections::TryReserveError>(())
+    /// ```
+    #[inline]
+    fn try_clone(&self) -> Result<Self, TryReserveError> {
+        self.try_clone_in(self.alloc.clone())
+    }
+}
+
 #[allow(clippy::use_self)]
 impl<T: Clone, A: AllocRef, B: AllocRef> CloneIn<B> for Box<T, A> {
     type Cloned = Box<T, B>;
@@ -819,11 +1423,7 @@ impl<T: Clone, A: AllocRef, B: AllocRef> CloneIn<B> for Box<T, A> {
     }
 
     fn try_clone_in(&self, a: B) -> Result<Self::Cloned, TryReserveError> {
-        Box::try_new_in(self.as_ref().clone(), a).map_err(|_| {
-            TryReserveError::AllocError {
-                layout: Layout::new::<T>(),
-            }
-        })
+        Box::try_new_in(self.as_ref().clone(), a).map_err(TryPushError::into_error)
     }
 }
 
@@ -973,7 +1573,7 @@ where
     /// ```
     fn from(slice: &[T]) -> Self {
         let len = slice.len();
-        let buf = RawVec::with_capacity_in(len, A::default());
+        let buf = RawVec::<T, A>::with_capacity_in(len, A::default());
         unsafe {
             ptr::copy_nonoverlapping(slice.as_ptr(), buf.ptr(), len);
             buf.into_box(slice.len()).assume_init()
@@ -1031,12 +1631,40 @@ impl<A: AllocRef> From<Box<str, A>> for Box<[u8], A> {
 }
 
 #[allow(clippy::use_self)]
-impl<T, const N: usize> core::convert::TryFrom<Box<[T]>> for Box<[T; N]> {
-    type Error = Box<[T]>;
+impl<T, A: AllocRef, const N: usize> From<[T; N]> for Box<[T], A>
+where
+    A: Default,
+{
+    /// Converts an array into a boxed slice.
+    ///
+    /// This conversion moves the array to a new allocation. Use
+    /// `Box::try_new_in(array, alloc)` to handle allocation failures, the resulting
+    /// `Box<[T; N], A>` coerces to `Box<[T], A>`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use alloc_wg::boxed::Box;
+    ///
+    /// let boxed: Box<[u8]> = Box::from([4, 2]);
+    /// assert_eq!(&*boxed, [4, 2]);
+    /// ```
+    fn from(array: [T; N]) -> Self {
+        Box::new_in(array, A::default())
+    }
+}
+
+#[allow(clippy::use_self)]
+impl<T, A: AllocRef, const N: usize> TryFrom<Box<[T], A>> for Box<[T; N], A> {
+    type Error = Box<[T], A>;
 
-    fn try_from(boxed_slice: Box<[T]>) -> Result<Self, Self::Error> {
+    /// Converts a boxed slice into a boxed array in place, if the slice has length `N`.
+    ///
+    /// Otherwise, the slice is handed back as error.
+    fn try_from(boxed_slice: Box<[T], A>) -> Result<Self, Self::Error> {
         if boxed_slice.len() == N {
-            Ok(unsafe { Self::from_raw(Box::into_raw(boxed_slice) as *mut [T; N]) })
+            let (ptr, alloc) = Box::into_raw_alloc(boxed_slice);
+            Ok(unsafe { Self::from_raw_in(ptr as *mut [T; N], alloc) })
         } else {
             Err(boxed_slice)
         }
@@ -1107,6 +1735,128 @@ impl<A: AllocRef> Box<dyn Any + Send, A> {
     }
 }
 
+#[allow(clippy::use_self)]
+impl<A: AllocRef> Box<dyn Any + Send + Sync, A> {
+    #[inline]
+    /// Attempt to downcast the box to a concrete type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use alloc_wg::boxed::Box;
+    /// use std::any::Any;
+    ///
+    /// let boxed: Box<dyn Any + Send + Sync> = Box::new(5_u32);
+    /// let boxed = boxed.downcast::<i32>().unwrap_err();
+    /// assert_eq!(*boxed.downcast::<u32>().unwrap(), 5);
+    /// ```
+    pub fn downcast<T: Any>(self) -> Result<Box<T, A>, Box<dyn Any + Send + Sync, A>> {
+        if self.is::<T>() {
+            unsafe {
+                let (raw, b): (*mut (dyn Any + Send + Sync), _) = Self::into_raw_alloc(self);
+                Ok(Box::from_raw_in(raw as *mut T, b))
+            }
+        } else {
+            Err(self)
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a, E: Error + 'a, A: AllocRef> From<E> for Box<dyn Error + 'a, A>
+where
+    A: Default,
+{
+    /// Converts a type of [`Error`] into a box of dyn [`Error`].
+    ///
+    /// Use `Box::try_new_in(err, alloc)` to handle allocation failures, the resulting box
+    /// coerces to `Box<dyn Error, A>`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use alloc_wg::boxed::Box;
+    /// use std::{error::Error, fmt};
+    ///
+    /// #[derive(Debug)]
+    /// struct AnError;
+    ///
+    /// impl fmt::Display for AnError {
+    ///     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    ///         write!(f, "An error")
+    ///     }
+    /// }
+    ///
+    /// impl Error for AnError {}
+    ///
+    /// let a_boxed_error: Box<dyn Error> = Box::from(AnError);
+    /// assert_eq!(a_boxed_error.to_string(), "An error");
+    /// ```
+    fn from(err: E) -> Self {
+        Box::new_in(err, A::default())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a, E: Error + Send + Sync + 'a, A: AllocRef> From<E> for Box<dyn Error + Send + Sync + 'a, A>
+where
+    A: Default,
+{
+    /// Converts a type of [`Error`] + [`Send`] + [`Sync`] into a box of dyn [`Error`] +
+    /// [`Send`] + [`Sync`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use alloc_wg::boxed::Box;
+    /// use std::{error::Error, fmt};
+    ///
+    /// #[derive(Debug)]
+    /// struct AnError;
+    ///
+    /// impl fmt::Display for AnError {
+    ///     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    ///         write!(f, "An error")
+    ///     }
+    /// }
+    ///
+    /// impl Error for AnError {}
+    ///
+    /// let a_boxed_error: Box<dyn Error + Send + Sync> = Box::from(AnError);
+    /// assert_eq!(a_boxed_error.to_string(), "An error");
+    /// ```
+    fn from(err: E) -> Self {
+        Box::new_in(err, A::default())
+    }
+}
+
+macro_rules! impl_error_downcast {
+    ($($bounds:tt)*) => {
+        #[cfg(feature = "std")]
+        #[allow(clippy::use_self)]
+        impl<A: AllocRef> Box<dyn Error $($bounds)*, A> {
+            /// Attempts to downcast the box to a concrete type.
+            ///
+            /// On failure, the box is handed back unchanged.
+            pub fn downcast<T: Error + 'static>(self) -> Result<Box<T, A>, Self> {
+                if self.is::<T>() {
+                    unsafe {
+                        let (raw, alloc): (*mut (dyn Error $($bounds)*), _) =
+                            Self::into_raw_alloc(self);
+                        Ok(Box::from_raw_in(raw as *mut T, alloc))
+                    }
+                } else {
+                    Err(self)
+                }
+            }
+        }
+    };
+}
+
+impl_error_downcast!();
+impl_error_downcast!(+ Send);
+impl_error_downcast!(+ Send + Sync);
+
 impl<T: fmt::Display + ?Sized, A: AllocRef> fmt::Display for Box<T, A> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         fmt::Display::fmt(&**self, f)
@@ -1199,23 +1949,28 @@ impl<I: ExactSizeIterator + ?Sized, A: AllocRef> ExactSizeIterator for Box<I, A>
 
 impl<I: FusedIterator + ?Sized, A: AllocRef> FusedIterator for Box<I, A> {}
 
-impl<Args, F: FnOnce<Args> + Copy + ?Sized, A: AllocRef> FnOnce<Args> for Box<F, A> {
+impl<Args, F: FnOnce<Args> + ?Sized, A: AllocRef> FnOnce<Args> for Box<F, A> {
     type Output = <F as FnOnce<Args>>::Output;
 
     extern "rust-call" fn call_once(self, args: Args) -> Self::Output {
-        <F as FnOnce<Args>>::call_once(*self, args)
+        // Only the built-in box supports moving an unsized value out of its allocation. It takes
+        // over the allocation together with `A`, so the closure is moved out of `A`'s memory and
+        // the memory is freed through `A` afterwards.
+        let (ptr, alloc) = Self::into_raw_alloc(self);
+        let boxed = unsafe { liballoc::boxed::Box::from_raw_in(ptr, alloc) };
+        <F as FnOnce<Args>>::call_once(*boxed, args)
     }
 }
 
-impl<Args, F: FnMut<Args> + Copy + ?Sized, A: AllocRef> FnMut<Args> for Box<F, A> {
+impl<Args, F: FnMut<Args> + ?Sized, A: AllocRef> FnMut<Args> for Box<F, A> {
     extern "rust-call" fn call_mut(&mut self, args: Args) -> Self::Output {
-        <F as FnMut<Args>>::call_mut(self, args)
+        <F as FnMut<Args>>::call_mut(&mut **self, args)
     }
 }
 
-impl<Args, F: Fn<Args> + Copy + ?Sized, A: AllocRef> Fn<Args> for Box<F, A> {
+impl<Args, F: Fn<Args> + ?Sized, A: AllocRef> Fn<Args> for Box<F, A> {
     extern "rust-call" fn call(&self, args: Args) -> Self::Output {
-        <F as Fn<Args>>::call(self, args)
+        <F as Fn<Args>>::call(&**self, args)
     }
 }
 
@@ -1225,7 +1980,7 @@ impl<T: ?Sized + core::marker::Unsize<U>, U: ?Sized, A: AllocRef>
 }
 
 // DispatchFromDyn may only be implemented for ZSTs for now. Until this limitation is lifted,
-// implement it only for Global, and System
+// implement it only for Global, and System. Other allocators use `BoxedDyn` instead.
 macro_rules! impl_dispatch_from_dyn {
     ($alloc:ty) => {
         impl<T: ?Sized + core::marker::Unsize<U>, U: ?Sized>
@@ -1239,6 +1994,67 @@ impl_dispatch_from_dyn!(Global);
 #[cfg(feature = "std")]
 impl_dispatch_from_dyn!(std::alloc::System);
 
+/// An owning pointer to a value whose memory is managed by someone else.
+///
+/// Dropping a `BoxedDyn` drops the value but does not free its memory. It is created by
+/// [`Box::dispatch`], which frees the memory through the allocator of the box once the
+/// `BoxedDyn` is gone.
+///
+/// Unlike `Box<T, A>`, a `BoxedDyn` is a single pointer for every allocator, so it can be used as
+/// `self` receiver of trait objects (requires `#![feature(arbitrary_self_types)]`).
+pub struct BoxedDyn<'a, T: ?Sized> {
+    ptr: &'a mut T,
+}
+
+impl<T> BoxedDyn<'_, T> {
+    /// Moves the value out.
+    ///
+    /// Note: this is an associated function, which means that you have to call it as
+    /// `BoxedDyn::into_inner(b)` instead of `b.into_inner()`.
+    pub fn into_inner(b: Self) -> T {
+        let b = mem::ManuallyDrop::new(b);
+        unsafe { ptr::read(&**b) }
+    }
+}
+
+impl<T: ?Sized> Deref for BoxedDyn<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.ptr
+    }
+}
+
+impl<T: ?Sized> DerefMut for BoxedDyn<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.ptr
+    }
+}
+
+impl<T: ?Sized> Drop for BoxedDyn<'_, T> {
+    fn drop(&mut self) {
+        unsafe { ptr::drop_in_place(self.ptr) }
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for BoxedDyn<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<T: ?Sized> core::ops::Receiver for BoxedDyn<'_, T> {}
+
+impl<'a, T: ?Sized + core::marker::Unsize<U>, U: ?Sized> core::ops::CoerceUnsized<BoxedDyn<'a, U>>
+    for BoxedDyn<'a, T>
+{
+}
+
+impl<'a, T: ?Sized + core::marker::Unsize<U>, U: ?Sized> core::ops::DispatchFromDyn<BoxedDyn<'a, U>>
+    for BoxedDyn<'a, T>
+{
+}
+
 #[allow(clippy::items_after_statements)]
 impl<T: Clone, A: Clone> Clone for Box<[T], A>
 where
@@ -1294,6 +2110,150 @@ where
     }
 }
 
+impl<T: Clone, A: AllocRef + Clone> TryClone for Box<[T], A> {
+    #[inline]
+    fn try_clone(&self) -> Result<Self, TryReserveError> {
+        self.try_clone_in(self.alloc.clone())
+    }
+}
+
+impl<A: AllocRef + Clone> Clone for Box<str, A> {
+    /// Returns a new box with a copy of this box's string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use alloc_wg::boxed::Box;
+    ///
+    /// let x: Box<str> = Box::from("hello");
+    /// let y = x.clone();
+    ///
+    /// assert_eq!(x, y);
+    /// assert_ne!(x.as_ptr(), y.as_ptr());
+    /// ```
+    #[inline]
+    fn clone(&self) -> Self {
+        self.clone_in(self.alloc.clone())
+    }
+}
+
+impl<A: AllocRef + Clone> TryClone for Box<str, A> {
+    #[inline]
+    fn try_clone(&self) -> Result<Self, TryReserveError> {
+        self.try_clone_in(self.alloc.clone())
+    }
+}
+
+#[allow(clippy::use_self)]
+impl<T: Clone, A: AllocRef, B: AllocRef> CloneIn<B> for Box<[T], A> {
+    type Cloned = Box<[T], B>;
+
+    fn clone_in(&self, a: B) -> Self::Cloned {
+        handle_reserve_error(self.try_clone_in(a))
+    }
+
+    fn try_clone_in(&self, a: B) -> Result<Self::Cloned, TryReserveError> {
+        FromIteratorIn::try_from_iter_in(self.iter().cloned(), a)
+    }
+}
+
+#[allow(clippy::use_self)]
+impl<A: AllocRef, B: AllocRef> CloneIn<B> for Box<str, A> {
+    type Cloned = Box<str, B>;
+
+    fn clone_in(&self, a: B) -> Self::Cloned {
+        handle_reserve_error(self.try_clone_in(a))
+    }
+
+    fn try_clone_in(&self, a: B) -> Result<Self::Cloned, TryReserveError> {
+        let mut buf = String::try_with_capacity_in(self.len(), a)?;
+        buf.try_push_str(self)?;
+        buf.try_into_boxed_str()
+    }
+}
+
+impl<T, A: AllocRef> FromIteratorIn<T, A> for Box<[T], A> {
+    fn from_iter_in<I: IntoIterator<Item = T>>(iter: I, a: A) -> Self {
+        handle_reserve_error(Self::try_from_iter_in(iter, a))
+    }
+
+    fn try_from_iter_in<I: IntoIterator<Item = T>>(iter: I, a: A) -> Result<Self, TryReserveError> {
+        Vec::try_from_iter_in(iter, a)?.try_into_boxed_slice()
+    }
+}
+
+/// Collects anything a `String<A>` can be collected from.
+impl<T, A: AllocRef> FromIteratorIn<T, A> for Box<str, A>
+where
+    String<A>: FromIteratorIn<T, A>,
+{
+    fn from_iter_in<I: IntoIterator<Item = T>>(iter: I, a: A) -> Self {
+        handle_reserve_error(Self::try_from_iter_in(iter, a))
+    }
+
+    fn try_from_iter_in<I: IntoIterator<Item = T>>(iter: I, a: A) -> Result<Self, TryReserveError> {
+        String::try_from_iter_in(iter, a)?.try_into_boxed_str()
+    }
+}
+
+impl<T, A: AllocRef> FromIterator<T> for Box<[T], A>
+where
+    A: Default,
+{
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::from_iter_in(iter, A::default())
+    }
+}
+
+/// Collects anything a `String<A>` can be collected from.
+impl<T, A: AllocRef> FromIterator<T> for Box<str, A>
+where
+    A: Default,
+    String<A>: FromIteratorIn<T, A>,
+{
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::from_iter_in(iter, A::default())
+    }
+}
+
+impl<T, A: AllocRef> TryExtend<T> for Box<[T], A> {
+    type Err = TryReserveError;
+
+    /// Grows the allocation of the slice with its allocator to make room for the new elements.
+    ///
+    /// The new elements are collected first, so `self` is left unchanged if an error is
+    /// returned.
+    fn try_extend<I: IntoIterator<Item = T>>(&mut self, iter: I) -> Result<(), Self::Err> {
+        let mut extra = Vec::try_from_iter_in(iter, &self.alloc)?;
+        if extra.is_empty() {
+            return Ok(());
+        }
+        let old_len = self.len();
+        let len = old_len
+            .checked_add(extra.len())
+            .ok_or(TryReserveError::CapacityOverflow)?;
+        let old_layout = Layout::for_value::<[T]>(&**self);
+        let new_layout = Layout::array::<T>(len)?;
+
+        let dst = if new_layout.size() == 0 {
+            NonNull::<T>::dangling().as_ptr()
+        } else {
+            let ptr = self.ptr.cast().into();
+            let memory = unsafe { self.alloc.grow(ptr, old_layout, new_layout) }
+                .map_err(|err| TryReserveError::alloc_error(new_layout).with_cause(err))?;
+            memory.as_mut_ptr() as *mut T
+        };
+
+        // `grow` has moved the old elements if needed, so only the new ones are left to write.
+        unsafe {
+            ptr::copy_nonoverlapping(extra.as_ptr(), dst.add(old_len), extra.len());
+            extra.set_len(0);
+            self.ptr = Unique::new_unchecked(ptr::slice_from_raw_parts_mut(dst, len));
+        }
+        Ok(())
+    }
+}
+
 impl<T: ?Sized, A: AllocRef> borrow::Borrow<T> for Box<T, A> {
     fn borrow(&self) -> &T {
         &**self
@@ -18,7 +18,13 @@ use super::{
     unwrap_unchecked,
 };
 
-use crate::alloc::{AllocRef, Global};
+use crate::{
+    alloc::{AllocRef, Global},
+    clone::{CloneIn, TryClone},
+    collections::{TryPushError, TryReserveError},
+    handle_reserve_error,
+    iter::{FromIteratorIn, TryExtend},
+};
 
 use Entry::*;
 use UnderflowResult::*;
@@ -151,10 +157,49 @@ unsafe impl<#[may_dangle] K, #[may_dangle] V, A: AllocRef> Drop for BTreeMap<K,
 //#[stable(feature = "rust1", since = "1.0.0")]
 impl<K: Clone, V: Clone, A: Clone + AllocRef> Clone for BTreeMap<K, V, A> {
     fn clone(&self) -> BTreeMap<K, V, A> {
+        handle_reserve_error(self.try_clone())
+    }
+}
+
+impl<K: Clone, V: Clone, A: Clone + AllocRef> BTreeMap<K, V, A> {
+    /// Same as `clone` but returns errors instead of aborting.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use alloc_wg::collections::BTreeMap;
+    ///
+    /// let mut map = BTreeMap::new();
+    /// map.insert(1, "a");
+    /// let copy = map.try_clone()?;
+    /// assert_eq!(copy[&1], "a");
+    /// # Ok::<(), alloc_wg::collections::TryReserveError>(())
+    /// ```
+    pub fn try_clone(&self) -> Result<BTreeMap<K, V, A>, TryReserveError> {
+        self.try_clone_in(ManuallyDrop::into_inner(self.alloc.clone()))
+    }
+}
+
+impl<K: Clone, V: Clone, A: Clone + AllocRef> TryClone for BTreeMap<K, V, A> {
+    #[inline]
+    fn try_clone(&self) -> Result<Self, TryReserveError> {
+        BTreeMap::try_clone(self)
+    }
+}
+
+#[allow(clippy::use_self)]
+impl<K: Clone, V: Clone, A: AllocRef, B: Clone + AllocRef> CloneIn<B> for BTreeMap<K, V, A> {
+    type Cloned = BTreeMap<K, V, B>;
+
+    fn clone_in(&self, alloc: B) -> Self::Cloned {
+        handle_reserve_error(self.try_clone_in(alloc))
+    }
+
+    fn try_clone_in(&self, alloc: B) -> Result<Self::Cloned, TryReserveError> {
         fn clone_subtree<'a, K: Clone, V: Clone, A: Clone + AllocRef>(
             node: NodeRef<marker::Immut<'a>, K, V, marker::LeafOrInternal>,
-            mut alloc: A,
-        ) -> BTreeMap<K, V, A>
+            alloc: A,
+        ) -> Result<BTreeMap<K, V, A>, TryReserveError>
         where
             K: 'a,
             V: 'a,
@@ -162,9 +207,9 @@ impl<K: Clone, V: Clone, A: Clone + AllocRef> Clone for BTreeMap<K, V, A> {
             match node.force() {
                 Leaf(leaf) => {
                     let mut out_tree = BTreeMap {
-                        root: Some(node::Root::new_leaf(&mut alloc)),
+                        root: Some(node::Root::try_new_leaf(&alloc)?),
                         length: 0,
-                        alloc: ManuallyDrop::new(alloc.clone()),
+                        alloc: ManuallyDrop::new(alloc),
                     };
 
                     {
@@ -184,15 +229,18 @@ impl<K: Clone, V: Clone, A: Clone + AllocRef> Clone for BTreeMap<K, V, A> {
                         }
                     }
 
-                    out_tree
+                    Ok(out_tree)
                 }
                 Internal(internal) => {
                     let mut out_tree =
-                        clone_subtree(internal.first_edge().descend(), alloc.clone());
+                        clone_subtree(internal.first_edge().descend(), alloc.clone())?;
 
                     {
-                        let out_root = BTreeMap::ensure_is_owned(&mut out_tree.root, &mut alloc);
-                        let mut out_node = out_root.push_internal_level(&mut alloc);
+                        // The subtree of a non-empty internal node always has a root. If
+                        // cloning a later subtree fails, `out_tree` is still a valid tree and
+                        // is dropped as such.
+                        let out_root = out_tree.root.as_mut().unwrap();
+                        let mut out_node = out_root.try_push_internal_level(&alloc)?;
                         let mut in_edge = internal.first_edge();
                         while let Ok(kv) = in_edge.right_kv() {
                             let (k, v) = kv.into_kv();
@@ -200,7 +248,7 @@ impl<K: Clone, V: Clone, A: Clone + AllocRef> Clone for BTreeMap<K, V, A> {
 
                             let k = (*k).clone();
                             let v = (*v).clone();
-                            let subtree = clone_subtree(in_edge.descend(), alloc.clone());
+                            let subtree = clone_subtree(in_edge.descend(), alloc.clone())?;
 
                             // We can't destructure subtree directly
                             // because BTreeMap implements Drop
@@ -211,33 +259,30 @@ impl<K: Clone, V: Clone, A: Clone + AllocRef> Clone for BTreeMap<K, V, A> {
                                 (root, length)
                             };
 
-                            out_node.push(
-                                k,
-                                v,
-                                subroot.unwrap_or_else(|| node::Root::new_leaf(&mut alloc)),
-                            );
+                            let subroot = match subroot {
+                                Some(subroot) => subroot,
+                                None => node::Root::try_new_leaf(&alloc)?,
+                            };
+                            out_node.push(k, v, subroot);
                             out_tree.length += 1 + sublength;
                         }
                     }
 
-                    out_tree
+                    Ok(out_tree)
                 }
             }
         }
 
         if self.is_empty() {
-            // Ideally we'd call `BTreeMap::new` here, but that has the `K:
+            // Ideally we'd call `BTreeMap::new_in` here, but that has the `K:
             // Ord` constraint, which this method lacks.
-            BTreeMap {
+            Ok(BTreeMap {
                 root: None,
                 length: 0,
-                alloc: self.alloc.clone(),
-            }
+                alloc: ManuallyDrop::new(alloc),
+            })
         } else {
-            clone_subtree(
-                self.root.as_ref().unwrap().node_as_ref(),
-                ManuallyDrop::into_inner(self.alloc.clone()),
-            ) // unwrap succeeds because not empty
+            clone_subtree(self.root.as_ref().unwrap().node_as_ref(), alloc) // unwrap succeeds because not empty
         }
     }
 }
@@ -556,6 +601,41 @@ impl<K: Debug + Ord, V: Debug, A: AllocRef> Debug for OccupiedEntry<'_, K, V, A>
     }
 }
 
+/// Returns the number of leaf and internal nodes `BTreeMap::from_sorted_iter` allocates when
+/// it builds a tree of `len` elements from scratch, by replaying its algorithm on node lengths.
+fn sorted_iter_node_count(len: usize) -> (usize, usize) {
+    // `lens[h]` is the length of the right-most node at height `h`. Every level fans out by
+    // more than 2, so the height can never reach the number of bits in `usize`.
+    let mut lens = [0; mem::size_of::<usize>() * 8];
+    let mut height = 0;
+    let (mut leaves, mut internals) = (1, 0);
+    for _ in 0..len {
+        if lens[0] < node::CAPACITY {
+            lens[0] += 1;
+            continue;
+        }
+
+        // Find a node with space left, or add a new root.
+        let mut open = 1;
+        while open <= height && lens[open] == node::CAPACITY {
+            open += 1;
+        }
+        if open > height {
+            height += 1;
+            internals += 1;
+        }
+
+        // Push the element and a new right subtree of height `open - 1`.
+        lens[open] += 1;
+        leaves += 1;
+        internals += open - 1;
+        for l in &mut lens[..open] {
+            *l = 0;
+        }
+    }
+    (leaves, internals)
+}
+
 // An iterator for merging two sorted sequences into one
 struct MergeIter<K, V, ILeft: Iterator<Item = (K, V)>, IRight: Iterator<Item = (K, V)>> {
     left: Peekable<ILeft>,
@@ -958,6 +1038,33 @@ impl<K: Ord, V, A: AllocRef> BTreeMap<K, V, A> {
         }
     }
 
+    /// Same as `insert` but returns errors instead of aborting.
+    ///
+    /// If the map needs to allocate and fails, it is left unchanged and the key and value are
+    /// handed back in the error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use alloc_wg::collections::BTreeMap;
+    ///
+    /// let mut map = BTreeMap::new();
+    /// assert_eq!(map.try_insert(37, "a")?, None);
+    /// assert_eq!(map.try_insert(37, "b")?, Some("a"));
+    /// assert_eq!(map[&37], "b");
+    /// # Ok::<(), alloc_wg::collections::TryReserveError>(())
+    /// ```
+    pub fn try_insert(&mut self, key: K, value: V) -> Result<Option<V>, TryPushError<(K, V)>> {
+        match self.try_entry(key) {
+            Ok(Occupied(mut entry)) => Ok(Some(entry.insert(value))),
+            Ok(Vacant(entry)) => entry.try_insert(value).map(|_| None),
+            Err(err) => {
+                let (key, error) = err.into_parts();
+                Err(TryPushError::new((key, value), error))
+            }
+        }
+    }
+
     /// Removes a key from the map, returning the value at the key if the key
     /// was previously in the map.
     ///
@@ -1055,20 +1162,58 @@ impl<K: Ord, V, A: AllocRef> BTreeMap<K, V, A> {
     /// ```
     //#[stable(feature = "btree_append", since = "1.11.0")]
     pub fn append(&mut self, other: &mut Self)
+    where
+        A: Clone,
+    {
+        handle_reserve_error(self.try_append(other))
+    }
+
+    /// Same as `append` but returns errors instead of aborting.
+    ///
+    /// All nodes of the merged tree are allocated before any element is moved, so both maps
+    /// are left unchanged if an error is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use alloc_wg::collections::BTreeMap;
+    ///
+    /// let mut a = BTreeMap::new();
+    /// a.insert(1, "a");
+    ///
+    /// let mut b = BTreeMap::new();
+    /// b.insert(1, "b");
+    /// b.insert(2, "c");
+    ///
+    /// a.try_append(&mut b)?;
+    ///
+    /// assert_eq!(a.len(), 2);
+    /// assert_eq!(b.len(), 0);
+    /// assert_eq!(a[&1], "b");
+    /// # Ok::<(), alloc_wg::collections::TryReserveError>(())
+    /// ```
+    pub fn try_append(&mut self, other: &mut Self) -> Result<(), TryReserveError>
     where
         A: Clone,
     {
         // Do we have to append anything at all?
         if other.is_empty() {
-            return;
+            return Ok(());
         }
 
         // We can just swap `self` and `other` if `self` is empty.
         if self.is_empty() {
             mem::swap(self, other);
-            return;
+            return Ok(());
         }
 
+        // Duplicate keys are merged into one element, so this may reserve a few nodes too many.
+        // They are released again when the reserve is dropped.
+        let alloc = ManuallyDrop::into_inner(self.alloc.clone());
+        let mut reserve = node::NodeReserve::new(&alloc);
+        let (leaves, internals) = sorted_iter_node_count(self.length + other.length);
+        reserve.try_reserve(leaves, internals)?;
+
         // First, we merge `self` and `other` into a sorted sequence in linear time.
         let self_iter = mem::replace(
             self,
@@ -1082,7 +1227,8 @@ impl<K: Ord, V, A: AllocRef> BTreeMap<K, V, A> {
         };
 
         // Second, we build a tree from the sorted sequence in linear time.
-        self.from_sorted_iter(iter);
+        self.from_sorted_iter(iter, &mut reserve);
+        Ok(())
     }
 
     /// Constructs a double-ended iterator over a sub-range of elements in the map.
@@ -1209,10 +1355,37 @@ impl<K: Ord, V, A: AllocRef> BTreeMap<K, V, A> {
     /// ```
     //#[stable(feature = "rust1", since = "1.0.0")]
     pub fn entry(&mut self, key: K) -> Entry<'_, K, V, A> {
+        handle_reserve_error(self.try_entry(key).map_err(TryPushError::into_error))
+    }
+
+    /// Same as `entry` but returns errors instead of aborting.
+    ///
+    /// An empty map allocates its root node here, which may fail. The key is handed back in
+    /// the error. Inserting into the returned entry may allocate again, see
+    /// [`VacantEntry::try_insert`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use alloc_wg::collections::BTreeMap;
+    ///
+    /// let mut count: BTreeMap<&str, usize> = BTreeMap::new();
+    /// for &x in &["a", "b", "a"] {
+    ///     *count.try_entry(x)?.try_or_insert(0)? += 1;
+    /// }
+    /// assert_eq!(count["a"], 2);
+    /// # Ok::<(), alloc_wg::collections::TryReserveError>(())
+    /// ```
+    ///
+    /// [`VacantEntry::try_insert`]: VacantEntry::try_insert
+    pub fn try_entry(&mut self, key: K) -> Result<Entry<'_, K, V, A>, TryPushError<K>> {
         // FIXME(@porglezomp) Avoid allocating if we don't insert
         let (map, dormant_map) = DormantMutRef::new(self);
-        let root_node = Self::ensure_is_owned(&mut map.root, &mut *map.alloc).node_as_mut();
-        match search::search_tree(root_node, &key) {
+        let root = match Self::try_ensure_is_owned(&mut map.root, &*map.alloc) {
+            Ok(root) => root,
+            Err(error) => return Err(TryPushError::new(key, error)),
+        };
+        Ok(match search::search_tree(root.node_as_mut(), &key) {
             Found(handle) => Occupied(OccupiedEntry {
                 handle,
                 dormant_map,
@@ -1226,11 +1399,17 @@ impl<K: Ord, V, A: AllocRef> BTreeMap<K, V, A> {
                 alloc: &mut *map.alloc,
                 _marker: PhantomData,
             }),
-        }
+        })
     }
 
-    fn from_sorted_iter<I: Iterator<Item = (K, V)>>(&mut self, iter: I) {
-        let root = Self::ensure_is_owned(&mut self.root, &mut *self.alloc);
+    fn from_sorted_iter<I: Iterator<Item = (K, V)>>(
+        &mut self,
+        iter: I,
+        reserve: &mut node::NodeReserve<'_, K, V, A>,
+    ) {
+        let root = self
+            .root
+            .get_or_insert_with(|| node::Root::new_leaf_reserved(reserve));
         let mut cur_node = root.node_as_mut().last_leaf_edge().into_node();
         // Iterate through all key-value pairs, pushing them into nodes at the right level.
         for (key, value) in iter {
@@ -1256,7 +1435,7 @@ impl<K: Ord, V, A: AllocRef> BTreeMap<K, V, A> {
                         }
                         Err(_) => {
                             // We are at the top, create a new root node and push there.
-                            open_node = root.push_internal_level(&mut *self.alloc);
+                            open_node = root.push_internal_level_reserved(reserve);
                             break;
                         }
                     }
@@ -1264,9 +1443,9 @@ impl<K: Ord, V, A: AllocRef> BTreeMap<K, V, A> {
 
                 // Push key-value pair and new right subtree.
                 let tree_height = open_node.height() - 1;
-                let mut right_tree = node::Root::new_leaf(&mut *self.alloc);
+                let mut right_tree = node::Root::new_leaf_reserved(reserve);
                 for _ in 0..tree_height {
-                    right_tree.push_internal_level(&mut *self.alloc);
+                    right_tree.push_internal_level_reserved(reserve);
                 }
                 open_node.push(key, value, right_tree);
 
@@ -1332,22 +1511,57 @@ impl<K: Ord, V, A: AllocRef> BTreeMap<K, V, A> {
     /// ```
     //#[stable(feature = "btree_split_off", since = "1.11.0")]
     pub fn split_off<Q: ?Sized + Ord>(&mut self, key: &Q) -> Self
+    where
+        K: Borrow<Q>,
+        A: Clone,
+    {
+        handle_reserve_error(self.try_split_off(key))
+    }
+
+    /// Same as `split_off` but returns errors instead of aborting.
+    ///
+    /// The nodes of the returned map are allocated before any element is moved, so `self` is
+    /// left unchanged if an error is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use alloc_wg::collections::BTreeMap;
+    ///
+    /// let mut a = BTreeMap::new();
+    /// a.insert(1, "a");
+    /// a.insert(2, "b");
+    /// a.insert(3, "c");
+    ///
+    /// let b = a.try_split_off(&2)?;
+    ///
+    /// assert_eq!(a.len(), 1);
+    /// assert_eq!(b.len(), 2);
+    /// assert_eq!(b[&3], "c");
+    /// # Ok::<(), alloc_wg::collections::TryReserveError>(())
+    /// ```
+    pub fn try_split_off<Q: ?Sized + Ord>(&mut self, key: &Q) -> Result<Self, TryReserveError>
     where
         K: Borrow<Q>,
         A: Clone,
     {
         if self.is_empty() {
-            return Self::new_in(ManuallyDrop::into_inner(self.alloc.clone()));
+            return Ok(Self::new_in(ManuallyDrop::into_inner(self.alloc.clone())));
         }
 
         let total_num = self.len();
         let left_root = self.root.as_mut().unwrap(); // unwrap succeeds because not empty
 
         let mut right = Self::new_in(ManuallyDrop::into_inner(self.alloc.clone()));
-        let right_root = Self::ensure_is_owned(&mut right.root, &mut *self.alloc);
+        let mut reserve = node::NodeReserve::new(&*right.alloc);
+        reserve.try_reserve(1, left_root.height())?;
+        let right_root = right
+            .root
+            .get_or_insert_with(|| node::Root::new_leaf_reserved(&mut reserve));
         for _ in 0..left_root.height() {
-            right_root.push_internal_level(&mut *self.alloc);
+            right_root.push_internal_level_reserved(&mut reserve);
         }
+        drop(reserve);
 
         {
             let mut left_node = left_root.node_as_mut();
@@ -1388,7 +1602,46 @@ impl<K: Ord, V, A: AllocRef> BTreeMap<K, V, A> {
             self.length = total_num - right.len();
         }
 
-        right
+        Ok(right)
+    }
+
+    /// Moves the map into the allocator `alloc` without cloning its elements.
+    ///
+    /// All nodes are allocated in `alloc` up front. The elements are then moved bitwise into the
+    /// new nodes, and the old nodes are freed with `A`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the new nodes cannot be allocated. The map is handed back unchanged
+    /// together with the error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use alloc_wg::{alloc::Global, collections::BTreeMap};
+    ///
+    /// let mut map = BTreeMap::new();
+    /// map.insert(1, "a");
+    /// let map = map.transfer_in(Global)?;
+    /// assert_eq!(map[&1], "a");
+    /// # Ok::<(), alloc_wg::collections::TryReserveError>(())
+    /// ```
+    pub fn transfer_in<B: Clone + AllocRef>(
+        self,
+        alloc: B,
+    ) -> Result<BTreeMap<K, V, B>, TryPushError<Self>> {
+        let mut map = BTreeMap::new_in(alloc.clone());
+        if self.is_empty() {
+            return Ok(map);
+        }
+
+        let mut reserve = node::NodeReserve::new(&alloc);
+        let (leaves, internals) = sorted_iter_node_count(self.length);
+        if let Err(err) = reserve.try_reserve(leaves, internals) {
+            return Err(TryPushError::new(self, err));
+        }
+        map.from_sorted_iter(self.into_iter(), &mut reserve);
+        Ok(map)
     }
 
     /// Creates an iterator which uses a closure to determine if an element should be removed.
@@ -2220,6 +2473,46 @@ impl<'a, K: Ord + Copy, V: Copy, A: AllocRef> Extend<(&'a K, &'a V)> for BTreeMa
     }
 }
 
+impl<K: Ord, V, A: AllocRef> FromIteratorIn<(K, V), A> for BTreeMap<K, V, A> {
+    fn from_iter_in<T: IntoIterator<Item = (K, V)>>(iter: T, alloc: A) -> BTreeMap<K, V, A> {
+        let mut map = BTreeMap::new_in(alloc);
+        map.extend(iter);
+        map
+    }
+
+    fn try_from_iter_in<T: IntoIterator<Item = (K, V)>>(
+        iter: T,
+        alloc: A,
+    ) -> Result<BTreeMap<K, V, A>, TryReserveError> {
+        let mut map = BTreeMap::new_in(alloc);
+        map.try_extend(iter)?;
+        Ok(map)
+    }
+}
+
+impl<K: Ord, V, A: AllocRef> TryExtend<(K, V)> for BTreeMap<K, V, A> {
+    type Err = TryReserveError;
+
+    /// Inserts the elements one by one. If an insertion fails, the elements inserted before it
+    /// stay in the map.
+    fn try_extend<T: IntoIterator<Item = (K, V)>>(&mut self, iter: T) -> Result<(), Self::Err> {
+        iter.into_iter()
+            .try_for_each(move |(k, v)| self.try_insert(k, v).map(drop))?;
+        Ok(())
+    }
+}
+
+impl<'a, K: Ord + Copy, V: Copy, A: AllocRef> TryExtend<(&'a K, &'a V)> for BTreeMap<K, V, A> {
+    type Err = TryReserveError;
+
+    fn try_extend<I: IntoIterator<Item = (&'a K, &'a V)>>(
+        &mut self,
+        iter: I,
+    ) -> Result<(), Self::Err> {
+        self.try_extend(iter.into_iter().map(|(&key, &value)| (key, value)))
+    }
+}
+
 //#[stable(feature = "rust1", since = "1.0.0")]
 impl<K: Hash, V: Hash, A: AllocRef> Hash for BTreeMap<K, V, A> {
     fn hash<H: Hasher>(&self, state: &mut H) {
@@ -2495,6 +2788,17 @@ impl<K, V, A: AllocRef> BTreeMap<K, V, A> {
     ) -> &'a mut node::Root<K, V> {
         root.get_or_insert_with(|| node::Root::new_leaf(alloc))
     }
+
+    /// Same as `ensure_is_owned` but returns errors instead of aborting.
+    fn try_ensure_is_owned<'a>(
+        root: &'a mut Option<node::Root<K, V>>,
+        alloc: &A,
+    ) -> Result<&'a mut node::Root<K, V>, TryReserveError> {
+        if root.is_none() {
+            *root = Some(node::Root::try_new_leaf(alloc)?);
+        }
+        Ok(root.as_mut().unwrap()) // unwrap succeeds because we just wrapped
+    }
 }
 
 impl<'a, K: Ord, V, A: AllocRef> Entry<'a, K, V, A> {
@@ -2519,6 +2823,26 @@ impl<'a, K: Ord, V, A: AllocRef> Entry<'a, K, V, A> {
         }
     }
 
+    /// Same as `or_insert` but returns errors instead of aborting.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use alloc_wg::collections::BTreeMap;
+    ///
+    /// let mut map: BTreeMap<&str, usize> = BTreeMap::new();
+    /// map.try_entry("poneyland")?.try_or_insert(12)?;
+    ///
+    /// assert_eq!(map["poneyland"], 12);
+    /// # Ok::<(), alloc_wg::collections::TryReserveError>(())
+    /// ```
+    pub fn try_or_insert(self, default: V) -> Result<&'a mut V, TryPushError<(K, V)>> {
+        match self {
+            Occupied(entry) => Ok(entry.into_mut()),
+            Vacant(entry) => entry.try_insert(default),
+        }
+    }
+
     /// Ensures a value is in the entry by inserting the result of the default function if empty,
     /// and returns a mutable reference to the value in the entry.
     ///
@@ -2695,10 +3019,39 @@ impl<'a, K: Ord, V, A: AllocRef> VacantEntry<'a, K, V, A> {
     /// ```
     //#[stable(feature = "rust1", since = "1.0.0")]
     pub fn insert(self, value: V) -> &'a mut V {
-        let out_ptr = match self
-            .handle
-            .insert_recursing(self.key, value, self.alloc)
-        {
+        let alloc = self.alloc;
+        self.insert_reserved(value, &mut node::NodeReserve::new(alloc))
+    }
+
+    /// Same as `insert` but returns errors instead of aborting.
+    ///
+    /// All nodes the insertion may need are allocated before the map is touched, so a failed
+    /// node split leaves the map unchanged. The key and value are handed back in the error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use alloc_wg::collections::{btree_map::Entry, BTreeMap};
+    ///
+    /// let mut map: BTreeMap<&str, u32> = BTreeMap::new();
+    ///
+    /// if let Entry::Vacant(o) = map.try_entry("poneyland")? {
+    ///     o.try_insert(37)?;
+    /// }
+    /// assert_eq!(map["poneyland"], 37);
+    /// # Ok::<(), alloc_wg::collections::TryReserveError>(())
+    /// ```
+    pub fn try_insert(self, value: V) -> Result<&'a mut V, TryPushError<(K, V)>> {
+        let alloc = self.alloc;
+        let mut reserve = node::NodeReserve::new(alloc);
+        match reserve.try_reserve_for_insert(&self.handle) {
+            Ok(()) => Ok(self.insert_reserved(value, &mut reserve)),
+            Err(error) => Err(TryPushError::new((self.key, value), error)),
+        }
+    }
+
+    fn insert_reserved(self, value: V, reserve: &mut node::NodeReserve<'_, K, V, A>) -> &'a mut V {
+        let out_ptr = match self.handle.insert_recursing(self.key, value, reserve) {
             (Fit(_), val_ptr) => {
                 // Safety: We have consumed self.handle and the handle returned.
                 let map = unsafe { self.dormant_map.awaken() };
@@ -2710,7 +3063,7 @@ impl<'a, K: Ord, V, A: AllocRef> VacantEntry<'a, K, V, A> {
                 // Safety: We have consumed self.handle and the reference returned.
                 let map = unsafe { self.dormant_map.awaken() };
                 let root = map.root.as_mut().unwrap();
-                root.push_internal_level(self.alloc)
+                root.push_internal_level_reserved(reserve)
                     .push(ins.k, ins.v, ins.right);
                 map.length += 1;
                 val_ptr
@@ -42,6 +42,7 @@ use core::{
 use crate::{
     alloc::{AllocRef, Layout},
     boxed::Box,
+    collections::{TryPushError, TryReserveError},
 };
 
 const B: usize = 6;
@@ -175,8 +176,20 @@ impl<K, V> Root<K, V> {
 
     /// Returns a new owned tree, with its own root node that is initially empty.
     pub fn new_leaf<A: AllocRef>(alloc: &A) -> Self {
+        Self::new_leaf_reserved(&mut NodeReserve::new(alloc))
+    }
+
+    /// Same as `new_leaf` but returns errors instead of aborting.
+    pub fn try_new_leaf<A: AllocRef>(alloc: &A) -> Result<Self, TryReserveError> {
+        let mut reserve = NodeReserve::new(alloc);
+        reserve.try_reserve(1, 0)?;
+        Ok(Self::new_leaf_reserved(&mut reserve))
+    }
+
+    /// Like `new_leaf`, but takes the root node from `reserve`.
+    pub fn new_leaf_reserved<A: AllocRef>(reserve: &mut NodeReserve<'_, K, V, A>) -> Self {
         Root {
-            node: BoxedNode::from_leaf(Box::new_in(unsafe { LeafNode::new() }, alloc)),
+            node: BoxedNode::from_leaf(reserve.leaf()),
             height: 0,
         }
     }
@@ -222,7 +235,26 @@ impl<K, V> Root<K, V> {
         &mut self,
         alloc: &A,
     ) -> NodeRef<marker::Mut<'_>, K, V, marker::Internal> {
-        let mut new_node = Box::new_in(unsafe { InternalNode::new() }, alloc);
+        self.push_internal_level_reserved(&mut NodeReserve::new(alloc))
+    }
+
+    /// Same as `push_internal_level` but returns errors instead of aborting. The tree is left
+    /// unchanged if an error is returned.
+    pub fn try_push_internal_level<A: AllocRef>(
+        &mut self,
+        alloc: &A,
+    ) -> Result<NodeRef<marker::Mut<'_>, K, V, marker::Internal>, TryReserveError> {
+        let mut reserve = NodeReserve::new(alloc);
+        reserve.try_reserve(0, 1)?;
+        Ok(self.push_internal_level_reserved(&mut reserve))
+    }
+
+    /// Like `push_internal_level`, but takes the new root node from `reserve`.
+    pub fn push_internal_level_reserved<A: AllocRef>(
+        &mut self,
+        reserve: &mut NodeReserve<'_, K, V, A>,
+    ) -> NodeRef<marker::Mut<'_>, K, V, marker::Internal> {
+        let mut new_node = reserve.internal();
         new_node.edges[0].write(unsafe { BoxedNode::from_ptr(self.node.as_ptr()) });
 
         self.node = BoxedNode::from_internal(new_node);
@@ -276,6 +308,119 @@ impl<K, V> Root<K, V> {
     }
 }
 
+/// Nodes allocated ahead of a tree operation, so that the operation itself cannot fail halfway
+/// through and leave the tree in an invalid state.
+///
+/// Spare nodes are chained through their `parent` field. Once the reserve runs dry, further
+/// nodes are allocated on demand and allocation failure aborts, which is what the infallible
+/// operations rely on when they start out with an empty reserve.
+pub struct NodeReserve<'a, K, V, A: AllocRef> {
+    alloc: &'a A,
+    leaves: Option<NonNull<LeafNode<K, V>>>,
+    internals: Option<NonNull<InternalNode<K, V>>>,
+}
+
+impl<'a, K, V, A: AllocRef> NodeReserve<'a, K, V, A> {
+    /// Creates an empty reserve allocating from `alloc`.
+    pub fn new(alloc: &'a A) -> Self {
+        NodeReserve {
+            alloc,
+            leaves: None,
+            internals: None,
+        }
+    }
+
+    /// Allocates `leaves` more leaf nodes and `internals` more internal nodes.
+    ///
+    /// Nodes allocated before an error occurs stay in the reserve.
+    pub fn try_reserve(&mut self, leaves: usize, internals: usize) -> Result<(), TryReserveError> {
+        for _ in 0..leaves {
+            let node = Box::try_new_in(unsafe { LeafNode::new() }, self.alloc)
+                .map_err(TryPushError::into_error)?;
+            let mut node = Box::into_raw_non_null(node);
+            unsafe { node.as_mut().parent = self.leaves.map(NonNull::cast) };
+            self.leaves = Some(node);
+        }
+        for _ in 0..internals {
+            let node = Box::try_new_in(unsafe { InternalNode::new() }, self.alloc)
+                .map_err(TryPushError::into_error)?;
+            let mut node = Box::into_raw_non_null(node);
+            unsafe { node.as_mut().data.parent = self.internals };
+            self.internals = Some(node);
+        }
+        Ok(())
+    }
+
+    /// Allocates every node that inserting a key/value pair at `edge` may need.
+    ///
+    /// A full leaf is split, which pushes a key/value pair into its parent. That split
+    /// propagates upwards through every full ancestor, and splitting the root adds a new
+    /// level on top of the tree.
+    pub fn try_reserve_for_insert(
+        &mut self,
+        edge: &Handle<NodeRef<marker::Mut<'_>, K, V, marker::Leaf>, marker::Edge>,
+    ) -> Result<(), TryReserveError> {
+        let mut node = edge.reborrow().into_node().forget_type();
+        if node.len() < CAPACITY {
+            return Ok(());
+        }
+
+        let mut internals = 0;
+        loop {
+            match node.ascend() {
+                Ok(parent) => {
+                    let parent = parent.into_node();
+                    if parent.len() < CAPACITY {
+                        break;
+                    }
+                    internals += 1;
+                    node = parent.forget_type();
+                }
+                Err(_) => {
+                    internals += 1;
+                    break;
+                }
+            }
+        }
+        self.try_reserve(1, internals)
+    }
+
+    /// Takes a leaf node from the reserve, allocating one if the reserve is empty.
+    fn leaf(&mut self) -> Box<LeafNode<K, V>, &'a A> {
+        match self.leaves {
+            Some(node) => unsafe {
+                self.leaves = (*node.as_ptr()).parent.map(NonNull::cast);
+                (*node.as_ptr()).parent = None;
+                Box::from_raw_in(node.as_ptr(), self.alloc)
+            },
+            None => Box::new_in(unsafe { LeafNode::new() }, self.alloc),
+        }
+    }
+
+    /// Takes an internal node from the reserve, allocating one if the reserve is empty.
+    fn internal(&mut self) -> Box<InternalNode<K, V>, &'a A> {
+        match self.internals {
+            Some(node) => unsafe {
+                self.internals = (*node.as_ptr()).data.parent;
+                (*node.as_ptr()).data.parent = None;
+                Box::from_raw_in(node.as_ptr(), self.alloc)
+            },
+            None => Box::new_in(unsafe { InternalNode::new() }, self.alloc),
+        }
+    }
+}
+
+impl<K, V, A: AllocRef> Drop for NodeReserve<'_, K, V, A> {
+    fn drop(&mut self) {
+        while self.leaves.is_some() {
+            drop(self.leaf());
+        }
+        while self.internals.is_some() {
+            drop(self.internal());
+        }
+    }
+}
+
 // N.B. `NodeRef` is always covariant in `K` and `V`, even when the `BorrowType`
 // is `Mut`. This is technically wrong, but cannot result in any unsafety due to
 // internal use of `NodeRef` because we stay completely generic over `K` and `V`.
@@ -1021,7 +1166,7 @@ impl<'a, K: 'a, V: 'a> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, mark
         mut self,
         key: K,
         val: V,
-        alloc: &A,
+        reserve: &mut NodeReserve<'_, K, V, A>,
     ) -> (InsertResult<'a, K, V, marker::Leaf>, *mut V) {
         if self.node.len() < CAPACITY {
             let val_ptr = self.insert_fit(key, val);
@@ -1030,7 +1175,7 @@ impl<'a, K: 'a, V: 'a> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, mark
         } else {
             let (middle_kv_idx, insertion) = splitpoint(self.idx);
             let middle = unsafe { Handle::new_kv(self.node, middle_kv_idx) };
-            let (mut left, k, v, mut right) = middle.split(alloc);
+            let (mut left, k, v, mut right) = middle.split(reserve);
             let val_ptr = match insertion {
                 InsertionPlace::Left(insert_idx) => unsafe {
                     Handle::new_edge(left.reborrow_mut(), insert_idx).insert_fit(key, val)
@@ -1103,7 +1248,7 @@ impl<'a, K: 'a, V: 'a> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Internal>,
         key: K,
         val: V,
         edge: Root<K, V>,
-        alloc: &A,
+        reserve: &mut NodeReserve<'_, K, V, A>,
     ) -> InsertResult<'a, K, V, marker::Internal> {
         assert!(edge.height == self.node.height - 1);
 
@@ -1114,7 +1259,7 @@ impl<'a, K: 'a, V: 'a> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Internal>,
         } else {
             let (middle_kv_idx, insertion) = splitpoint(self.idx);
             let middle = unsafe { Handle::new_kv(self.node, middle_kv_idx) };
-            let (mut left, k, v, mut right) = middle.split(alloc);
+            let (mut left, k, v, mut right) = middle.split(reserve);
             match insertion {
                 InsertionPlace::Left(insert_idx) => unsafe {
                     Handle::new_edge(left.reborrow_mut(), insert_idx).insert_fit(key, val, edge);
@@ -1145,13 +1290,15 @@ impl<'a, K: 'a, V: 'a> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, mark
     /// If the returned result is a `Fit`, its handle's node can be this edge's node or an ancestor.
     /// If the returned result is a `Split`, the `left` field will be the root node.
     /// The returned pointer points to the inserted value.
+    ///
+    /// New nodes are taken from `reserve`, see `NodeReserve::try_reserve_for_insert`.
     pub fn insert_recursing<A: AllocRef>(
         self,
         key: K,
         value: V,
-        alloc: &A,
+        reserve: &mut NodeReserve<'_, K, V, A>,
     ) -> (InsertResult<'a, K, V, marker::LeafOrInternal>, *mut V) {
-        let (mut split, val_ptr) = match self.insert(key, value, alloc) {
+        let (mut split, val_ptr) = match self.insert(key, value, reserve) {
             (InsertResult::Fit(handle), ptr) => {
                 return (InsertResult::Fit(handle.forget_node_type()), ptr);
             }
@@ -1160,7 +1307,7 @@ impl<'a, K: 'a, V: 'a> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, mark
 
         loop {
             split = match split.left.ascend() {
-                Ok(parent) => match parent.insert(split.k, split.v, split.right, alloc) {
+                Ok(parent) => match parent.insert(split.k, split.v, split.right, reserve) {
                     InsertResult::Fit(handle) => {
                         return (InsertResult::Fit(handle.forget_node_type()), val_ptr);
                     }
@@ -1275,7 +1422,7 @@ impl<'a, K: 'a, V: 'a> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, mark
     ///   allocated node.
     pub fn split<A: AllocRef>(
         mut self,
-        alloc: &A,
+        reserve: &mut NodeReserve<'_, K, V, A>,
     ) -> (
         NodeRef<marker::Mut<'a>, K, V, marker::Leaf>,
         K,
@@ -1283,7 +1430,7 @@ impl<'a, K: 'a, V: 'a> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, mark
         Root<K, V>,
     ) {
         unsafe {
-            let mut new_node = Box::new_in(LeafNode::new(), alloc);
+            let mut new_node = reserve.leaf();
 
             let (k, v, _) = self.leafy_split(&mut new_node);
 
@@ -1333,7 +1480,7 @@ impl<'a, K: 'a, V: 'a> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Internal>,
     ///   a newly allocated node.
     pub fn split<A: AllocRef>(
         mut self,
-        alloc: &A,
+        reserve: &mut NodeReserve<'_, K, V, A>,
     ) -> (
         NodeRef<marker::Mut<'a>, K, V, marker::Internal>,
         K,
@@ -1341,7 +1488,7 @@ impl<'a, K: 'a, V: 'a> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Internal>,
         Root<K, V>,
     ) {
         unsafe {
-            let mut new_node = Box::new_in(InternalNode::new(), alloc);
+            let mut new_node = reserve.internal();
 
             let (k, v, new_len) = self.leafy_split(&mut new_node.data);
             let height = self.node.height;
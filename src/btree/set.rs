@@ -20,7 +20,12 @@ use super::{
     Recover,
 };
 
-use crate::alloc::{AllocRef, Global};
+use crate::{
+    alloc::{AllocRef, Global},
+    clone::{CloneIn, TryClone},
+    collections::{TryPushError, TryReserveError},
+    iter::{FromIteratorIn, TryExtend},
+};
 
 // FIXME(conventions): implement bounded iterators
 
@@ -86,6 +91,39 @@ impl<T: Clone, A: AllocRef + Clone> Clone for BTreeSet<T, A> {
     }
 }
 
+impl<T: Clone, A: AllocRef + Clone> BTreeSet<T, A> {
+    /// Same as `clone` but returns errors instead of aborting.
+    pub fn try_clone(&self) -> Result<Self, TryReserveError> {
+        Ok(BTreeSet {
+            map: self.map.try_clone()?,
+        })
+    }
+}
+
+impl<T: Clone, A: AllocRef + Clone> TryClone for BTreeSet<T, A> {
+    #[inline]
+    fn try_clone(&self) -> Result<Self, TryReserveError> {
+        BTreeSet::try_clone(self)
+    }
+}
+
+#[allow(clippy::use_self)]
+impl<T: Clone, A: AllocRef, B: AllocRef + Clone> CloneIn<B> for BTreeSet<T, A> {
+    type Cloned = BTreeSet<T, B>;
+
+    fn clone_in(&self, alloc: B) -> Self::Cloned {
+        BTreeSet {
+            map: self.map.clone_in(alloc),
+        }
+    }
+
+    fn try_clone_in(&self, alloc: B) -> Result<Self::Cloned, TryReserveError> {
+        Ok(BTreeSet {
+            map: self.map.try_clone_in(alloc)?,
+        })
+    }
+}
+
 /// An iterator over the items of a `BTreeSet`.
 ///
 /// This `struct` is created by the [`iter`] method on [`BTreeSet`].
@@ -872,6 +910,33 @@ impl<T: Ord, A: AllocRef> BTreeSet<T, A> {
         self.map.insert(value, ()).is_none()
     }
 
+    /// Same as `insert` but returns errors instead of aborting.
+    ///
+    /// If the set needs to allocate and fails, it is left unchanged and the value is handed
+    /// back in the error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use alloc_wg::collections::BTreeSet;
+    ///
+    /// let mut set = BTreeSet::new();
+    ///
+    /// assert_eq!(set.try_insert(2)?, true);
+    /// assert_eq!(set.try_insert(2)?, false);
+    /// assert_eq!(set.len(), 1);
+    /// # Ok::<(), alloc_wg::collections::TryReserveError>(())
+    /// ```
+    pub fn try_insert(&mut self, value: T) -> Result<bool, TryPushError<T>> {
+        match self.map.try_insert(value, ()) {
+            Ok(old) => Ok(old.is_none()),
+            Err(err) => {
+                let ((value, ()), error) = err.into_parts();
+                Err(TryPushError::new(value, error))
+            }
+        }
+    }
+
     /// Adds a value to the set, replacing the existing value, if any, that is equal to the given
     /// one. Returns the replaced value.
     ///
@@ -979,6 +1044,16 @@ impl<T: Ord, A: AllocRef> BTreeSet<T, A> {
         self.map.append(&mut other.map);
     }
 
+    /// Same as `append` but returns errors instead of aborting.
+    ///
+    /// Both sets are left unchanged if an error is returned.
+    pub fn try_append(&mut self, other: &mut Self) -> Result<(), TryReserveError>
+    where
+        A: Clone,
+    {
+        self.map.try_append(&mut other.map)
+    }
+
     /// Splits the collection into two at the given key. Returns everything after the given key,
     /// including the key.
     ///
@@ -1019,6 +1094,19 @@ impl<T: Ord, A: AllocRef> BTreeSet<T, A> {
         }
     }
 
+    /// Same as `split_off` but returns errors instead of aborting.
+    ///
+    /// The set is left unchanged if an error is returned.
+    pub fn try_split_off<Q: ?Sized + Ord>(&mut self, key: &Q) -> Result<Self, TryReserveError>
+    where
+        T: Borrow<Q>,
+        A: Clone,
+    {
+        Ok(BTreeSet {
+            map: self.map.try_split_off(key)?,
+        })
+    }
+
     /// Creates an iterator which uses a closure to determine if a value should be removed.
     ///
     /// If the closure returns true, then the value is removed and yielded.
@@ -1254,6 +1342,43 @@ impl<'a, T: 'a + Ord + Copy, A: AllocRef> Extend<&'a T> for BTreeSet<T, A> {
     }
 }
 
+impl<T: Ord, A: AllocRef> FromIteratorIn<T, A> for BTreeSet<T, A> {
+    fn from_iter_in<I: IntoIterator<Item = T>>(iter: I, alloc: A) -> BTreeSet<T, A> {
+        let mut set = BTreeSet::new_in(alloc);
+        set.extend(iter);
+        set
+    }
+
+    fn try_from_iter_in<I: IntoIterator<Item = T>>(
+        iter: I,
+        alloc: A,
+    ) -> Result<BTreeSet<T, A>, TryReserveError> {
+        let mut set = BTreeSet::new_in(alloc);
+        set.try_extend(iter)?;
+        Ok(set)
+    }
+}
+
+impl<T: Ord, A: AllocRef> TryExtend<T> for BTreeSet<T, A> {
+    type Err = TryReserveError;
+
+    /// Inserts the elements one by one. If an insertion fails, the elements inserted before it
+    /// stay in the set.
+    fn try_extend<Iter: IntoIterator<Item = T>>(&mut self, iter: Iter) -> Result<(), Self::Err> {
+        iter.into_iter()
+            .try_for_each(move |elem| self.try_insert(elem).map(drop))?;
+        Ok(())
+    }
+}
+
+impl<'a, T: 'a + Ord + Copy, A: AllocRef> TryExtend<&'a T> for BTreeSet<T, A> {
+    type Err = TryReserveError;
+
+    fn try_extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) -> Result<(), Self::Err> {
+        self.try_extend(iter.into_iter().cloned())
+    }
+}
+
 //#[stable(feature = "rust1", since = "1.0.0")]
 impl<T: Ord> Default for BTreeSet<T> {
     /// Makes an empty `BTreeSet<T>` with a reasonable choice of B.
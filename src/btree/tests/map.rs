@@ -1,6 +1,11 @@
-use crate::collections::{
-    btree_map::Entry::{Occupied, Vacant},
-    BTreeMap,
+use crate::{
+    alloc::{AllocRef, Global},
+    clone::{CloneIn, TryClone},
+    collections::{
+        btree_map::Entry::{Occupied, Vacant},
+        BTreeMap,
+    },
+    iter::IteratorExt,
 };
 use std::{
     convert::TryFrom,
@@ -15,7 +20,7 @@ use std::{
     sync::atomic::{AtomicUsize, Ordering},
 };
 
-use super::DeterministicRng;
+use super::{DeterministicRng, Limited, Limits};
 
 // Value of node::CAPACITY, thus capacity of a tree with a single level,
 // i.e. a tree who's root is a leaf node at height 0.
@@ -1402,3 +1407,153 @@ fn test_into_iter_drop_leak_height_1() {
         assert_eq!(DROPS.load(Ordering::SeqCst), size);
     }
 }
+
+fn check_limited<A: AllocRef>(map: &BTreeMap<u32, u32, A>, expected: impl Iterator<Item = u32>) {
+    let keys = map.keys().copied().collect::<Vec<_>>();
+    assert_eq!(keys, expected.collect::<Vec<_>>());
+    assert_eq!(map.len(), keys.len());
+    assert!(keys.iter().all(|k| map[k] == k * 10));
+}
+
+#[test]
+fn test_try_insert_leaves_map_valid() {
+    let limits = Limits::default();
+    let mut map = BTreeMap::new_in(Limited(&limits));
+
+    let err = map.try_insert(0, 0).unwrap_err();
+    assert_eq!(err.into_element(), (0, 0));
+    assert!(map.is_empty());
+
+    // Whenever the budget runs out, the failed insertion must leave the map untouched.
+    for i in 0..2000 {
+        if let Err(err) = map.try_insert(i, i * 10) {
+            assert_eq!(err.element(), &(i, i * 10));
+            check_limited(&map, 0..i);
+            limits.budget.set(16);
+            assert_eq!(map.try_insert(i, i * 10).unwrap(), None);
+        }
+    }
+    check_limited(&map, 0..2000);
+    assert_eq!(map.try_insert(5, 50).unwrap(), Some(50));
+
+    drop(map);
+    assert_eq!(limits.live.get(), 0);
+}
+
+#[test]
+fn test_try_entry() {
+    let limits = Limits::default();
+    let mut map = BTreeMap::new_in(Limited(&limits));
+    assert_eq!(map.try_entry(1).unwrap_err().into_element(), 1);
+
+    limits.budget.set(1);
+    *map.try_entry(1).unwrap().try_or_insert(0).unwrap() += 10;
+    assert_eq!(map[&1], 10);
+}
+
+#[test]
+fn test_try_clone() {
+    let limits = Limits::default();
+    limits.budget.set(usize::MAX);
+    let mut map = BTreeMap::new_in(Limited(&limits));
+    for i in 0..500 {
+        map.insert(i, i * 10);
+    }
+    let nodes = limits.live.get();
+
+    for budget in 0..nodes {
+        limits.budget.set(budget);
+        assert!(map.try_clone().is_err());
+        assert_eq!(limits.live.get(), nodes);
+    }
+
+    limits.budget.set(nodes);
+    let copy = map.try_clone().unwrap();
+    check_limited(&copy, 0..500);
+}
+
+#[test]
+fn test_try_append() {
+    let limits = Limits::default();
+    limits.budget.set(usize::MAX);
+    let mut a = BTreeMap::new_in(Limited(&limits));
+    let mut b = BTreeMap::new_in(Limited(&limits));
+    for i in 0..300 {
+        a.insert(i, i * 10);
+        b.insert(i + 200, (i + 200) * 10);
+    }
+
+    limits.budget.set(3);
+    assert!(a.try_append(&mut b).is_err());
+    check_limited(&a, 0..300);
+    check_limited(&b, 200..500);
+
+    limits.budget.set(usize::MAX);
+    a.try_append(&mut b).unwrap();
+    check_limited(&a, 0..500);
+    assert!(b.is_empty());
+}
+
+#[test]
+fn test_try_split_off() {
+    let limits = Limits::default();
+    limits.budget.set(usize::MAX);
+    let mut map = BTreeMap::new_in(Limited(&limits));
+    for i in 0..500 {
+        map.insert(i, i * 10);
+    }
+
+    limits.budget.set(1);
+    assert!(map.try_split_off(&250).is_err());
+    check_limited(&map, 0..500);
+
+    limits.budget.set(usize::MAX);
+    let right = map.try_split_off(&250).unwrap();
+    check_limited(&map, 0..250);
+    check_limited(&right, 250..500);
+}
+
+#[test]
+fn test_clone_in() {
+    let mut map = BTreeMap::new();
+    map.insert(1, "a");
+    assert_eq!(map.try_clone_in(Global).unwrap()[&1], "a");
+    assert_eq!(map.clone_in(Global), map);
+}
+
+#[test]
+fn test_try_collect_in() {
+    let map: BTreeMap<_, _> = vec![(2, 'b'), (1, 'a')]
+        .into_iter()
+        .try_collect_in(Global)
+        .unwrap();
+    assert_eq!(map.into_iter().collect::<Vec<_>>(), [(1, 'a'), (2, 'b')]);
+}
+
+#[test]
+fn test_transfer_in() {
+    let limits = Limits::default();
+    limits.budget.set(usize::MAX);
+    let mut map = BTreeMap::new_in(Limited(&limits));
+    for i in 0..100 {
+        map.insert(i, i * 10);
+    }
+
+    limits.budget.set(0);
+    let map = map
+        .transfer_in(Limited(&limits))
+        .unwrap_err()
+        .into_element();
+    check_limited(&map, 0..100);
+
+    let map = map.transfer_in(Global).unwrap();
+    assert_eq!(limits.live.get(), 0);
+    check_limited(&map, 0..100);
+}
+
+#[test]
+fn test_try_clone_trait() {
+    let mut map = BTreeMap::new();
+    map.insert(1, "a");
+    assert_eq!(TryClone::try_clone(&map).unwrap(), map);
+}
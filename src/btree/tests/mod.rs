@@ -1,6 +1,9 @@
+use crate::alloc::{AllocError, AllocRef, Global, Layout};
 use std::{
+    cell::Cell,
     collections::hash_map::DefaultHasher,
     hash::{Hash, Hasher},
+    ptr::NonNull,
 };
 mod map;
 mod set;
@@ -40,3 +43,32 @@ fn hash<T: Hash>(t: &T) -> u64 {
     t.hash(&mut s);
     s.finish()
 }
+
+/// Allocates from `Global` until `budget` allocations have been made, then fails. Counts the
+/// allocations that are still live.
+#[derive(Default)]
+struct Limits {
+    budget: Cell<usize>,
+    live: Cell<usize>,
+}
+
+#[derive(Copy, Clone)]
+struct Limited<'a>(&'a Limits);
+
+unsafe impl AllocRef for Limited<'_> {
+    fn alloc(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        let budget = self.0.budget.get();
+        if budget == 0 {
+            return Err(AllocError);
+        }
+        let ptr = Global.alloc(layout)?;
+        self.0.budget.set(budget - 1);
+        self.0.live.set(self.0.live.get() + 1);
+        Ok(ptr)
+    }
+
+    unsafe fn dealloc(&self, ptr: NonNull<u8>, layout: Layout) {
+        self.0.live.set(self.0.live.get() - 1);
+        Global.dealloc(ptr, layout)
+    }
+}
@@ -1,11 +1,16 @@
-use crate::collections::BTreeSet;
+use crate::{
+    alloc::Global,
+    clone::{CloneIn, TryClone},
+    collections::BTreeSet,
+    iter::{IteratorExt, TryExtend},
+};
 use std::{
     iter::FromIterator,
     panic::{catch_unwind, AssertUnwindSafe},
     sync::atomic::{AtomicU32, Ordering},
 };
 
-use super::DeterministicRng;
+use super::{DeterministicRng, Limited, Limits};
 
 #[test]
 fn test_clone_eq() {
@@ -651,3 +656,34 @@ fn test_split_off_large_random_sorted() {
     );
     assert!(right.into_iter().eq(data.into_iter().filter(|x| *x >= key)));
 }
+
+#[test]
+fn test_try_insert() {
+    let limits = Limits::default();
+    let mut set = BTreeSet::new_in(Limited(&limits));
+    assert_eq!(set.try_insert(1).unwrap_err().into_element(), 1);
+
+    limits.budget.set(1);
+    assert_eq!(set.try_insert(1).unwrap(), true);
+    assert_eq!(set.try_insert(1).unwrap(), false);
+    assert!(set.try_clone().is_err());
+}
+
+#[test]
+fn test_collect_in() {
+    let mut set: BTreeSet<_> = [3, 1, 3].iter().copied().collect_in(Global);
+    set.try_extend(&[2, 1]).unwrap();
+    assert_eq!(set.into_iter().collect::<Vec<_>>(), [1, 2, 3]);
+}
+
+#[test]
+fn test_clone_in() {
+    let set: BTreeSet<_> = (0..100).collect();
+    assert_eq!(set.clone_in(Global).len(), 100);
+}
+
+#[test]
+fn test_try_clone_trait() {
+    let set: BTreeSet<_> = (0..10).collect();
+    assert_eq!(TryClone::try_clone(&set).unwrap(), set);
+}
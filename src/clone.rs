@@ -7,3 +7,21 @@ pub trait CloneIn<A: AllocRef>: Sized {
 
     fn try_clone_in(&self, a: A) -> Result<Self::Cloned, TryReserveError>;
 }
+
+/// Same as `Clone` but returns errors instead of aborting.
+///
+/// The clone is allocated with a clone of the allocator of `self`. If cloning an element panics,
+/// the elements cloned so far are dropped and the new allocation is freed.
+///
+/// # Examples
+///
+/// ```
+/// use alloc_wg::{clone::TryClone, vec};
+///
+/// let v = vec![1, 2, 3];
+/// assert_eq!(v.try_clone()?, [1, 2, 3]);
+/// # Ok::<(), alloc_wg::collections::TryReserveError>(())
+/// ```
+pub trait TryClone: Sized {
+    fn try_clone(&self) -> Result<Self, TryReserveError>;
+}
@@ -1,6 +1,6 @@
 //! Collection types.
 
-use crate::alloc::{Layout, LayoutErr};
+use crate::alloc::{handle_alloc_error, AllocError, Layout, LayoutErr};
 use core::fmt::Display;
 pub use liballoc::collections::{binary_heap, linked_list, vec_deque};
 
@@ -21,36 +21,294 @@ pub use self::{
 };
 
 /// The error type for `try_reserve` methods.
+///
+/// Besides the failing [`Layout`], an `AllocError` records the capacities involved and the
+/// error returned by the allocator, where they are known, so the reason can be logged.
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub enum TryReserveError {
     /// Error due to the computed capacity exceeding the collection's maximum
     /// (usually `isize::MAX` bytes).
     CapacityOverflow,
 
+    /// The layout of the allocation could not be computed
+    InvalidLayout(LayoutErr),
+
     /// The memory allocator returned an error
     #[non_exhaustive]
     AllocError {
         /// The layout of allocation request that failed
         layout: Layout,
+        /// The capacity of the collection before the request, if known
+        old_capacity: Option<usize>,
+        /// The capacity which was requested, if known
+        requested_capacity: Option<usize>,
+        /// The error returned by the allocator, if any
+        cause: Option<AllocError>,
     },
 }
 
+impl TryReserveError {
+    /// Creates an `AllocError` for the failing `layout` without any further information.
+    #[inline]
+    pub const fn alloc_error(layout: Layout) -> Self {
+        Self::AllocError {
+            layout,
+            old_capacity: None,
+            requested_capacity: None,
+            cause: None,
+        }
+    }
+
+    /// Records the capacity before the request and the requested capacity.
+    ///
+    /// Does nothing unless `self` is an `AllocError`.
+    #[inline]
+    #[must_use]
+    pub fn with_capacities(mut self, old: usize, requested: usize) -> Self {
+        if let Self::AllocError {
+            old_capacity,
+            requested_capacity,
+            ..
+        } = &mut self
+        {
+            *old_capacity = Some(old);
+            *requested_capacity = Some(requested);
+        }
+        self
+    }
+
+    /// Records the error returned by the allocator.
+    ///
+    /// Does nothing unless `self` is an `AllocError`.
+    #[inline]
+    #[must_use]
+    pub fn with_cause(mut self, err: AllocError) -> Self {
+        if let Self::AllocError { cause, .. } = &mut self {
+            *cause = Some(err);
+        }
+        self
+    }
+
+    /// Returns the layout of the failing allocation request.
+    #[inline]
+    pub fn layout(&self) -> Option<Layout> {
+        match self {
+            Self::CapacityOverflow | Self::InvalidLayout(_) => None,
+            Self::AllocError { layout, .. } => Some(*layout),
+        }
+    }
+
+    /// Returns the capacity of the collection before the failing request, if known.
+    #[inline]
+    pub fn old_capacity(&self) -> Option<usize> {
+        match self {
+            Self::CapacityOverflow | Self::InvalidLayout(_) => None,
+            Self::AllocError { old_capacity, .. } => *old_capacity,
+        }
+    }
+
+    /// Returns the requested capacity, if known.
+    #[inline]
+    pub fn requested_capacity(&self) -> Option<usize> {
+        match self {
+            Self::CapacityOverflow | Self::InvalidLayout(_) => None,
+            Self::AllocError {
+                requested_capacity, ..
+            } => *requested_capacity,
+        }
+    }
+
+    /// Returns the error returned by the allocator, if any.
+    #[inline]
+    pub fn cause(&self) -> Option<&AllocError> {
+        match self {
+            Self::CapacityOverflow | Self::InvalidLayout(_) => None,
+            Self::AllocError { cause, .. } => cause.as_ref(),
+        }
+    }
+
+    /// Panics on `CapacityOverflow` and `InvalidLayout` and calls [`handle_alloc_error`] on
+    /// `AllocError`, which is what the infallible methods do.
+    ///
+    /// [`handle_alloc_error`]: crate::alloc::handle_alloc_error
+    pub fn handle(self) -> ! {
+        match self {
+            Self::CapacityOverflow | Self::InvalidLayout(_) => crate::capacity_overflow(),
+            Self::AllocError { layout, .. } => handle_alloc_error(layout),
+        }
+    }
+}
+
 impl From<LayoutErr> for TryReserveError {
     #[inline]
-    fn from(_: LayoutErr) -> Self {
-        Self::CapacityOverflow
+    fn from(err: LayoutErr) -> Self {
+        Self::InvalidLayout(err)
     }
 }
 
 impl Display for TryReserveError {
     fn fmt(&self, fmt: &mut core::fmt::Formatter<'_>) -> Result<(), core::fmt::Error> {
         fmt.write_str("memory allocation failed")?;
-        let reason = match &self {
+        match self {
             Self::CapacityOverflow => {
-                " because the computed capacity exceeded the collection's maximum"
+                fmt.write_str(" because the computed capacity exceeded the collection's maximum")
+            }
+            Self::InvalidLayout(_) => {
+                fmt.write_str(" because the layout of the allocation could not be computed")
             }
-            Self::AllocError { .. } => " because the memory allocator returned a error",
-        };
-        fmt.write_str(reason)
+            Self::AllocError {
+                layout,
+                old_capacity,
+                requested_capacity,
+                ..
+            } => {
+                fmt.write_str(" because the memory allocator returned an error")?;
+                write!(fmt, " (size: {}, align: {}", layout.size(), layout.align())?;
+                if let (Some(old), Some(requested)) = (old_capacity, requested_capacity) {
+                    write!(fmt, ", capacity: {} -> {}", old, requested)?;
+                }
+                fmt.write_str(")")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for TryReserveError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::InvalidLayout(err) => Some(err),
+            _ => self
+                .cause()
+                .map(|cause| cause as &(dyn std::error::Error + 'static)),
+        }
+    }
+}
+
+/// The error type for inserting into a collection with a fixed capacity.
+///
+/// The rejected element is handed back and can be recovered with [`into_element`].
+///
+/// [`into_element`]: Self::into_element()
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct CapacityError<T = ()> {
+    element: T,
+}
+
+impl<T> CapacityError<T> {
+    /// Creates a new `CapacityError` holding the rejected `element`.
+    #[inline]
+    pub const fn new(element: T) -> Self {
+        Self { element }
+    }
+
+    /// Returns the element which could not be inserted.
+    #[inline]
+    pub fn into_element(self) -> T {
+        self.element
+    }
+
+    /// Returns a reference to the element which could not be inserted.
+    #[inline]
+    pub fn element(&self) -> &T {
+        &self.element
+    }
+
+    /// Drops the rejected element, leaving a `CapacityError<()>`.
+    #[inline]
+    pub fn simplify(self) -> CapacityError {
+        CapacityError::new(())
+    }
+}
+
+impl<T> core::fmt::Debug for CapacityError<T> {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter<'_>) -> Result<(), core::fmt::Error> {
+        fmt.debug_struct("CapacityError").finish()
+    }
+}
+
+impl<T> Display for CapacityError<T> {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter<'_>) -> Result<(), core::fmt::Error> {
+        fmt.write_str("insufficient capacity")
+    }
+}
+
+/// The error type for fallible methods taking ownership of an element, like [`Vec::try_push`]
+/// or [`Vec::transfer_in`].
+///
+/// The rejected element is handed back together with the [`TryReserveError`] which caused the
+/// failure, so it can be retried with another allocator instead of being dropped.
+///
+/// [`Vec::try_push`]: crate::vec::Vec::try_push()
+/// [`Vec::transfer_in`]: crate::vec::Vec::transfer_in()
+#[derive(Clone, PartialEq, Eq)]
+pub struct TryPushError<T> {
+    element: T,
+    error: TryReserveError,
+}
+
+impl<T> TryPushError<T> {
+    /// Creates a new `TryPushError` from the rejected `element` and the `error` which caused it.
+    #[inline]
+    pub const fn new(element: T, error: TryReserveError) -> Self {
+        Self { element, error }
+    }
+
+    /// Returns the element which could not be inserted.
+    #[inline]
+    pub fn into_element(self) -> T {
+        self.element
+    }
+
+    /// Returns a reference to the element which could not be inserted.
+    #[inline]
+    pub fn element(&self) -> &T {
+        &self.element
+    }
+
+    /// Returns a reference to the error which caused the failure.
+    #[inline]
+    pub fn error(&self) -> &TryReserveError {
+        &self.error
+    }
+
+    /// Drops the rejected element, returning only the error.
+    #[inline]
+    pub fn into_error(self) -> TryReserveError {
+        self.error
+    }
+
+    /// Splits the error into the rejected element and the error which caused the failure.
+    #[inline]
+    pub fn into_parts(self) -> (T, TryReserveError) {
+        (self.element, self.error)
+    }
+}
+
+impl<T> From<TryPushError<T>> for TryReserveError {
+    #[inline]
+    fn from(err: TryPushError<T>) -> Self {
+        err.error
+    }
+}
+
+impl<T> core::fmt::Debug for TryPushError<T> {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter<'_>) -> Result<(), core::fmt::Error> {
+        fmt.debug_struct("TryPushError")
+            .field("error", &self.error)
+            .finish()
+    }
+}
+
+impl<T> Display for TryPushError<T> {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter<'_>) -> Result<(), core::fmt::Error> {
+        Display::fmt(&self.error, fmt)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> std::error::Error for TryPushError<T> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
     }
 }
@@ -0,0 +1,473 @@
+//! Utilities related to FFI bindings.
+//!
+//! This module provides [`CString`], an owned, NUL-terminated C string backed by a
+//! [`Vec<u8, A>`], so C strings can live in the same allocator as the rest of the data
+//! structures. With the `std` feature enabled on Unix, [`OsString`] is provided as well.
+//!
+//! [`Vec<u8, A>`]: crate::vec::Vec
+
+#[cfg(all(feature = "std", unix))]
+mod os_string;
+
+#[cfg(all(feature = "std", unix))]
+pub use self::os_string::OsString;
+
+use crate::{
+    alloc::{AllocRef, Global},
+    boxed::Box,
+    clone::CloneIn,
+    collections::TryReserveError::{self, CapacityOverflow},
+    vec::Vec,
+};
+use core::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    ops,
+};
+#[cfg(feature = "std")]
+use std::{borrow::Borrow, ffi::CStr};
+
+#[cfg(feature = "std")]
+pub use std::os::raw::c_char;
+
+/// Equivalent to C's `char` type.
+#[cfg(not(feature = "std"))]
+#[cfg(all(
+    any(
+        target_arch = "aarch64",
+        target_arch = "arm",
+        target_arch = "powerpc",
+        target_arch = "powerpc64",
+        target_arch = "s390x",
+        target_arch = "riscv64"
+    ),
+    not(any(target_vendor = "apple", windows))
+))]
+#[allow(non_camel_case_types)]
+pub type c_char = u8;
+
+/// Equivalent to C's `char` type.
+#[cfg(not(feature = "std"))]
+#[cfg(not(all(
+    any(
+        target_arch = "aarch64",
+        target_arch = "arm",
+        target_arch = "powerpc",
+        target_arch = "powerpc64",
+        target_arch = "s390x",
+        target_arch = "riscv64"
+    ),
+    not(any(target_vendor = "apple", windows))
+)))]
+#[allow(non_camel_case_types)]
+pub type c_char = i8;
+
+/// A type representing an owned, C-compatible, NUL-terminated string with no NUL bytes in the
+/// middle.
+///
+/// Unlike `std::ffi::CString`, the bytes are stored in a [`Vec<u8, A>`], so the string can be
+/// allocated in any allocator.
+///
+/// # Examples
+///
+/// ```
+/// use alloc_wg::{alloc::Global, ffi::CString};
+///
+/// let s = CString::new_in(b"foo", Global).expect("no interior NUL");
+/// assert_eq!(s.as_bytes_with_nul(), b"foo\0");
+/// ```
+///
+/// [`Vec<u8, A>`]: crate::vec::Vec
+pub struct CString<A: AllocRef = Global> {
+    // Invariant: the last byte is the only NUL byte
+    inner: Vec<u8, A>,
+}
+
+/// An error indicating that an interior NUL byte was found.
+///
+/// The error holds the bytes which were attempted to convert to a `CString`.
+#[derive(Debug)]
+pub struct NulError<A: AllocRef = Global>(usize, Vec<u8, A>);
+
+/// An error returned from the fallible `CString` constructors.
+#[derive(Debug)]
+pub enum CStringError<A: AllocRef = Global> {
+    /// The bytes contained an interior NUL byte.
+    Nul(NulError<A>),
+    /// Allocating the string failed.
+    Reserve(TryReserveError),
+}
+
+impl<A: AllocRef> From<NulError<A>> for CStringError<A> {
+    fn from(err: NulError<A>) -> Self {
+        Self::Nul(err)
+    }
+}
+
+impl<A: AllocRef> From<TryReserveError> for CStringError<A> {
+    fn from(err: TryReserveError) -> Self {
+        Self::Reserve(err)
+    }
+}
+
+fn memchr_nul(bytes: &[u8]) -> Option<usize> {
+    bytes.iter().position(|&b| b == 0)
+}
+
+impl CString {
+    /// Creates a new C-compatible string from a container of bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`NulError`] if the bytes contain an interior NUL byte.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the reallocation for the trailing NUL byte fails.
+    pub fn new<T: Into<Vec<u8>>>(t: T) -> Result<Self, NulError> {
+        Self::from_vec(t.into())
+    }
+
+    /// Retakes ownership of a `CString` that was transferred to C via [`into_raw`].
+    ///
+    /// # Safety
+    ///
+    /// This should only ever be called with a pointer that was earlier obtained by calling
+    /// [`into_raw`] on a `CString` allocated in [`Global`].
+    ///
+    /// [`into_raw`]: Self::into_raw()
+    /// [`Global`]: crate::alloc::Global
+    pub unsafe fn from_raw(ptr: *mut c_char) -> Self {
+        Self::from_raw_in(ptr, Global)
+    }
+}
+
+impl<A: AllocRef> CString<A> {
+    /// Creates a new C-compatible string by copying `bytes` into a buffer allocated with `a`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`NulError`] if the bytes contain an interior NUL byte.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the allocation fails.
+    pub fn new_in(bytes: impl AsRef<[u8]>, a: A) -> Result<Self, NulError<A>> {
+        match Self::try_new_in(bytes, a) {
+            Ok(s) => Ok(s),
+            Err(CStringError::Nul(err)) => Err(err),
+            Err(CStringError::Reserve(err)) => err.handle(),
+        }
+    }
+
+    /// Same as `new_in` but returns allocation errors instead of panicking.
+    pub fn try_new_in(bytes: impl AsRef<[u8]>, a: A) -> Result<Self, CStringError<A>> {
+        let bytes = bytes.as_ref();
+        let capacity = bytes.len().checked_add(1).ok_or(CapacityOverflow)?;
+        let mut vec = Vec::try_with_capacity_in(capacity, a)?;
+        vec.try_extend_from_slice(bytes)?;
+        Self::try_from_vec(vec)
+    }
+
+    /// Creates a C-compatible string from a byte vector, appending the trailing NUL byte.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`NulError`] if the bytes contain an interior NUL byte.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the reallocation for the trailing NUL byte fails.
+    pub fn from_vec(vec: Vec<u8, A>) -> Result<Self, NulError<A>> {
+        match memchr_nul(&vec) {
+            Some(i) => Err(NulError(i, vec)),
+            None => Ok(unsafe { Self::from_vec_unchecked(vec) }),
+        }
+    }
+
+    /// Same as `from_vec` but returns allocation errors instead of panicking.
+    pub fn try_from_vec(vec: Vec<u8, A>) -> Result<Self, CStringError<A>> {
+        match memchr_nul(&vec) {
+            Some(i) => Err(NulError(i, vec).into()),
+            None => Ok(unsafe { Self::try_from_vec_unchecked(vec)? }),
+        }
+    }
+
+    /// Creates a C-compatible string from a byte vector without checking for interior NUL
+    /// bytes.
+    ///
+    /// # Safety
+    ///
+    /// `vec` must not contain any NUL byte.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the reallocation for the trailing NUL byte fails.
+    pub unsafe fn from_vec_unchecked(mut vec: Vec<u8, A>) -> Self {
+        vec.reserve_exact(1);
+        vec.push(0);
+        Self { inner: vec }
+    }
+
+    /// Same as `from_vec_unchecked` but returns errors instead of panicking.
+    ///
+    /// # Safety
+    ///
+    /// `vec` must not contain any NUL byte.
+    pub unsafe fn try_from_vec_unchecked(mut vec: Vec<u8, A>) -> Result<Self, TryReserveError> {
+        vec.try_reserve_exact(1)?;
+        vec.try_push(0)?;
+        Ok(Self { inner: vec })
+    }
+
+    /// Transfers ownership of the string to a C caller.
+    ///
+    /// The pointer must be returned to Rust and reconstituted using [`from_raw_in`] to be
+    /// properly deallocated. The allocator is dropped, use [`into_raw_alloc`] to keep it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if shrinking the buffer to the length of the string fails.
+    ///
+    /// [`from_raw_in`]: Self::from_raw_in()
+    /// [`into_raw_alloc`]: Self::into_raw_alloc()
+    #[inline]
+    pub fn into_raw(self) -> *mut c_char {
+        self.into_raw_alloc().0
+    }
+
+    /// Same as `into_raw` but also returns the allocator.
+    pub fn into_raw_alloc(self) -> (*mut c_char, A) {
+        let (ptr, alloc) = Box::into_raw_alloc(self.inner.into_boxed_slice());
+        (ptr as *mut c_char, alloc)
+    }
+
+    /// Retakes ownership of a `CString` that was transferred to C via [`into_raw_alloc`].
+    ///
+    /// # Safety
+    ///
+    /// This should only ever be called with a pointer that was earlier obtained by calling
+    /// [`into_raw`] or [`into_raw_alloc`] on a `CString` allocated with `alloc`. The length
+    /// of the string must not have been changed by the C code.
+    ///
+    /// [`into_raw`]: Self::into_raw()
+    /// [`into_raw_alloc`]: Self::into_raw_alloc()
+    pub unsafe fn from_raw_in(ptr: *mut c_char, alloc: A) -> Self {
+        let ptr = ptr as *mut u8;
+        let mut len = 0;
+        while *ptr.add(len) != 0 {
+            len += 1;
+        }
+        Self {
+            inner: Vec::from_raw_parts_in(ptr, len + 1, len + 1, alloc),
+        }
+    }
+
+    /// Returns the contents of this `CString` as a slice of bytes, without the trailing NUL
+    /// byte.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.inner[..self.inner.len() - 1]
+    }
+
+    /// Equivalent to [`as_bytes`] except that the returned slice includes the trailing NUL
+    /// byte.
+    ///
+    /// [`as_bytes`]: Self::as_bytes()
+    #[inline]
+    pub fn as_bytes_with_nul(&self) -> &[u8] {
+        &self.inner
+    }
+
+    /// Returns the inner pointer to this C string.
+    #[inline]
+    pub fn as_ptr(&self) -> *const c_char {
+        self.inner.as_ptr() as *const c_char
+    }
+
+    /// Extracts a `CStr` slice containing the entire string.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn as_c_str(&self) -> &CStr {
+        unsafe { CStr::from_bytes_with_nul_unchecked(&self.inner) }
+    }
+
+    /// Consumes the `CString` and returns the underlying byte buffer without the trailing NUL
+    /// byte.
+    pub fn into_bytes(self) -> Vec<u8, A> {
+        let mut vec = self.inner;
+        let _nul = vec.pop();
+        debug_assert_eq!(_nul, Some(0));
+        vec
+    }
+
+    /// Equivalent to [`into_bytes`] except that the returned vector includes the trailing
+    /// NUL byte.
+    ///
+    /// [`into_bytes`]: Self::into_bytes()
+    #[inline]
+    pub fn into_bytes_with_nul(self) -> Vec<u8, A> {
+        self.inner
+    }
+
+    /// Returns a shared reference to the allocator backing this `CString`.
+    #[inline]
+    pub fn alloc_ref(&self) -> &A {
+        self.inner.alloc_ref()
+    }
+}
+
+impl<A: AllocRef> NulError<A> {
+    /// Returns the position of the NUL byte in the slice that caused the error.
+    pub fn nul_position(&self) -> usize {
+        self.0
+    }
+
+    /// Consumes this error, returning the underlying vector of bytes which generated the
+    /// error in the first place.
+    pub fn into_vec(self) -> Vec<u8, A> {
+        self.1
+    }
+}
+
+impl<A: AllocRef> fmt::Display for NulError<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "nul byte found in provided data at position: {}", self.0)
+    }
+}
+
+impl<A: AllocRef> fmt::Display for CStringError<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Nul(err) => fmt::Display::fmt(err, f),
+            Self::Reserve(err) => fmt::Display::fmt(err, f),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<A: AllocRef> ops::Deref for CString<A> {
+    type Target = CStr;
+
+    #[inline]
+    fn deref(&self) -> &CStr {
+        self.as_c_str()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<A: AllocRef> AsRef<CStr> for CString<A> {
+    #[inline]
+    fn as_ref(&self) -> &CStr {
+        self
+    }
+}
+
+#[cfg(feature = "std")]
+impl<A: AllocRef> Borrow<CStr> for CString<A> {
+    #[inline]
+    fn borrow(&self) -> &CStr {
+        self
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<&CStr> for CString {
+    fn from(s: &CStr) -> Self {
+        Self {
+            inner: Vec::from(s.to_bytes_with_nul()),
+        }
+    }
+}
+
+impl<A: AllocRef> ops::Index<ops::RangeFull> for CString<A> {
+    type Output = [u8];
+
+    #[inline]
+    fn index(&self, _index: ops::RangeFull) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl<A: AllocRef + Clone> Clone for CString<A> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+#[allow(clippy::use_self)]
+impl<A: AllocRef, B: AllocRef> CloneIn<B> for CString<A> {
+    type Cloned = CString<B>;
+
+    fn clone_in(&self, a: B) -> Self::Cloned {
+        CString {
+            inner: self.inner.clone_in(a),
+        }
+    }
+
+    fn try_clone_in(&self, a: B) -> Result<Self::Cloned, TryReserveError> {
+        Ok(CString {
+            inner: self.inner.try_clone_in(a)?,
+        })
+    }
+}
+
+impl<A: AllocRef + Default> Default for CString<A> {
+    /// Creates an empty `CString`.
+    fn default() -> Self {
+        unsafe { Self::from_vec_unchecked(Vec::new_in(A::default())) }
+    }
+}
+
+impl<A: AllocRef> fmt::Debug for CString<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("\"")?;
+        for &byte in self.as_bytes() {
+            for c in core::ascii::escape_default(byte) {
+                fmt::Write::write_char(f, c as char)?;
+            }
+        }
+        f.write_str("\"")
+    }
+}
+
+impl<A: AllocRef, B: AllocRef> PartialEq<CString<B>> for CString<A> {
+    #[inline]
+    fn eq(&self, other: &CString<B>) -> bool {
+        self.as_bytes() == other.as_bytes()
+    }
+}
+
+impl<A: AllocRef> Eq for CString<A> {}
+
+impl<A: AllocRef> PartialOrd for CString<A> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.as_bytes().partial_cmp(other.as_bytes())
+    }
+}
+
+impl<A: AllocRef> Ord for CString<A> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_bytes().cmp(other.as_bytes())
+    }
+}
+
+impl<A: AllocRef> Hash for CString<A> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // same as `CStr`
+        self.as_bytes_with_nul().hash(state);
+    }
+}
+
+impl<A: AllocRef> From<CString<A>> for Vec<u8, A> {
+    /// Converts a `CString` into a `Vec<u8>`, removing the trailing NUL byte.
+    #[inline]
+    fn from(s: CString<A>) -> Self {
+        s.into_bytes()
+    }
+}
@@ -0,0 +1,345 @@
+use crate::{
+    alloc::{AllocRef, Global},
+    clone::CloneIn,
+    collections::TryReserveError,
+    handle_reserve_error,
+    string::String,
+    vec::Vec,
+};
+use core::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    ops,
+};
+use std::{borrow::Borrow, ffi::OsStr, os::unix::ffi::OsStrExt};
+
+/// A type that can represent owned, mutable platform-native strings.
+///
+/// On Unix, platform strings are arbitrary byte sequences, so `OsString` is a thin wrapper
+/// around a [`Vec<u8, A>`]. It dereferences to `std::ffi::OsStr` and can therefore be used
+/// with the standard library's APIs.
+///
+/// # Examples
+///
+/// ```
+/// use alloc_wg::{alloc::Global, ffi::OsString};
+///
+/// let mut s = OsString::new_in(Global);
+/// s.try_push("foo")?;
+/// s.try_push("bar")?;
+/// assert_eq!(s.as_os_str(), "foobar");
+/// # Ok::<(), alloc_wg::collections::TryReserveError>(())
+/// ```
+///
+/// [`Vec<u8, A>`]: crate::vec::Vec
+pub struct OsString<A: AllocRef = Global> {
+    inner: Vec<u8, A>,
+}
+
+impl OsString {
+    /// Constructs a new empty `OsString`.
+    #[inline]
+    pub fn new() -> Self {
+        Self::new_in(Global)
+    }
+
+    /// Creates a new `OsString` with the given capacity.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the allocation fails.
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_in(capacity, Global)
+    }
+}
+
+impl<A: AllocRef> OsString<A> {
+    /// Like `new` but parameterized over the choice of allocator for the returned `OsString`.
+    #[inline]
+    pub fn new_in(a: A) -> Self {
+        Self {
+            inner: Vec::new_in(a),
+        }
+    }
+
+    /// Like `with_capacity` but parameterized over the choice of allocator for the returned
+    /// `OsString`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the allocation fails.
+    #[inline]
+    pub fn with_capacity_in(capacity: usize, a: A) -> Self {
+        Self {
+            inner: Vec::with_capacity_in(capacity, a),
+        }
+    }
+
+    /// Same as `with_capacity_in` but returns errors instead of panicking.
+    #[inline]
+    pub fn try_with_capacity_in(capacity: usize, a: A) -> Result<Self, TryReserveError> {
+        Ok(Self {
+            inner: Vec::try_with_capacity_in(capacity, a)?,
+        })
+    }
+
+    /// Copies `s` into a new `OsString` allocated with `a`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the allocation fails.
+    #[inline]
+    pub fn from_os_str_in(s: impl AsRef<OsStr>, a: A) -> Self {
+        handle_reserve_error(Self::try_from_os_str_in(s, a))
+    }
+
+    /// Same as `from_os_str_in` but returns errors instead of panicking.
+    pub fn try_from_os_str_in(s: impl AsRef<OsStr>, a: A) -> Result<Self, TryReserveError> {
+        let bytes = s.as_ref().as_bytes();
+        let mut inner = Vec::try_with_capacity_in(bytes.len(), a)?;
+        inner.try_extend_from_slice(bytes)?;
+        Ok(Self { inner })
+    }
+
+    /// Creates an `OsString` from a byte vector.
+    #[inline]
+    pub fn from_vec(vec: Vec<u8, A>) -> Self {
+        Self { inner: vec }
+    }
+
+    /// Yields the underlying byte vector of this `OsString`.
+    #[inline]
+    pub fn into_vec(self) -> Vec<u8, A> {
+        self.inner
+    }
+
+    pub(crate) fn as_mut_vec(&mut self) -> &mut Vec<u8, A> {
+        &mut self.inner
+    }
+
+    /// Converts to an `OsStr` slice.
+    #[inline]
+    pub fn as_os_str(&self) -> &OsStr {
+        OsStr::from_bytes(&self.inner)
+    }
+
+    /// Converts the `OsString` into a [`String`] if it contains valid Unicode data.
+    ///
+    /// On failure, ownership of the original `OsString` is returned.
+    ///
+    /// [`String`]: crate::string::String
+    pub fn into_string(self) -> Result<String<A>, Self> {
+        String::from_utf8(self.inner).map_err(|err| Self {
+            inner: err.into_bytes(),
+        })
+    }
+
+    /// Extends the string with the given `&OsStr` slice.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the reallocation fails.
+    #[inline]
+    pub fn push(&mut self, s: impl AsRef<OsStr>) {
+        self.inner.extend_from_slice(s.as_ref().as_bytes())
+    }
+
+    /// Same as `push` but returns errors instead of panicking.
+    #[inline]
+    pub fn try_push(&mut self, s: impl AsRef<OsStr>) -> Result<(), TryReserveError> {
+        self.inner.try_extend_from_slice(s.as_ref().as_bytes())
+    }
+
+    /// Returns the capacity this `OsString` can hold without reallocating.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.inner.capacity()
+    }
+
+    /// Returns the length of this `OsString` in bytes.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Checks whether the `OsString` is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Truncates the `OsString` to zero length.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.inner.clear()
+    }
+
+    /// Reserves capacity for at least `additional` more bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity overflows `usize` or if the allocation fails.
+    #[inline]
+    pub fn reserve(&mut self, additional: usize) {
+        self.inner.reserve(additional)
+    }
+
+    /// Same as `reserve` but returns errors instead of panicking.
+    #[inline]
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.inner.try_reserve(additional)
+    }
+
+    /// Reserves the minimum capacity for exactly `additional` more bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity overflows `usize` or if the allocation fails.
+    #[inline]
+    pub fn reserve_exact(&mut self, additional: usize) {
+        self.inner.reserve_exact(additional)
+    }
+
+    /// Same as `reserve_exact` but returns errors instead of panicking.
+    #[inline]
+    pub fn try_reserve_exact(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.inner.try_reserve_exact(additional)
+    }
+
+    /// Shrinks the capacity of the `OsString` to match its length.
+    #[inline]
+    pub fn shrink_to_fit(&mut self) {
+        self.inner.shrink_to_fit()
+    }
+
+    /// Returns a shared reference to the allocator backing this `OsString`.
+    #[inline]
+    pub fn alloc_ref(&self) -> &A {
+        self.inner.alloc_ref()
+    }
+}
+
+impl<A: AllocRef> ops::Deref for OsString<A> {
+    type Target = OsStr;
+
+    #[inline]
+    fn deref(&self) -> &OsStr {
+        self.as_os_str()
+    }
+}
+
+impl<A: AllocRef> AsRef<OsStr> for OsString<A> {
+    #[inline]
+    fn as_ref(&self) -> &OsStr {
+        self
+    }
+}
+
+impl<A: AllocRef> Borrow<OsStr> for OsString<A> {
+    #[inline]
+    fn borrow(&self) -> &OsStr {
+        self
+    }
+}
+
+impl<A: AllocRef + Clone> Clone for OsString<A> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+#[allow(clippy::use_self)]
+impl<A: AllocRef, B: AllocRef> CloneIn<B> for OsString<A> {
+    type Cloned = OsString<B>;
+
+    fn clone_in(&self, a: B) -> Self::Cloned {
+        OsString {
+            inner: self.inner.clone_in(a),
+        }
+    }
+
+    fn try_clone_in(&self, a: B) -> Result<Self::Cloned, TryReserveError> {
+        Ok(OsString {
+            inner: self.inner.try_clone_in(a)?,
+        })
+    }
+}
+
+impl<A: AllocRef + Default> Default for OsString<A> {
+    /// Constructs an empty `OsString`.
+    #[inline]
+    fn default() -> Self {
+        Self::new_in(A::default())
+    }
+}
+
+impl<A: AllocRef> fmt::Debug for OsString<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_os_str(), f)
+    }
+}
+
+impl<A: AllocRef, B: AllocRef> PartialEq<OsString<B>> for OsString<A> {
+    #[inline]
+    fn eq(&self, other: &OsString<B>) -> bool {
+        self.as_os_str() == other.as_os_str()
+    }
+}
+
+impl<A: AllocRef> PartialEq<OsStr> for OsString<A> {
+    #[inline]
+    fn eq(&self, other: &OsStr) -> bool {
+        self.as_os_str() == other
+    }
+}
+
+impl<A: AllocRef> PartialEq<str> for OsString<A> {
+    #[inline]
+    fn eq(&self, other: &str) -> bool {
+        self.as_os_str() == other
+    }
+}
+
+impl<A: AllocRef> Eq for OsString<A> {}
+
+impl<A: AllocRef> PartialOrd for OsString<A> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.as_os_str().partial_cmp(other.as_os_str())
+    }
+}
+
+impl<A: AllocRef> Ord for OsString<A> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_os_str().cmp(other.as_os_str())
+    }
+}
+
+impl<A: AllocRef> Hash for OsString<A> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_os_str().hash(state)
+    }
+}
+
+impl<A: AllocRef> From<String<A>> for OsString<A> {
+    /// Converts a `String` into an `OsString` without copying.
+    #[inline]
+    fn from(s: String<A>) -> Self {
+        Self {
+            inner: s.into_bytes(),
+        }
+    }
+}
+
+impl<A: AllocRef> From<OsString<A>> for Vec<u8, A> {
+    #[inline]
+    fn from(s: OsString<A>) -> Self {
+        s.into_vec()
+    }
+}
@@ -31,6 +31,7 @@
     exhaustive_patterns,
     extend_one,
     fn_traits,
+    inplace_iteration,
     maybe_uninit_extra,
     maybe_uninit_ref,
     maybe_uninit_slice,
@@ -41,6 +42,7 @@
     raw_ref_op,
     raw_vec_internals,
     receiver_trait,
+    set_ptr_value,
     slice_ptr_get,
     slice_ptr_len,
     specialization,
@@ -92,76 +94,178 @@
     incomplete_features
 )]
 
+/// Creates a [`Vec`] containing the arguments.
+///
+/// Besides the forms of `std`'s `vec!`, this accepts `in alloc;` to use a specific allocator,
+/// and `try` to return a `Result<Vec<T, A>, TryReserveError>` instead of panicking. The `try`
+/// forms allocate the exact length up front.
+///
+/// ```
+/// use alloc_wg::{alloc::Global, vec};
+///
+/// let v = vec![try 1, 2, 3]?;
+/// assert_eq!(v.capacity(), 3);
+/// let v = vec![try in Global; 0; 5]?;
+/// assert_eq!(v, [0; 5]);
+/// # Ok::<(), alloc_wg::collections::TryReserveError>(())
+/// ```
+///
+/// [`Vec`]: crate::vec::Vec
 #[macro_export]
 macro_rules! vec {
+    // The `try` and `in` forms have to come first, as `try` would be parsed as an expression.
+    // `Vec::<_, _>` selects the default growth policy, so the result type can be inferred.
+    (try in $alloc:expr) => {
+        $crate::vec::Vec::<_, _>::try_with_capacity_in(0, $alloc)
+    };
+    (try in $alloc:expr;) => {
+        $crate::vec::Vec::<_, _>::try_with_capacity_in(0, $alloc)
+    };
+    (try in $alloc:expr; $elem:expr; $n:expr) => {
+        $crate::vec::try_from_elem_in($elem, $n, $alloc)
+    };
+    (try in $alloc:expr; $($x:expr),*) => {
+        match $crate::vec::Vec::<_, _>::try_with_capacity_in($crate::__count!($($x),*), $alloc) {
+            Ok(mut v) => {
+                // the capacity is exact, so pushing can't reallocate
+                $( v.push($x); )*
+                Ok(v)
+            }
+            Err(err) => Err(err),
+        }
+    };
+    (try in $alloc:expr; $($x:expr,)*) => ($crate::vec![try in $alloc; $($x),*]);
+    (try $elem:expr; $n:expr) => ($crate::vec![try in $crate::alloc::Global; $elem; $n]);
+    (try $($x:expr),*) => ($crate::vec![try in $crate::alloc::Global; $($x),*]);
+    (try $($x:expr,)*) => ($crate::vec![try $($x),*]);
+    (in $alloc:expr) => {
+        $crate::vec::Vec::<_, _>::new_in($alloc)
+    };
+    (in $alloc:expr;) => {
+        $crate::vec::Vec::<_, _>::new_in($alloc)
+    };
+    (in $alloc:expr; $elem:expr; $n:expr) => {{
+        $crate::vec::from_elem_in($elem, $n, $alloc)
+    }};
+    (in $alloc:expr; $($x:expr),*) => {{
+        let mut v = $crate::vec::Vec::<_, _>::new_in($alloc);
+        $( v.push($x); )*
+        v
+    }};
+    (in $alloc:expr; $($x:expr,)*) => ($crate::vec![in $alloc; $($x),*]);
+    ($elem:expr; $n:expr) => (
+        $crate::vec::from_elem($elem, $n)
+    );
     ($($x:expr),*) => ({
         let mut v = $crate::vec::Vec::new();
         $( v.push($x); )*
         v
     });
     ($($x:expr,)*) => ($crate::vec![$($x),*]);
-    ($elem:expr; $n:expr) => (
-        $crate::vec::from_elem($elem, $n)
-    );
+}
+
+/// Creates a [`String`] from a string slice.
+///
+/// Like [`vec!`], `in alloc;` selects the allocator and `try` returns a
+/// `Result<String<A>, TryReserveError>` instead of panicking.
+///
+/// ```
+/// use alloc_wg::{alloc::Global, string};
+///
+/// let s = string![try in Global; "foo"]?;
+/// assert_eq!(s, "foo");
+/// # Ok::<(), alloc_wg::collections::TryReserveError>(())
+/// ```
+///
+/// [`String`]: crate::string::String
+/// [`vec!`]: crate::vec!
+#[macro_export]
+macro_rules! string {
+    (try in $alloc:expr) => {
+        $crate::string::String::try_with_capacity_in(0, $alloc)
+    };
+    (try in $alloc:expr; $s:expr) => {
+        $crate::string::String::try_from_str_in($s, $alloc)
+    };
+    (try $s:expr) => ($crate::string![try in $crate::alloc::Global; $s]);
     (in $alloc:expr) => {
-        $crate::vec::Vec::new_in($alloc)
+        $crate::string::String::new_in($alloc)
     };
-    (in $alloc:expr; $($x:expr),*) => {{
-        let mut v = $crate::vec::Vec::new_in($alloc);
-        $( v.push($x); )*
-        v
-    }};
-    (in $alloc:expr; $($x:expr,)*) => ($crate::vec![in $alloc; $($x),*]);
-    (in $alloc:expr; $elem:expr; $n:expr) => {{
-        $crate::vec::from_elem_in($elem, $n, $alloc)
-    }};
-    (try $($x:expr),*) => {{
-        (|| -> Result<$crate::vec::Vec<_,_>, $crate::collections::CollectionAllocErr<_>> {
-            let mut v = $crate::vec::Vec::new();
-            $( v.try_push($x)?; )*
-            Ok(v)
-        })()
-    }};
-    (try $($x:expr,)*) => ($crate::vec![try $($x),*]);
-    (try $elem:expr; $n:expr) => {{
-        $crate::vec::try_from_elem_in($elem, $n, $crate::alloc::AbortAlloc<$crate::alloc::Global>)
-    }};
-    (try in $alloc:expr; $($x:expr),*) => {{
-        (|| -> Result<$crate::vec::Vec<_,_>, $crate::collections::CollectionAllocErr<_>> {
-            let mut v = $crate::vec::Vec::new_in($alloc);
-            $( v.try_push($x)?; )*
-            Ok(v)
-        })()
-    }};
-    (try in $alloc:expr; $($x:expr,)*) => ($crate::vec![try in $alloc; $($x),*]);
-    (try in $alloc:expr; $elem:expr; $n:expr) => {{
-        $crate::vec::try_from_elem_in($elem, $n, $alloc)
-    }};
+    (in $alloc:expr; $s:expr) => {
+        $crate::string::String::from_str_in($s, $alloc)
+    };
+    () => ($crate::string::String::new());
+    ($s:expr) => ($crate::string![in $crate::alloc::Global; $s]);
+}
+
+/// Creates a [`String`] using interpolation of runtime expressions, returning a
+/// `Result<String<A>, TryReserveError>` instead of panicking if the allocation fails.
+///
+/// `try_format!(in alloc, ...)` allocates the string in `alloc`.
+///
+/// ```
+/// use alloc_wg::{alloc::Global, try_format};
+///
+/// let s = try_format!(in Global, "{}-{}", 1, 2)?;
+/// assert_eq!(s, "1-2");
+/// # Ok::<(), alloc_wg::collections::TryReserveError>(())
+/// ```
+///
+/// [`String`]: crate::string::String
+#[macro_export]
+macro_rules! try_format {
+    (in $alloc:expr, $($arg:tt)*) => {
+        $crate::string::try_format_in(format_args!($($arg)*), $alloc)
+    };
+    ($($arg:tt)*) => {
+        $crate::string::try_format_in(format_args!($($arg)*), $crate::alloc::Global)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __count {
+    ($($x:expr),*) => (<[()]>::len(&[$($crate::__count!(@unit $x)),*]));
+    (@unit $x:expr) => (());
 }
 
+/// Creates a [`String`] using interpolation of runtime expressions.
+///
+/// `format!(in alloc, ...)` allocates the string in `alloc`. Unlike `std`'s `format!`, this
+/// only depends on `core` and `alloc`, so it is available without the `std` feature.
+///
+/// # Panics
+///
+/// Panics if the allocation fails or if a formatting trait implementation returns an error.
+///
+/// ```
+/// use alloc_wg::{alloc::Global, format};
+///
+/// assert_eq!(format!("test"), "test");
+/// assert_eq!(format!(in Global, "x = {}", 10), "x = 10");
+/// ```
+///
+/// [`String`]: crate::string::String
 #[macro_export]
 macro_rules! format {
-    ( in $alloc:expr, $fmt:expr, $($args:expr),* ) => {{
-        use std::fmt::Write;
-        let mut s = $crate::string::String::new_in($alloc);
-        let _ = write!(&mut s, $fmt, $($args),*);
-        s
-    }};
-    ( $fmt:expr, $($args:expr),* ) => {{
-        use std::fmt::Write;
-        let mut s = $crate::string::String::new();
-        let _ = write!(&mut s, $fmt, $($args),*);
-        s
-    }}
+    (in $alloc:expr, $($arg:tt)*) => {
+        $crate::string::format_in(format_args!($($arg)*), $alloc)
+    };
+    ($($arg:tt)*) => {
+        $crate::string::format_in(format_args!($($arg)*), $crate::alloc::Global)
+    };
 }
 
-// pub mod alloc;
-pub use liballoc::alloc;
+pub mod alloc;
+pub mod borrow;
 pub mod boxed;
 mod btree;
 pub mod clone;
 pub mod collections;
+pub mod ffi;
 pub mod iter;
+#[cfg(all(feature = "std", unix))]
+pub mod path;
 pub mod raw_vec;
 pub mod str;
 pub mod string;
@@ -170,10 +274,9 @@ pub mod vec;
 
 extern crate alloc as liballoc;
 
-pub use liballoc::{borrow, fmt, rc, slice};
+pub use liballoc::{fmt, rc, slice};
 
 use crate::collections::TryReserveError;
-use liballoc::alloc::handle_alloc_error;
 
 // One central function responsible for reporting capacity overflows. This'll
 // ensure that the code generation related to these panics is minimal as there's
@@ -185,7 +288,6 @@ pub(in crate) fn capacity_overflow() -> ! {
 pub(crate) fn handle_reserve_error<T>(result: Result<T, TryReserveError>) -> T {
     match result {
         Ok(t) => t,
-        Err(TryReserveError::AllocError { layout }) => handle_alloc_error(layout),
-        Err(TryReserveError::CapacityOverflow) => capacity_overflow(),
+        Err(err) => err.handle(),
     }
 }
@@ -0,0 +1,410 @@
+//! Allocator-aware owned paths.
+//!
+//! [`PathBuf`] is built on [`OsString`] and dereferences to `std::path::Path`. It is only
+//! available with the `std` feature on Unix.
+//!
+//! [`OsString`]: crate::ffi::OsString
+
+use crate::{
+    alloc::{AllocRef, Global},
+    clone::CloneIn,
+    collections::TryReserveError,
+    ffi::OsString,
+    handle_reserve_error,
+    string::String,
+};
+use core::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    ops,
+};
+use std::{borrow::Borrow, ffi::OsStr, os::unix::ffi::OsStrExt, path::Path};
+
+/// An owned, mutable path, akin to `std::path::PathBuf`, stored in the allocator `A`.
+///
+/// # Examples
+///
+/// ```
+/// use alloc_wg::{alloc::Global, path::PathBuf};
+/// use std::path::Path;
+///
+/// let mut path = PathBuf::try_from_path_in("/usr", Global)?;
+/// path.try_push("lib")?;
+/// path.try_set_extension("so")?;
+/// assert_eq!(path.as_path(), Path::new("/usr/lib.so"));
+/// # Ok::<(), alloc_wg::collections::TryReserveError>(())
+/// ```
+pub struct PathBuf<A: AllocRef = Global> {
+    inner: OsString<A>,
+}
+
+impl PathBuf {
+    /// Allocates an empty `PathBuf`.
+    #[inline]
+    pub fn new() -> Self {
+        Self::new_in(Global)
+    }
+}
+
+impl<A: AllocRef> PathBuf<A> {
+    /// Like `new` but parameterized over the choice of allocator for the returned `PathBuf`.
+    #[inline]
+    pub fn new_in(a: A) -> Self {
+        Self {
+            inner: OsString::new_in(a),
+        }
+    }
+
+    /// Creates a new `PathBuf` with a given capacity allocated with `a`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the allocation fails.
+    #[inline]
+    pub fn with_capacity_in(capacity: usize, a: A) -> Self {
+        Self {
+            inner: OsString::with_capacity_in(capacity, a),
+        }
+    }
+
+    /// Same as `with_capacity_in` but returns errors instead of panicking.
+    #[inline]
+    pub fn try_with_capacity_in(capacity: usize, a: A) -> Result<Self, TryReserveError> {
+        Ok(Self {
+            inner: OsString::try_with_capacity_in(capacity, a)?,
+        })
+    }
+
+    /// Copies `path` into a new `PathBuf` allocated with `a`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the allocation fails.
+    #[inline]
+    pub fn from_path_in(path: impl AsRef<Path>, a: A) -> Self {
+        handle_reserve_error(Self::try_from_path_in(path, a))
+    }
+
+    /// Same as `from_path_in` but returns errors instead of panicking.
+    #[inline]
+    pub fn try_from_path_in(path: impl AsRef<Path>, a: A) -> Result<Self, TryReserveError> {
+        Ok(Self {
+            inner: OsString::try_from_os_str_in(path.as_ref(), a)?,
+        })
+    }
+
+    /// Coerces to a `Path` slice.
+    #[inline]
+    pub fn as_path(&self) -> &Path {
+        Path::new(self.inner.as_os_str())
+    }
+
+    /// Extends `self` with `path`.
+    ///
+    /// If `path` is absolute, it replaces the current path. Otherwise it is appended after a
+    /// separator.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the reallocation fails.
+    #[inline]
+    pub fn push(&mut self, path: impl AsRef<Path>) {
+        handle_reserve_error(self.try_push(path))
+    }
+
+    /// Same as `push` but returns errors instead of panicking.
+    ///
+    /// The path is left unchanged if an error is returned.
+    pub fn try_push(&mut self, path: impl AsRef<Path>) -> Result<(), TryReserveError> {
+        let bytes = path.as_ref().as_os_str().as_bytes();
+        let buf = self.inner.as_mut_vec();
+        if path.as_ref().has_root() {
+            buf.try_reserve(bytes.len().saturating_sub(buf.len()))?;
+            buf.clear();
+        } else {
+            let need_sep = buf.last().map_or(false, |&c| c != b'/');
+            buf.try_reserve(bytes.len() + need_sep as usize)?;
+            if need_sep {
+                buf.try_push(b'/')?;
+            }
+        }
+        buf.try_extend_from_slice(bytes)
+    }
+
+    /// Truncates `self` to its parent.
+    ///
+    /// Returns `false` and does nothing if there is no parent.
+    pub fn pop(&mut self) -> bool {
+        match self.as_path().parent().map(|p| p.as_os_str().len()) {
+            Some(len) => {
+                self.inner.as_mut_vec().truncate(len);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Updates the file name of `self` to `file_name`, see `std::path::PathBuf::set_file_name`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the reallocation fails.
+    #[inline]
+    pub fn set_file_name(&mut self, file_name: impl AsRef<OsStr>) {
+        handle_reserve_error(self.try_set_file_name(file_name))
+    }
+
+    /// Same as `set_file_name` but returns errors instead of panicking.
+    ///
+    /// The path is left unchanged if an error is returned.
+    pub fn try_set_file_name(
+        &mut self,
+        file_name: impl AsRef<OsStr>,
+    ) -> Result<(), TryReserveError> {
+        let file_name = file_name.as_ref();
+        // popping only shrinks the path, so this is enough for the push below
+        self.inner.try_reserve(file_name.len() + 1)?;
+        if self.as_path().file_name().is_some() {
+            let popped = self.pop();
+            debug_assert!(popped);
+        }
+        self.try_push(file_name)
+    }
+
+    /// Updates the extension of `self` to `extension`, see
+    /// `std::path::PathBuf::set_extension`.
+    ///
+    /// Returns `false` and does nothing if there is no file name.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the reallocation fails.
+    #[inline]
+    pub fn set_extension(&mut self, extension: impl AsRef<OsStr>) -> bool {
+        handle_reserve_error(self.try_set_extension(extension))
+    }
+
+    /// Same as `set_extension` but returns errors instead of panicking.
+    ///
+    /// The path is left unchanged if an error is returned.
+    pub fn try_set_extension(
+        &mut self,
+        extension: impl AsRef<OsStr>,
+    ) -> Result<bool, TryReserveError> {
+        let end_file_stem = match self.as_path().file_stem() {
+            None => return Ok(false),
+            Some(f) => {
+                let f = f.as_bytes();
+                f[f.len()..].as_ptr() as usize
+            }
+        };
+        let start = self.inner.as_bytes().as_ptr() as usize;
+        let new = extension.as_ref().as_bytes();
+
+        let buf = self.inner.as_mut_vec();
+        if !new.is_empty() {
+            buf.try_reserve(new.len() + 1)?;
+        }
+        buf.truncate(end_file_stem.wrapping_sub(start));
+        if !new.is_empty() {
+            buf.try_push(b'.')?;
+            buf.try_extend_from_slice(new)?;
+        }
+        Ok(true)
+    }
+
+    /// Creates an owned `PathBuf` with `path` adjoined to `self`, allocated with a clone of
+    /// the allocator of `self`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the allocation fails.
+    #[inline]
+    pub fn join(&self, path: impl AsRef<Path>) -> Self
+    where
+        A: Clone,
+    {
+        handle_reserve_error(self.try_join(path))
+    }
+
+    /// Same as `join` but returns errors instead of panicking.
+    pub fn try_join(&self, path: impl AsRef<Path>) -> Result<Self, TryReserveError>
+    where
+        A: Clone,
+    {
+        let mut buf = self.try_clone_in(self.alloc_ref().clone())?;
+        buf.try_push(path)?;
+        Ok(buf)
+    }
+
+    /// Consumes the `PathBuf`, yielding its internal `OsString` storage.
+    #[inline]
+    pub fn into_os_string(self) -> OsString<A> {
+        self.inner
+    }
+
+    /// Returns the capacity of the underlying `OsString`.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.inner.capacity()
+    }
+
+    /// Truncates the path to zero length.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.inner.clear()
+    }
+
+    /// Reserves capacity for at least `additional` more bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity overflows `usize` or if the allocation fails.
+    #[inline]
+    pub fn reserve(&mut self, additional: usize) {
+        self.inner.reserve(additional)
+    }
+
+    /// Same as `reserve` but returns errors instead of panicking.
+    #[inline]
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.inner.try_reserve(additional)
+    }
+
+    /// Returns a shared reference to the allocator backing this `PathBuf`.
+    #[inline]
+    pub fn alloc_ref(&self) -> &A {
+        self.inner.alloc_ref()
+    }
+}
+
+impl<A: AllocRef> ops::Deref for PathBuf<A> {
+    type Target = Path;
+
+    #[inline]
+    fn deref(&self) -> &Path {
+        self.as_path()
+    }
+}
+
+impl<A: AllocRef> AsRef<Path> for PathBuf<A> {
+    #[inline]
+    fn as_ref(&self) -> &Path {
+        self
+    }
+}
+
+impl<A: AllocRef> AsRef<OsStr> for PathBuf<A> {
+    #[inline]
+    fn as_ref(&self) -> &OsStr {
+        self.inner.as_os_str()
+    }
+}
+
+impl<A: AllocRef> Borrow<Path> for PathBuf<A> {
+    #[inline]
+    fn borrow(&self) -> &Path {
+        self
+    }
+}
+
+impl<A: AllocRef + Clone> Clone for PathBuf<A> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+#[allow(clippy::use_self)]
+impl<A: AllocRef, B: AllocRef> CloneIn<B> for PathBuf<A> {
+    type Cloned = PathBuf<B>;
+
+    fn clone_in(&self, a: B) -> Self::Cloned {
+        PathBuf {
+            inner: self.inner.clone_in(a),
+        }
+    }
+
+    fn try_clone_in(&self, a: B) -> Result<Self::Cloned, TryReserveError> {
+        Ok(PathBuf {
+            inner: self.inner.try_clone_in(a)?,
+        })
+    }
+}
+
+impl<A: AllocRef + Default> Default for PathBuf<A> {
+    #[inline]
+    fn default() -> Self {
+        Self::new_in(A::default())
+    }
+}
+
+impl<A: AllocRef> fmt::Debug for PathBuf<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_path(), f)
+    }
+}
+
+impl<A: AllocRef, B: AllocRef> PartialEq<PathBuf<B>> for PathBuf<A> {
+    #[inline]
+    fn eq(&self, other: &PathBuf<B>) -> bool {
+        self.as_path() == other.as_path()
+    }
+}
+
+impl<A: AllocRef> PartialEq<Path> for PathBuf<A> {
+    #[inline]
+    fn eq(&self, other: &Path) -> bool {
+        self.as_path() == other
+    }
+}
+
+impl<A: AllocRef> Eq for PathBuf<A> {}
+
+impl<A: AllocRef> PartialOrd for PathBuf<A> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.as_path().partial_cmp(other.as_path())
+    }
+}
+
+impl<A: AllocRef> Ord for PathBuf<A> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_path().cmp(other.as_path())
+    }
+}
+
+impl<A: AllocRef> Hash for PathBuf<A> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_path().hash(state)
+    }
+}
+
+impl<A: AllocRef> From<OsString<A>> for PathBuf<A> {
+    /// Converts an `OsString` into a `PathBuf` without copying.
+    #[inline]
+    fn from(s: OsString<A>) -> Self {
+        Self { inner: s }
+    }
+}
+
+impl<A: AllocRef> From<String<A>> for PathBuf<A> {
+    /// Converts a `String` into a `PathBuf` without copying.
+    #[inline]
+    fn from(s: String<A>) -> Self {
+        Self {
+            inner: OsString::from(s),
+        }
+    }
+}
+
+impl<A: AllocRef> From<PathBuf<A>> for OsString<A> {
+    #[inline]
+    fn from(path: PathBuf<A>) -> Self {
+        path.inner
+    }
+}
@@ -3,6 +3,7 @@
 use core::{
     alloc::LayoutErr,
     cmp,
+    marker::PhantomData,
     mem::{self, ManuallyDrop, MaybeUninit},
     ops::Drop,
     ptr::{self, NonNull, slice_from_raw_parts_mut, Unique},
@@ -10,9 +11,11 @@ use core::{
 };
 
 use crate::{
-    alloc::{handle_alloc_error, AllocRef, Global, Layout},
+    alloc::{AllocRef, Global, Layout, SpecAdopt, SpecReallocInPlace},
     boxed::Box,
-    collections::TryReserveError::{self, AllocError, CapacityOverflow},
+    collections::TryReserveError::{self, CapacityOverflow},
+    handle_reserve_error,
+    vec::{Doubling, GrowthPolicy},
 };
 
 #[derive(Copy, Clone)]
@@ -45,11 +48,14 @@ enum AllocInit {
 /// Note that the excess of a zero-sized types is always infinite, so `capacity()` always returns
 /// `usize::MAX`. This means that you need to be careful when round-tripping this type with a
 /// `Box<[T]>`, since `capacity()` won't yield the length.
+///
+/// Amortized growth and shrinking is decided by the [`GrowthPolicy`] `G`.
 #[allow(missing_debug_implementations)]
-pub struct RawVec<T, A: AllocRef = Global> {
+pub struct RawVec<T, A: AllocRef = Global, G: GrowthPolicy = Doubling> {
     ptr: Unique<T>,
     cap: usize,
     alloc: A,
+    policy: PhantomData<G>,
 }
 
 impl<T> RawVec<T, Global> {
@@ -118,7 +124,7 @@ impl<T> RawVec<T, Global> {
     }
 }
 
-impl<T, A: AllocRef> RawVec<T, A> {
+impl<T, A: AllocRef, G: GrowthPolicy> RawVec<T, A, G> {
     /// Like `new`, but parameterized over the choice of allocator for
     /// the returned `RawVec`.
     pub const fn new_in(alloc: A) -> Self {
@@ -127,14 +133,17 @@ impl<T, A: AllocRef> RawVec<T, A> {
             ptr: Unique::dangling(),
             cap: 0,
             alloc,
+            policy: PhantomData,
         }
     }
 
+    /// Like `with_capacity_in`, but returns an error instead of panicking or aborting.
     #[inline]
     pub fn try_with_capacity_in(capacity: usize, alloc: A) -> Result<Self, TryReserveError> {
         Self::allocate_in(capacity, AllocInit::Uninitialized, alloc)
     }
 
+    /// Like `with_capacity_zeroed_in`, but returns an error instead of panicking or aborting.
     #[inline]
     pub fn try_with_capacity_zeroed_in(capacity: usize, alloc: A) -> Result<Self, TryReserveError> {
         Self::allocate_in(capacity, AllocInit::Zeroed, alloc)
@@ -144,22 +153,14 @@ impl<T, A: AllocRef> RawVec<T, A> {
     /// allocator for the returned `RawVec`.
     #[inline]
     pub fn with_capacity_in(capacity: usize, alloc: A) -> Self {
-        match Self::try_with_capacity_in(capacity, alloc) {
-            Err(CapacityOverflow) => capacity_overflow(),
-            Err(AllocError { layout, .. }) => handle_alloc_error(layout),
-            Ok(v) => v,
-        }
+        handle_reserve_error(Self::try_with_capacity_in(capacity, alloc))
     }
 
     /// Like `with_capacity_zeroed`, but parameterized over the choice
     /// of allocator for the returned `RawVec`.
     #[inline]
     pub fn with_capacity_zeroed_in(capacity: usize, alloc: A) -> Self {
-        match Self::try_with_capacity_zeroed_in(capacity, alloc) {
-            Err(CapacityOverflow) => capacity_overflow(),
-            Err(AllocError { layout, .. }) => handle_alloc_error(layout),
-            Ok(v) => v,
-        }
+        handle_reserve_error(Self::try_with_capacity_zeroed_in(capacity, alloc))
     }
 
     fn allocate_in(
@@ -177,11 +178,16 @@ impl<T, A: AllocRef> RawVec<T, A> {
                 AllocInit::Uninitialized => alloc.alloc(layout),
                 AllocInit::Zeroed => alloc.alloc_zeroed(layout),
             }
-            .map_err(|_| AllocError { layout })?;
+            .map_err(|err| {
+                TryReserveError::alloc_error(layout)
+                    .with_capacities(0, capacity)
+                    .with_cause(err)
+            })?;
             Ok(Self {
                 ptr: Unique::new(memory.as_non_null_ptr().cast().as_ptr()).unwrap(),
                 cap: Self::capacity_from_bytes(memory.len()),
                 alloc,
+                policy: PhantomData,
             })
         }
     }
@@ -202,6 +208,7 @@ impl<T, A: AllocRef> RawVec<T, A> {
             ptr: Unique::new_unchecked(ptr),
             cap: capacity,
             alloc,
+            policy: PhantomData,
         }
     }
 
@@ -212,6 +219,20 @@ impl<T, A: AllocRef> RawVec<T, A> {
             Self::from_raw_parts_in(ptr.as_mut_ptr(), ptr.len(), alloc)
         }
     }
+}
+
+impl<T, A: AllocRef, G: GrowthPolicy> RawVec<T, A, G> {
+    /// Switches to the growth policy `H`, keeping the buffer.
+    #[inline]
+    pub fn with_policy<H: GrowthPolicy>(self) -> RawVec<T, A, H> {
+        let me = ManuallyDrop::new(self);
+        RawVec {
+            ptr: me.ptr,
+            cap: me.cap,
+            alloc: unsafe { ptr::read(&me.alloc) },
+            policy: PhantomData,
+        }
+    }
 
     /// Converts the entire buffer into `Box<[MaybeUninit<T>]>` with the specified `len`.
     ///
@@ -332,11 +353,7 @@ impl<T, A: AllocRef> RawVec<T, A> {
     /// # }
     /// ```
     pub fn reserve(&mut self, len: usize, additional: usize) {
-        match self.try_reserve(len, additional) {
-            Err(CapacityOverflow) => capacity_overflow(),
-            Err(AllocError { layout, .. }) => handle_alloc_error(layout),
-            Ok(()) => { /* yay */ }
-        }
+        handle_reserve_error(self.try_reserve(len, additional));
     }
 
     /// The same as `reserve`, but returns on errors instead of panicking or aborting.
@@ -366,11 +383,7 @@ impl<T, A: AllocRef> RawVec<T, A> {
     ///
     /// Aborts on OOM.
     pub fn reserve_exact(&mut self, len: usize, additional: usize) {
-        match self.try_reserve_exact(len, additional) {
-            Err(CapacityOverflow) => capacity_overflow(),
-            Err(AllocError { layout, .. }) => handle_alloc_error(layout),
-            Ok(()) => { /* yay */ }
-        }
+        handle_reserve_error(self.try_reserve_exact(len, additional));
     }
 
     /// The same as `reserve_exact`, but returns on errors instead of panicking or aborting.
@@ -386,10 +399,66 @@ impl<T, A: AllocRef> RawVec<T, A> {
         }
     }
 
+    /// Ensures that the buffer contains at least enough space to hold `len + additional`
+    /// elements without moving it.
+    ///
+    /// This only succeeds if the buffer already has enough capacity, or if the allocator
+    /// implements [`ReallocInPlace`] and can extend the block at its current address. An
+    /// unallocated buffer cannot grow in place.
+    ///
+    /// [`ReallocInPlace`]: crate::alloc::ReallocInPlace
+    pub fn try_grow_in_place(
+        &mut self,
+        len: usize,
+        additional: usize,
+    ) -> Result<(), TryReserveError> {
+        if !self.needs_to_grow(len, additional) {
+            return Ok(());
+        }
+        if mem::size_of::<T>() == 0 {
+            // Since we return a capacity of `usize::MAX` when the type size is
+            // 0, getting to here necessarily means the `RawVec` is overfull.
+            return Err(CapacityOverflow);
+        }
+
+        let cap = len.checked_add(additional).ok_or(CapacityOverflow)?;
+        let new_layout = Layout::array::<T>(cap)?;
+        alloc_guard(new_layout.size())?;
+
+        let old_cap = self.cap;
+        let error = || TryReserveError::alloc_error(new_layout).with_capacities(old_cap, cap);
+        let (ptr, layout) = self.current_memory().ok_or_else(error)?;
+        let memory = unsafe {
+            self.alloc
+                .spec_grow_in_place(ptr, layout, new_layout)
+                .map_err(|err| error().with_cause(err))?
+        };
+        debug_assert_eq!(memory.as_mut_ptr(), ptr.as_ptr());
+        self.set_ptr(memory);
+        Ok(())
+    }
+
     pub fn try_shrink_to_fit(&mut self, amount: usize) -> Result<(), TryReserveError> {
         self.shrink(amount)
     }
 
+    /// Shrinks the allocation to the capacity the growth policy picks for holding at least
+    /// `min_capacity` elements.
+    ///
+    /// The allocation is left unchanged if `min_capacity` is *larger* than the current capacity.
+    pub fn try_shrink_amortized(&mut self, min_capacity: usize) -> Result<(), TryReserveError> {
+        if mem::size_of::<T>() == 0 {
+            return Ok(());
+        }
+        let cap = G::shrink(self.cap, min_capacity, mem::size_of::<T>());
+        let cap = cmp::min(cmp::max(cap, min_capacity), self.cap);
+        if cap == self.cap {
+            Ok(())
+        } else {
+            self.shrink(cap)
+        }
+    }
+
     /// Shrinks the allocation down to the specified amount. If the given amount
     /// is 0, actually completely deallocates.
     ///
@@ -401,11 +470,48 @@ impl<T, A: AllocRef> RawVec<T, A> {
     ///
     /// Aborts on OOM.
     pub fn shrink_to_fit(&mut self, amount: usize) {
-        match self.try_shrink_to_fit(amount) {
-            Err(CapacityOverflow) => capacity_overflow(),
-            Err(AllocError { layout, .. }) => handle_alloc_error(layout),
-            Ok(()) => { /* yay */ }
-        }
+        handle_reserve_error(self.try_shrink_to_fit(amount));
+    }
+
+    /// Moves the buffer into `alloc`, leaving `self` unallocated.
+    ///
+    /// If `alloc` can adopt memory from `A` (see [`AdoptAlloc`]), the allocation is handed over
+    /// and the capacity is kept. Otherwise, a buffer with a capacity of `len` is allocated in
+    /// `alloc`, the first `len` elements are copied bitwise and the old buffer is freed with `A`.
+    /// On error, `self` is unchanged.
+    ///
+    /// # Safety
+    ///
+    /// `len` must be smaller than or equal to `self.capacity()`.
+    ///
+    /// [`AdoptAlloc`]: crate::alloc::AdoptAlloc
+    pub unsafe fn try_transfer_in<B: AllocRef>(
+        &mut self,
+        len: usize,
+        alloc: B,
+    ) -> Result<RawVec<T, B, G>, TryReserveError> {
+        debug_assert!(
+            len <= self.capacity(),
+            "`len` must be smaller than or equal to `self.capacity()`"
+        );
+
+        let new: RawVec<T, B, G> = if let Some((ptr, layout)) = self.current_memory() {
+            if let Ok(memory) = alloc.spec_adopt(ptr, layout, &self.alloc) {
+                let mut new = RawVec::new_in(alloc);
+                new.set_ptr(memory);
+                new
+            } else {
+                let new = RawVec::try_with_capacity_in(len, alloc)?;
+                ptr::copy_nonoverlapping(self.ptr(), new.ptr(), len);
+                self.alloc.dealloc(ptr, layout);
+                new
+            }
+        } else {
+            RawVec::new_in(alloc)
+        };
+        self.ptr = Unique::dangling();
+        self.cap = 0;
+        Ok(new)
     }
 
     pub fn leak(self) -> (Option<NonNull<[u8]>>, A) {
@@ -424,7 +530,7 @@ impl<T, A: AllocRef> RawVec<T, A> {
     }
 }
 
-impl<T, A: AllocRef> RawVec<T, A> {
+impl<T, A: AllocRef, G: GrowthPolicy> RawVec<T, A, G> {
     /// Returns if the buffer needs to grow to fulfill the needed extra capacity.
     /// Mainly used to make inlining reserve-calls possible without inlining `grow`.
     fn needs_to_grow(&self, len: usize, additional: usize) -> bool {
@@ -461,30 +567,14 @@ impl<T, A: AllocRef> RawVec<T, A> {
         // Nothing we can really do about these checks, sadly.
         let required_cap = len.checked_add(additional).ok_or(CapacityOverflow)?;
 
-        // This guarantees exponential growth. The doubling cannot overflow
-        // because `cap <= isize::MAX` and the type of `cap` is `usize`.
-        let cap = cmp::max(self.cap * 2, required_cap);
-
-        // Tiny Vecs are dumb. Skip to:
-        // - 8 if the element size is 1, because any heap allocators is likely
-        //   to round up a request of less than 8 bytes to at least 8 bytes.
-        // - 4 if elements are moderate-sized (<= 1 KiB).
-        // - 1 otherwise, to avoid wasting too much space for very short Vecs.
-        // Note that `min_non_zero_cap` is computed statically.
-        let elem_size = mem::size_of::<T>();
-        let min_non_zero_cap = if elem_size == 1 {
-            8
-        } else if elem_size <= 1024 {
-            4
-        } else {
-            1
-        };
-        let cap = cmp::max(min_non_zero_cap, cap);
-
+        // The growth policy picks the new capacity, which never drops below the required one.
+        let cap = G::grow(self.cap, required_cap, mem::size_of::<T>());
+        let cap = cmp::max(cap, required_cap);
         let new_layout = Layout::array::<T>(cap);
 
         // `finish_grow` is non-generic over `T`.
-        let ptr = finish_grow(new_layout, self.current_memory(), &mut self.alloc)?;
+        let ptr = finish_grow(new_layout, self.current_memory(), &mut self.alloc)
+            .map_err(|err| err.with_capacities(self.cap, cap))?;
         self.set_ptr(ptr);
         Ok(())
     }
@@ -503,7 +593,8 @@ impl<T, A: AllocRef> RawVec<T, A> {
         let new_layout = Layout::array::<T>(cap);
 
         // `finish_grow` is non-generic over `T`.
-        let ptr = finish_grow(new_layout, self.current_memory(), &mut self.alloc)?;
+        let ptr = finish_grow(new_layout, self.current_memory(), &mut self.alloc)
+            .map_err(|err| err.with_capacities(self.cap, cap))?;
         self.set_ptr(ptr);
         Ok(())
     }
@@ -522,9 +613,11 @@ impl<T, A: AllocRef> RawVec<T, A> {
         let ptr = unsafe {
             let new_size = amount * mem::size_of::<T>();
             let new_layout = Layout::from_size_align_unchecked(new_size, layout.align());
-            self.alloc
-                .shrink(ptr, layout, new_layout)
-                .map_err(|_| AllocError { layout: new_layout })?
+            self.alloc.shrink(ptr, layout, new_layout).map_err(|err| {
+                TryReserveError::alloc_error(new_layout)
+                    .with_capacities(self.cap, amount)
+                    .with_cause(err)
+            })?
         };
         self.set_ptr(ptr);
         Ok(())
@@ -544,7 +637,7 @@ where
     A: AllocRef,
 {
     // Check for the error here to minimize the size of `RawVec::grow_*`.
-    let new_layout = new_layout.map_err(|_| CapacityOverflow)?;
+    let new_layout = new_layout?;
 
     alloc_guard(new_layout.size())?;
 
@@ -558,12 +651,12 @@ where
     } else {
         alloc.alloc(new_layout)
     }
-    .map_err(|_| AllocError { layout: new_layout })?;
+    .map_err(|err| TryReserveError::alloc_error(new_layout).with_cause(err))?;
 
     Ok(memory)
 }
 
-unsafe impl<#[may_dangle] T, A: AllocRef> Drop for RawVec<T, A> {
+unsafe impl<#[may_dangle] T, A: AllocRef, G: GrowthPolicy> Drop for RawVec<T, A, G> {
     /// Frees the memory owned by the `RawVec` *without* trying to drop its contents.
     fn drop(&mut self) {
         if let Some((ptr, layout)) = self.current_memory() {
@@ -589,10 +682,3 @@ fn alloc_guard(alloc_size: usize) -> Result<(), TryReserveError> {
         Ok(())
     }
 }
-
-// One central function responsible for reporting capacity overflows. This'll
-// ensure that the code generation related to these panics is minimal as there's
-// only one location which panics rather than a bunch throughout the module.
-fn capacity_overflow() -> ! {
-    panic!("capacity overflow");
-}
@@ -54,9 +54,9 @@
 use crate::{
     alloc::{AllocRef, Global},
     boxed::Box,
-    capacity_overflow,
-    collections::TryReserveError::{self, AllocError, CapacityOverflow},
-    iter::TryExtend,
+    collections::{TryPushError, TryReserveError},
+    handle_reserve_error,
+    iter::{FromIteratorIn, TryExtend},
     str::from_boxed_utf8_unchecked,
     vec::Vec,
 };
@@ -81,11 +81,15 @@ use core::{
 #[cfg(feature = "std")]
 use std::borrow::Cow;
 
-use crate::{alloc::handle_alloc_error, clone::CloneIn};
+use crate::clone::{CloneIn, TryClone};
 pub use liballoc::string::{ParseError, ToString};
 use std::ptr::NonNull;
 use crate::str::from_utf8_unchecked_mut;
 
+pub mod array_string;
+
+pub use self::array_string::ArrayString;
+
 /// A UTF-8 encoded, growable string.
 ///
 /// The `String` type is the most common string type that has ownership over the
@@ -457,6 +461,30 @@ impl String {
         Self::with_capacity_in(capacity, Global)
     }
 
+    /// Creates a new empty `String` with a particular capacity, with the buffer being filled with
+    /// `0` bytes.
+    ///
+    /// The zeroed bytes are not part of the string, but a `0` byte is a valid UTF-8 code unit, so
+    /// they can be added through [`as_mut_vec`] and [`Vec::set_len`].
+    ///
+    /// [`as_mut_vec`]: String::as_mut_vec()
+    /// [`Vec::set_len`]: crate::vec::Vec::set_len()
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use alloc_wg::string::String;
+    ///
+    /// let mut s = String::with_capacity_zeroed(4);
+    /// unsafe { s.as_mut_vec().set_len(4) };
+    /// assert_eq!(s, "\0\0\0\0");
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn with_capacity_zeroed(capacity: usize) -> Self {
+        Self::with_capacity_zeroed_in(capacity, Global)
+    }
+
     /// Decode a UTF-16 encoded vector `v` into a `String`, returning [`Err`]
     /// if `v` contains any invalid data.
     ///
@@ -601,6 +629,26 @@ impl<A: AllocRef> String<A> {
         })
     }
 
+    /// Like `with_capacity_zeroed` but parameterized over the choice of allocator for the
+    /// returned `String`.
+    ///
+    /// # Panics
+    /// Panics if the allocation fails.
+    #[inline]
+    pub fn with_capacity_zeroed_in(capacity: usize, a: A) -> Self {
+        Self {
+            vec: Vec::with_capacity_zeroed_in(capacity, a),
+        }
+    }
+
+    /// Like `with_capacity_zeroed_in` but returns errors instead of panicking.
+    #[inline]
+    pub fn try_with_capacity_zeroed_in(capacity: usize, a: A) -> Result<Self, TryReserveError> {
+        Ok(Self {
+            vec: Vec::try_with_capacity_zeroed_in(capacity, a)?,
+        })
+    }
+
     /// Like `from_str` but parameterized over the choice of allocator for the returned `String`.
     ///
     /// # Panics
@@ -697,19 +745,21 @@ impl<A: AllocRef> String<A> {
 
     /// Like `from_utf8_lossy` but parameterized over the choice of allocator for the returned `String`.
     ///
+    /// If `v` is valid UTF-8, it is borrowed and `a` is dropped. Otherwise the invalid sequences
+    /// are replaced and the result is allocated with `a`.
+    ///
     /// # Panics
     ///
     /// Panics if allocation fails.
-    pub fn from_utf8_lossy_in(v: &[u8], a: A) -> Self {
-        match Self::try_from_utf8_lossy_in(v, a) {
-            Err(CapacityOverflow) => capacity_overflow(),
-            Err(AllocError { layout, .. }) => handle_alloc_error(layout),
-            Ok(string) => string,
-        }
+    pub fn from_utf8_lossy_in(v: &[u8], a: A) -> crate::borrow::Cow<'_, str, A> {
+        handle_reserve_error(Self::try_from_utf8_lossy_in(v, a))
     }
 
     /// Like `from_utf8_lossy_in` but returns errors instead of panicking.
-    pub fn try_from_utf8_lossy_in(v: &[u8], a: A) -> Result<Self, TryReserveError> {
+    pub fn try_from_utf8_lossy_in(
+        v: &[u8],
+        a: A,
+    ) -> Result<crate::borrow::Cow<'_, str, A>, TryReserveError> {
         const REPLACEMENT: &str = "\u{FFFD}";
 
         let mut iter = lossy::Utf8Lossy::from_bytes(v).chunks();
@@ -718,11 +768,11 @@ impl<A: AllocRef> String<A> {
             let lossy::Utf8LossyChunk { valid, broken } = chunk;
             if valid.len() == v.len() {
                 debug_assert!(broken.is_empty());
-                return Self::try_from_str_in(valid, a);
+                return Ok(crate::borrow::Cow::Borrowed(valid));
             }
             (valid, broken)
         } else {
-            return Ok(Self::new_in(a));
+            return Ok(crate::borrow::Cow::Borrowed(""));
         };
 
         let mut res = Self::try_with_capacity_in(v.len(), a)?;
@@ -738,7 +788,7 @@ impl<A: AllocRef> String<A> {
             }
         }
 
-        Ok(res)
+        Ok(crate::borrow::Cow::Owned(res))
     }
 
     /// Like `from_utf16` but parameterized over the choice of allocator for the returned `String`.
@@ -924,6 +974,42 @@ impl<A: AllocRef> String<A> {
         self.vec.try_extend_from_slice(string.as_bytes())
     }
 
+    /// Appends the formatted `args` onto the end of this `String`.
+    ///
+    /// Unlike `fmt::Write::write_fmt`, which can only report a `fmt::Error`, this returns the
+    /// `TryReserveError` which caused the failure.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a formatting trait implementation returns an error on its own.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use alloc_wg::string::String;
+    ///
+    /// let mut s = String::from("x = ");
+    /// s.try_write_fmt(format_args!("{}", 10))?;
+    /// assert_eq!(s, "x = 10");
+    /// # Ok::<(), alloc_wg::collections::TryReserveError>(())
+    /// ```
+    pub fn try_write_fmt(&mut self, args: fmt::Arguments<'_>) -> Result<(), TryReserveError> {
+        let mut writer = TryWriter {
+            string: self,
+            error: None,
+        };
+        let result = fmt::write(&mut writer, args);
+        // A `Display` impl may ignore the error of `write!` and return `Ok(())`, so the recorded
+        // error has to be checked regardless of `result`.
+        if let Some(err) = writer.error {
+            return Err(err);
+        }
+        if result.is_err() {
+            panic!("a formatting trait implementation returned an error");
+        }
+        Ok(())
+    }
+
     /// Returns this `String`'s capacity, in bytes.
     ///
     /// # Examples
@@ -1115,6 +1201,15 @@ impl<A: AllocRef> String<A> {
         self.vec.try_reserve_exact(additional)
     }
 
+    /// Tries to reserve the minimum capacity for exactly `additional` more bytes without moving
+    /// the buffer, see [`Vec::try_reserve_in_place`].
+    ///
+    /// [`Vec::try_reserve_in_place`]: crate::vec::Vec::try_reserve_in_place
+    #[inline]
+    pub fn try_reserve_in_place(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.vec.try_reserve_in_place(additional)
+    }
+
     /// Shrinks the capacity of this `String` to match its length.
     ///
     /// # Examples
@@ -1208,22 +1303,24 @@ impl<A: AllocRef> String<A> {
     /// Panics if the reallocation fails.
     #[inline]
     pub fn push(&mut self, ch: char) {
-        match self.try_push(ch) {
-            Err(CapacityOverflow) => capacity_overflow(),
-            Err(AllocError { layout, .. }) => handle_alloc_error(layout),
-            Ok(()) => { /* yay */ }
-        }
+        handle_reserve_error(self.try_push(ch).map_err(TryPushError::into_error));
     }
 
     /// Like `push` but returns errors instead of panicking.
+    ///
+    /// On failure, `ch` is handed back inside the [`TryPushError`].
     #[inline]
-    pub fn try_push(&mut self, ch: char) -> Result<(), TryReserveError> {
-        match ch.len_utf8() {
-            1 => self.vec.try_push(ch as u8),
+    pub fn try_push(&mut self, ch: char) -> Result<(), TryPushError<char>> {
+        let result = match ch.len_utf8() {
+            1 => self
+                .vec
+                .try_push(ch as u8)
+                .map_err(TryPushError::into_error),
             _ => self
                 .vec
                 .try_extend_from_slice(ch.encode_utf8(&mut [0; 4]).as_bytes()),
-        }
+        };
+        result.map_err(|error| TryPushError::new(ch, error))
     }
 
     /// Returns a byte slice of this `String`'s contents.
@@ -1454,21 +1551,20 @@ impl<A: AllocRef> String<A> {
     /// Panics if reallocation fails.
     #[inline]
     pub fn insert(&mut self, idx: usize, ch: char) {
-        match self.try_insert(idx, ch) {
-            Err(CapacityOverflow) => capacity_overflow(),
-            Err(AllocError { layout, .. }) => handle_alloc_error(layout),
-            Ok(()) => { /* yay */ }
-        }
+        handle_reserve_error(self.try_insert(idx, ch).map_err(TryPushError::into_error));
     }
 
     /// Like `insert` but returns errors instead of panicking.
+    ///
+    /// On failure, `ch` is handed back inside the [`TryPushError`].
     #[inline]
-    pub fn try_insert(&mut self, idx: usize, ch: char) -> Result<(), TryReserveError> {
+    pub fn try_insert(&mut self, idx: usize, ch: char) -> Result<(), TryPushError<char>> {
         assert!(self.is_char_boundary(idx));
         let mut bits = [0; 4];
         let bits = ch.encode_utf8(&mut bits).as_bytes();
 
-        unsafe { self.try_insert_bytes(idx, bits) }
+        let result = unsafe { self.try_insert_bytes(idx, bits) };
+        result.map_err(|error| TryPushError::new(ch, error))
     }
 
     unsafe fn try_insert_bytes(&mut self, idx: usize, bytes: &[u8]) -> Result<(), TryReserveError> {
@@ -1516,11 +1612,7 @@ impl<A: AllocRef> String<A> {
     /// Panics if the reallocation fails.
     #[inline]
     pub fn insert_str(&mut self, idx: usize, string: &str) {
-        match self.try_insert_str(idx, string) {
-            Err(CapacityOverflow) => capacity_overflow(),
-            Err(AllocError { layout, .. }) => handle_alloc_error(layout),
-            Ok(()) => { /* yay */ }
-        }
+        handle_reserve_error(self.try_insert_str(idx, string));
     }
 
     /// Like `insert_str` but returns errors instead of panicking.
@@ -1636,11 +1728,7 @@ impl<A: AllocRef> String<A> {
     where
         A: AllocRef + Clone,
     {
-        match self.try_split_off(at) {
-            Err(CapacityOverflow) => capacity_overflow(),
-            Err(AllocError { layout, .. }) => handle_alloc_error(layout),
-            Ok(split) => split,
-        }
+        handle_reserve_error(self.try_split_off(at))
     }
 
     /// Like `split_off` but returns errors instead of panicking.
@@ -1829,6 +1917,30 @@ impl<A: AllocRef> String<A> {
         unsafe { Ok(from_boxed_utf8_unchecked(slice)) }
     }
 
+    /// Moves the string into the allocator `alloc`, see [`Vec::transfer_in`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use alloc_wg::{alloc::Global, string::String};
+    ///
+    /// let s = String::from("hello");
+    /// assert_eq!(s.transfer_in(Global)?, "hello");
+    /// # Ok::<(), alloc_wg::collections::TryReserveError>(())
+    /// ```
+    ///
+    /// [`Vec::transfer_in`]: crate::vec::Vec::transfer_in
+    #[inline]
+    pub fn transfer_in<B: AllocRef>(self, alloc: B) -> Result<String<B>, TryPushError<Self>> {
+        match self.vec.transfer_in(alloc) {
+            Ok(vec) => Ok(String { vec }),
+            Err(err) => {
+                let (vec, err) = err.into_parts();
+                Err(TryPushError::new(Self { vec }, err))
+            }
+        }
+    }
+
     pub fn leak_alloc<'a>(self) -> (Option<NonNull<str>>, &'a mut str, A)
         where str: 'a,
     {
@@ -1947,6 +2059,18 @@ where
     }
 }
 
+impl<A> TryClone for String<A>
+where
+    A: AllocRef + Clone,
+{
+    #[inline]
+    fn try_clone(&self) -> Result<Self, TryReserveError> {
+        Ok(Self {
+            vec: self.vec.try_clone()?,
+        })
+    }
+}
+
 #[allow(clippy::use_self)]
 impl<A: AllocRef, B: AllocRef> CloneIn<B> for String<A> {
     type Cloned = String<B>;
@@ -1967,6 +2091,23 @@ impl<A: AllocRef, B: AllocRef> CloneIn<B> for String<A> {
     }
 }
 
+/// Collects anything a `String<A>` can be extended with, i.e. `char`s, `&char`s, `&str`s and
+/// `String`s in any allocator.
+impl<T, A: AllocRef> FromIteratorIn<T, A> for String<A>
+where
+    Self: TryExtend<T, Err = TryReserveError>,
+{
+    fn from_iter_in<I: IntoIterator<Item = T>>(iter: I, a: A) -> Self {
+        handle_reserve_error(Self::try_from_iter_in(iter, a))
+    }
+
+    fn try_from_iter_in<I: IntoIterator<Item = T>>(iter: I, a: A) -> Result<Self, TryReserveError> {
+        let mut buf = Self::new_in(a);
+        buf.try_extend(iter)?;
+        Ok(buf)
+    }
+}
+
 impl FromIterator<char> for String {
     fn from_iter<I: IntoIterator<Item = char>>(iter: I) -> Self {
         let mut buf = Self::new();
@@ -2498,6 +2639,37 @@ impl<A: AllocRef> fmt::Write for String<A> {
     }
 }
 
+/// A `fmt::Write` adapter which records the `TryReserveError` when pushing onto the `String`
+/// fails, as `fmt::Error` can't carry it.
+struct TryWriter<'s, A: AllocRef> {
+    string: &'s mut String<A>,
+    error: Option<TryReserveError>,
+}
+
+impl<A: AllocRef> fmt::Write for TryWriter<'_, A> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.string.try_push_str(s).map_err(|err| {
+            self.error = Some(err);
+            fmt::Error
+        })
+    }
+}
+
+#[doc(hidden)]
+pub fn format_in<A: AllocRef>(args: fmt::Arguments<'_>, a: A) -> String<A> {
+    crate::handle_reserve_error(try_format_in(args, a))
+}
+
+#[doc(hidden)]
+pub fn try_format_in<A: AllocRef>(
+    args: fmt::Arguments<'_>,
+    a: A,
+) -> Result<String<A>, TryReserveError> {
+    let mut string = String::new_in(a);
+    string.try_write_fmt(args)?;
+    Ok(string)
+}
+
 /// A draining iterator for `String`.
 ///
 /// This struct is created by the [`drain`] method on [`String`]. See its
@@ -0,0 +1,666 @@
+//! A UTF-8 encoded string with a fixed capacity which never allocates, written
+//! `ArrayString<N>`.
+//!
+//! # Examples
+//!
+//! ```
+//! use alloc_wg::string::ArrayString;
+//! use core::fmt::Write;
+//!
+//! let mut s = ArrayString::<8>::new();
+//! write!(s, "{}-{}", 1, 2).unwrap();
+//! assert_eq!(s, "1-2");
+//!
+//! let err = s.try_push_str("too long").unwrap_err();
+//! assert_eq!(err.into_element(), "too long");
+//! ```
+
+use super::String;
+use crate::{
+    alloc::AllocRef,
+    collections::{CapacityError, TryReserveError},
+    handle_reserve_error,
+    iter::TryExtend,
+    vec::ArrayVec,
+};
+use core::{
+    cmp::Ordering,
+    convert::TryFrom,
+    fmt,
+    hash,
+    iter::FusedIterator,
+    ops::{
+        self,
+        Bound::{Excluded, Included, Unbounded},
+        RangeBounds,
+    },
+    ptr,
+    str::{self, Chars},
+};
+
+/// A UTF-8 encoded string with a fixed capacity of `N` bytes.
+///
+/// `ArrayString` mirrors the API of [`String`], but stores its contents inline. Fallible
+/// methods return a [`CapacityError`] holding the rejected `char` or `&str`.
+///
+/// [`String`]: crate::string::String
+/// [`CapacityError`]: crate::collections::CapacityError
+pub struct ArrayString<const N: usize> {
+    vec: ArrayVec<u8, N>,
+}
+
+impl<const N: usize> ArrayString<N> {
+    /// Creates a new empty `ArrayString`.
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            vec: ArrayVec::new(),
+        }
+    }
+
+    /// Creates a new `ArrayString` from a string slice, or returns it back if it does not fit.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use alloc_wg::string::ArrayString;
+    ///
+    /// assert_eq!(ArrayString::<5>::try_from_str("hello").unwrap(), "hello");
+    /// assert!(ArrayString::<4>::try_from_str("hello").is_err());
+    /// ```
+    pub fn try_from_str(s: &str) -> Result<Self, CapacityError<&str>> {
+        let mut string = Self::new();
+        string.try_push_str(s)?;
+        Ok(string)
+    }
+
+    /// Returns this `ArrayString`'s capacity in bytes, which is always `N`.
+    #[inline]
+    #[must_use]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Returns the number of bytes which can still be pushed onto the string.
+    #[inline]
+    #[must_use]
+    pub fn remaining_capacity(&self) -> usize {
+        self.vec.remaining_capacity()
+    }
+
+    /// Returns the length of this `ArrayString`, in bytes.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.vec.len()
+    }
+
+    /// Returns `true` if this `ArrayString` has a length of zero.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.vec.is_empty()
+    }
+
+    /// Returns `true` if no more bytes fit into this `ArrayString`.
+    #[inline]
+    #[must_use]
+    pub fn is_full(&self) -> bool {
+        self.vec.is_full()
+    }
+
+    /// Extracts a string slice containing the entire `ArrayString`.
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        self
+    }
+
+    /// Converts an `ArrayString` into a mutable string slice.
+    #[inline]
+    pub fn as_mut_str(&mut self) -> &mut str {
+        self
+    }
+
+    /// Returns a byte slice of this `ArrayString`'s contents.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.vec
+    }
+
+    /// Appends the given `char` to the end of this `ArrayString`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `char` does not fit.
+    #[inline]
+    pub fn push(&mut self, ch: char) {
+        if let Err(err) = self.try_push(ch) {
+            panic!("{}", err)
+        }
+    }
+
+    /// Like `push` but hands back the `char` instead of panicking.
+    #[inline]
+    pub fn try_push(&mut self, ch: char) -> Result<(), CapacityError<char>> {
+        match ch.len_utf8() {
+            1 => self
+                .vec
+                .try_push(ch as u8)
+                .map_err(|_| CapacityError::new(ch)),
+            _ => self
+                .vec
+                .try_extend_from_slice(ch.encode_utf8(&mut [0; 4]).as_bytes())
+                .map_err(|_| CapacityError::new(ch)),
+        }
+    }
+
+    /// Appends a given string slice onto the end of this `ArrayString`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the string slice does not fit.
+    #[inline]
+    pub fn push_str(&mut self, string: &str) {
+        if let Err(err) = self.try_push_str(string) {
+            panic!("{}", err)
+        }
+    }
+
+    /// Like `push_str` but hands back the string slice instead of panicking.
+    ///
+    /// Nothing is appended if `string` does not fit completely.
+    #[inline]
+    pub fn try_push_str<'a>(&mut self, string: &'a str) -> Result<(), CapacityError<&'a str>> {
+        self.vec
+            .try_extend_from_slice(string.as_bytes())
+            .map_err(|_| CapacityError::new(string))
+    }
+
+    /// Shortens this `ArrayString` to the specified length.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `new_len` does not lie on a [`char`] boundary.
+    #[inline]
+    pub fn truncate(&mut self, new_len: usize) {
+        if new_len <= self.len() {
+            assert!(self.is_char_boundary(new_len));
+            self.vec.truncate(new_len)
+        }
+    }
+
+    /// Removes the last character from the string buffer and returns it.
+    #[inline]
+    pub fn pop(&mut self) -> Option<char> {
+        let ch = self.chars().rev().next()?;
+        let newlen = self.len() - ch.len_utf8();
+        unsafe {
+            self.vec.set_len(newlen);
+        }
+        Some(ch)
+    }
+
+    /// Removes a [`char`] from this `ArrayString` at a byte position and returns it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is larger than or equal to the `ArrayString`'s length,
+    /// or if it does not lie on a [`char`] boundary.
+    #[inline]
+    pub fn remove(&mut self, idx: usize) -> char {
+        let ch = match self[idx..].chars().next() {
+            Some(ch) => ch,
+            None => panic!("cannot remove a char from the end of a string"),
+        };
+
+        let next = idx + ch.len_utf8();
+        let len = self.len();
+        unsafe {
+            ptr::copy(
+                self.vec.as_ptr().add(next),
+                self.vec.as_mut_ptr().add(idx),
+                len - next,
+            );
+            self.vec.set_len(len - (next - idx));
+        }
+        ch
+    }
+
+    /// Retains only the characters specified by the predicate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use alloc_wg::string::ArrayString;
+    ///
+    /// let mut s = ArrayString::<16>::try_from_str("f_o_ob_ar").unwrap();
+    /// s.retain(|c| c != '_');
+    /// assert_eq!(s, "foobar");
+    /// ```
+    #[inline]
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(char) -> bool,
+    {
+        let len = self.len();
+        let mut del_bytes = 0;
+        let mut idx = 0;
+
+        while idx < len {
+            let ch = unsafe { self.get_unchecked(idx..len).chars().next().unwrap() };
+            let ch_len = ch.len_utf8();
+
+            if !f(ch) {
+                del_bytes += ch_len;
+            } else if del_bytes > 0 {
+                unsafe {
+                    ptr::copy(
+                        self.vec.as_ptr().add(idx),
+                        self.vec.as_mut_ptr().add(idx - del_bytes),
+                        ch_len,
+                    );
+                }
+            }
+
+            // Point idx to the next char
+            idx += ch_len;
+        }
+
+        if del_bytes > 0 {
+            unsafe {
+                self.vec.set_len(len - del_bytes);
+            }
+        }
+    }
+
+    /// Inserts a character into this `ArrayString` at a byte position.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is larger than the `ArrayString`'s length, if it does not lie on a
+    /// [`char`] boundary, or if the character does not fit.
+    #[inline]
+    pub fn insert(&mut self, idx: usize, ch: char) {
+        if let Err(err) = self.try_insert(idx, ch) {
+            panic!("{}", err)
+        }
+    }
+
+    /// Like `insert` but hands back the `char` instead of panicking if it does not fit.
+    #[inline]
+    pub fn try_insert(&mut self, idx: usize, ch: char) -> Result<(), CapacityError<char>> {
+        assert!(self.is_char_boundary(idx));
+        let mut bits = [0; 4];
+        let bits = ch.encode_utf8(&mut bits).as_bytes();
+
+        unsafe { self.try_insert_bytes(idx, bits) }.map_err(|_| CapacityError::new(ch))
+    }
+
+    unsafe fn try_insert_bytes(&mut self, idx: usize, bytes: &[u8]) -> Result<(), CapacityError> {
+        let len = self.len();
+        let amt = bytes.len();
+        if amt > self.remaining_capacity() {
+            return Err(CapacityError::new(()));
+        }
+
+        ptr::copy(
+            self.vec.as_ptr().add(idx),
+            self.vec.as_mut_ptr().add(idx + amt),
+            len - idx,
+        );
+        ptr::copy(bytes.as_ptr(), self.vec.as_mut_ptr().add(idx), amt);
+        self.vec.set_len(len + amt);
+        Ok(())
+    }
+
+    /// Inserts a string slice into this `ArrayString` at a byte position.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is larger than the `ArrayString`'s length, if it does not lie on a
+    /// [`char`] boundary, or if the string slice does not fit.
+    #[inline]
+    pub fn insert_str(&mut self, idx: usize, string: &str) {
+        if let Err(err) = self.try_insert_str(idx, string) {
+            panic!("{}", err)
+        }
+    }
+
+    /// Like `insert_str` but hands back the string slice instead of panicking if it does not
+    /// fit.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use alloc_wg::string::ArrayString;
+    ///
+    /// let mut s = ArrayString::<6>::try_from_str("bar").unwrap();
+    /// s.try_insert_str(0, "foo").unwrap();
+    /// assert_eq!(s, "foobar");
+    /// assert!(s.try_insert_str(0, "!").is_err());
+    /// ```
+    #[inline]
+    pub fn try_insert_str<'a>(
+        &mut self,
+        idx: usize,
+        string: &'a str,
+    ) -> Result<(), CapacityError<&'a str>> {
+        assert!(self.is_char_boundary(idx));
+
+        unsafe { self.try_insert_bytes(idx, string.as_bytes()) }
+            .map_err(|_| CapacityError::new(string))
+    }
+
+    /// Truncates this `ArrayString`, removing all contents.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.vec.clear()
+    }
+
+    /// Creates a draining iterator that removes the specified range in the `ArrayString`
+    /// and yields the removed `chars`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the starting point or end point do not lie on a [`char`]
+    /// boundary, or if they're out of bounds.
+    pub fn drain<R>(&mut self, range: R) -> Drain<'_, N>
+    where
+        R: RangeBounds<usize>,
+    {
+        let len = self.len();
+        let start = match range.start_bound() {
+            Included(&n) => n,
+            Excluded(&n) => n + 1,
+            Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Included(&n) => n + 1,
+            Excluded(&n) => n,
+            Unbounded => len,
+        };
+
+        // Take out two simultaneous borrows. The &mut ArrayString won't be accessed
+        // until iteration is over, in Drop.
+        let self_ptr: *mut Self = self;
+        // slicing does the appropriate bounds checks
+        let chars_iter = self[start..end].chars();
+
+        Drain {
+            start,
+            end,
+            iter: chars_iter,
+            string: self_ptr,
+        }
+    }
+
+    /// Copies the contents into a [`String<A>`] allocated from `a`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the allocation fails.
+    ///
+    /// [`String<A>`]: crate::string::String
+    pub fn into_string_in<A: AllocRef>(self, a: A) -> String<A> {
+        handle_reserve_error(self.try_into_string_in(a))
+    }
+
+    /// Same as `into_string_in` but returns errors instead of panicking.
+    pub fn try_into_string_in<A: AllocRef>(self, a: A) -> Result<String<A>, TryReserveError> {
+        String::try_from_str_in(self.as_str(), a)
+    }
+}
+
+impl<const N: usize> ops::Deref for ArrayString<N> {
+    type Target = str;
+
+    #[inline]
+    fn deref(&self) -> &str {
+        unsafe { str::from_utf8_unchecked(&self.vec) }
+    }
+}
+
+impl<const N: usize> ops::DerefMut for ArrayString<N> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut str {
+        unsafe { str::from_utf8_unchecked_mut(&mut *self.vec) }
+    }
+}
+
+impl<const N: usize> Clone for ArrayString<N> {
+    fn clone(&self) -> Self {
+        Self {
+            vec: self.vec.clone(),
+        }
+    }
+}
+
+impl<const N: usize> Default for ArrayString<N> {
+    /// Creates an empty `ArrayString<N>`.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> fmt::Display for ArrayString<N> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&**self, f)
+    }
+}
+
+impl<const N: usize> fmt::Debug for ArrayString<N> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<const N: usize> hash::Hash for ArrayString<N> {
+    #[inline]
+    fn hash<H: hash::Hasher>(&self, hasher: &mut H) {
+        (**self).hash(hasher)
+    }
+}
+
+impl<const N: usize> fmt::Write for ArrayString<N> {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.try_push_str(s).map_err(|_| fmt::Error)
+    }
+
+    #[inline]
+    fn write_char(&mut self, c: char) -> fmt::Result {
+        self.try_push(c).map_err(|_| fmt::Error)
+    }
+}
+
+impl<const N: usize> AsRef<str> for ArrayString<N> {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        self
+    }
+}
+
+impl<const N: usize> AsRef<[u8]> for ArrayString<N> {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl<'a, const N: usize> TryFrom<&'a str> for ArrayString<N> {
+    type Error = CapacityError<&'a str>;
+
+    #[inline]
+    fn try_from(s: &'a str) -> Result<Self, Self::Error> {
+        Self::try_from_str(s)
+    }
+}
+
+impl<A: AllocRef, const N: usize> From<ArrayString<N>> for String<A>
+where
+    A: Default,
+{
+    /// Copies the contents into a `String` using the default allocator.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the allocation fails.
+    fn from(s: ArrayString<N>) -> Self {
+        s.into_string_in(A::default())
+    }
+}
+
+impl<const N: usize> Extend<char> for ArrayString<N> {
+    /// # Panics
+    ///
+    /// Panics if the string is full before the iterator is exhausted.
+    fn extend<I: IntoIterator<Item = char>>(&mut self, iter: I) {
+        iter.into_iter().for_each(move |c| self.push(c));
+    }
+}
+
+impl<'a, const N: usize> Extend<&'a str> for ArrayString<N> {
+    /// # Panics
+    ///
+    /// Panics if the string is full before the iterator is exhausted.
+    fn extend<I: IntoIterator<Item = &'a str>>(&mut self, iter: I) {
+        iter.into_iter().for_each(move |s| self.push_str(s));
+    }
+}
+
+impl<const N: usize> TryExtend<char> for ArrayString<N> {
+    type Err = CapacityError<char>;
+
+    fn try_extend<I: IntoIterator<Item = char>>(&mut self, iter: I) -> Result<(), Self::Err> {
+        for c in iter {
+            self.try_push(c)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a, const N: usize> TryExtend<&'a str> for ArrayString<N> {
+    type Err = CapacityError<&'a str>;
+
+    fn try_extend<I: IntoIterator<Item = &'a str>>(&mut self, iter: I) -> Result<(), Self::Err> {
+        for s in iter {
+            self.try_push_str(s)?;
+        }
+        Ok(())
+    }
+}
+
+impl<const N: usize, const M: usize> PartialEq<ArrayString<M>> for ArrayString<N> {
+    #[inline]
+    fn eq(&self, other: &ArrayString<M>) -> bool {
+        PartialEq::eq(&self[..], &other[..])
+    }
+}
+
+impl<const N: usize> Eq for ArrayString<N> {}
+
+impl<const N: usize> PartialOrd for ArrayString<N> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        PartialOrd::partial_cmp(&**self, &**other)
+    }
+}
+
+impl<const N: usize> Ord for ArrayString<N> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        Ord::cmp(&**self, &**other)
+    }
+}
+
+macro_rules! impl_eq {
+    ($lhs:ty, $rhs:ty) => {
+        #[allow(unused_lifetimes)]
+        impl<const N: usize> PartialEq<$rhs> for $lhs {
+            #[inline]
+            fn eq(&self, other: &$rhs) -> bool {
+                PartialEq::eq(&self[..], &other[..])
+            }
+        }
+
+        #[allow(unused_lifetimes)]
+        impl<const N: usize> PartialEq<$lhs> for $rhs {
+            #[inline]
+            fn eq(&self, other: &$lhs) -> bool {
+                PartialEq::eq(&self[..], &other[..])
+            }
+        }
+    };
+}
+
+impl_eq! { ArrayString<N>, str }
+impl_eq! { ArrayString<N>, &'_ str }
+
+/// A draining iterator for `ArrayString`.
+///
+/// This struct is created by the [`drain`] method on [`ArrayString`]. See its
+/// documentation for more.
+///
+/// [`drain`]: ArrayString::drain()
+pub struct Drain<'a, const N: usize> {
+    /// Will be used as &'a mut ArrayString in the destructor
+    string: *mut ArrayString<N>,
+    /// Start of part to remove
+    start: usize,
+    /// End of part to remove
+    end: usize,
+    /// Current remaining range to remove
+    iter: Chars<'a>,
+}
+
+impl<const N: usize> fmt::Debug for Drain<'_, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad("Drain { .. }")
+    }
+}
+
+unsafe impl<const N: usize> Sync for Drain<'_, N> {}
+unsafe impl<const N: usize> Send for Drain<'_, N> {}
+
+impl<const N: usize> Drop for Drain<'_, N> {
+    fn drop(&mut self) {
+        unsafe {
+            // Use ArrayVec::drain. "Reaffirm" the bounds checks to avoid
+            // panic code being inserted again.
+            let self_vec = &mut (*self.string).vec;
+            if self.start <= self.end && self.end <= self_vec.len() {
+                self_vec.drain(self.start..self.end);
+            }
+        }
+    }
+}
+
+impl<const N: usize> Iterator for Drain<'_, N> {
+    type Item = char;
+
+    #[inline]
+    fn next(&mut self) -> Option<char> {
+        self.iter.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+
+    #[inline]
+    fn last(mut self) -> Option<char> {
+        self.next_back()
+    }
+}
+
+impl<const N: usize> DoubleEndedIterator for Drain<'_, N> {
+    #[inline]
+    fn next_back(&mut self) -> Option<char> {
+        self.iter.next_back()
+    }
+}
+
+impl<const N: usize> FusedIterator for Drain<'_, N> {}
@@ -15,7 +15,7 @@ use core::intrinsics::abort;
 use core::iter;
 use core::marker::{PhantomData, Unpin, Unsize};
 use core::mem::{self, align_of, align_of_val, MaybeUninit, size_of_val};
-use core::ops::{CoerceUnsized, Deref, DispatchFromDyn, Receiver};
+use core::ops::{CoerceUnsized, Deref, DerefMut, DispatchFromDyn, Receiver};
 use core::pin::Pin;
 use core::ptr::{self, NonNull, Unique};
 use core::slice::from_raw_parts_mut;
@@ -23,8 +23,9 @@ use core::sync::atomic;
 use core::sync::atomic::Ordering::{Acquire, Relaxed, Release, SeqCst};
 
 use crate::alloc::{handle_alloc_error, Global};
-use crate::borrow::{Cow, ToOwned};
+use crate::borrow::{Cow, ToOwned, ToOwnedIn};
 use crate::boxed::Box;
+use crate::clone::{CloneIn, TryClone};
 use crate::collections::TryReserveError;
 use crate::iter::FromIteratorIn;
 use crate::string::String;
@@ -57,6 +58,11 @@ macro_rules! acquire {
     };
 }
 
+// Declared after `acquire!` so the module can use it.
+pub mod thin_arc;
+
+pub use self::thin_arc::{ThinArc, ThinWeak};
+
 /// A thread-safe reference-counting pointer. 'Arc' stands for 'Atomically
 /// Reference Counted'.
 ///
@@ -316,12 +322,15 @@ impl<T, A: AllocRef> ArcInner<T, A> {
         }
         inner_ptr.cast()
     }
-    fn try_new_in(strong: usize, weak: usize, data: T, alloc: A)
-        -> Result<NonNull<Self>, TryReserveError>
-    {
-        let err = TryReserveError::AllocError { layout: Layout::new::<MaybeUninit<T>>(), };
-        let b = Box::try_new_uninit_in(alloc)
-          .map_err(move |_| err )?;
+    fn try_new_in(
+        strong: usize,
+        weak: usize,
+        data: T,
+        alloc: A,
+    ) -> Result<NonNull<Self>, TryReserveError> {
+        let b = Box::try_new_uninit_in(alloc).map_err(|e| {
+            TryReserveError::alloc_error(Layout::new::<ArcInner<T, A>>()).with_cause(e)
+        })?;
         let (inner_ptr, alloc): (NonNull<MaybeUninit<ArcInner<T, A>>>, _) =
           Box::into_raw_non_null_alloc(b);
         unsafe {
@@ -463,6 +472,100 @@ impl<T, A: AllocRef> Arc<T, A> {
         Ok(Self::from_inner(ArcInner::try_new_in(1, 1, data, alloc)?))
     }
 
+    /// Constructs a new `Arc<T, A>` from the value returned by `f`.
+    ///
+    /// The memory is allocated before `f` is called, so `f` is never called if the allocation
+    /// fails. The value is still returned by `f`, so whether it is built on the stack first is
+    /// up to the optimizer. Use [`init_in`] to write a large value directly into the `Arc`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use alloc_wg::{alloc::Global, sync::Arc};
+    ///
+    /// let arc = Arc::new_with_in(|| [1_u8; 4096], Global);
+    /// assert_eq!(arc[4095], 1);
+    /// ```
+    ///
+    /// [`init_in`]: Self::init_in
+    pub fn new_with_in<F: FnOnce() -> T>(f: F, alloc: A) -> Arc<T, A> {
+        let mut arc = Arc::new_uninit_in(alloc);
+        unsafe {
+            Arc::get_mut_unchecked(&mut arc).as_mut_ptr().write(f());
+            arc.assume_init()
+        }
+    }
+
+    /// Same as `new_with_in` but returns errors instead of aborting.
+    ///
+    /// `f` is not called if the allocation fails.
+    pub fn try_new_with_in<F: FnOnce() -> T>(f: F, alloc: A) -> Result<Arc<T, A>, TryReserveError> {
+        let mut arc = Arc::try_new_uninit_in(alloc)?;
+        unsafe {
+            Arc::get_mut_unchecked(&mut arc).as_mut_ptr().write(f());
+            Ok(arc.assume_init())
+        }
+    }
+
+    /// Constructs a new `Arc<T, A>`, whose value is initialized in place by `init`.
+    ///
+    /// The memory is allocated before `init` is called, and `init` receives the uninitialized
+    /// slot inside the `Arc`, so even large values are never built on the stack. It has to
+    /// return the slot as initialized reference, e.g. by calling
+    /// [`MaybeUninit::assume_init_mut`]. If `init` panics, the memory is freed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `init` returns a reference to anything else than the slot.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(allocator_api, maybe_uninit_ref)]
+    /// use alloc_wg::{alloc::Global, sync::Arc};
+    /// use core::ptr;
+    ///
+    /// let arc = Arc::<[u8; 4096], _>::init_in(Global, |slot| unsafe {
+    ///     ptr::write_bytes(slot.as_mut_ptr(), 1, 1);
+    ///     slot.assume_init_mut()
+    /// });
+    /// assert_eq!(arc[4095], 1);
+    /// ```
+    ///
+    /// [`MaybeUninit::assume_init_mut`]: core::mem::MaybeUninit::assume_init_mut
+    pub fn init_in<F>(alloc: A, init: F) -> Arc<T, A>
+    where
+        F: for<'a> FnOnce(&'a mut MaybeUninit<T>) -> &'a mut T,
+    {
+        Arc::new_uninit_in(alloc).init_with(init)
+    }
+
+    /// Same as `init_in` but returns errors instead of aborting.
+    ///
+    /// `init` is not called if the allocation fails.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `init` returns a reference to anything else than the slot.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(allocator_api, maybe_uninit_extra)]
+    /// use alloc_wg::{alloc::Global, sync::Arc};
+    ///
+    /// let arc = Arc::try_init_in(Global, |slot| slot.write(5_u32))?;
+    /// assert_eq!(*arc, 5);
+    /// # Ok::<_, alloc_wg::collections::TryReserveError>(())
+    /// ```
+    pub fn try_init_in<F>(alloc: A, init: F) -> Result<Arc<T, A>, TryReserveError>
+    where
+        F: for<'a> FnOnce(&'a mut MaybeUninit<T>) -> &'a mut T,
+    {
+        Ok(Arc::try_new_uninit_in(alloc)?.init_with(init))
+    }
+
     #[inline]
     fn init_cyclic_in(init_ptr: NonNull<ArcInner<T, A>>,
                       data_fn: impl FnOnce(&Weak<T, A>) -> T) -> Arc<T, A>
@@ -643,6 +746,59 @@ impl<T, A: AllocRef> Arc<T, A> {
         Ok(unsafe { Pin::new_unchecked(Arc::try_new_in(data, alloc)?) })
     }
 
+    /// Constructs a new `Pin<Arc<T, A>>` by initializing the value in place with `init`.
+    ///
+    /// `init` receives the pinned, uninitialized slot at the final address of the value, so the
+    /// value can refer to itself and is never moved after construction.
+    ///
+    /// # Safety
+    ///
+    /// `init` has to fully initialize the slot. If `init` panics, the memory is freed without
+    /// dropping the slot.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use alloc_wg::{alloc::Global, sync::Arc};
+    /// use core::{mem::MaybeUninit, pin::Pin};
+    ///
+    /// let five = unsafe {
+    ///     Arc::new_pinned_with(Global, |slot: Pin<&mut MaybeUninit<u32>>| {
+    ///         slot.get_unchecked_mut().as_mut_ptr().write(5);
+    ///     })
+    /// };
+    ///
+    /// assert_eq!(*five, 5);
+    /// ```
+    pub unsafe fn new_pinned_with<F>(alloc: A, init: F) -> Pin<Arc<T, A>>
+    where
+        F: FnOnce(Pin<&mut MaybeUninit<T>>),
+    {
+        let mut arc = Arc::new_uninit_in(alloc);
+        init(Pin::new_unchecked(Arc::get_mut_unchecked(&mut arc)));
+        Pin::new_unchecked(arc.assume_init())
+    }
+
+    /// Same as `new_pinned_with` but returns errors instead of aborting.
+    ///
+    /// `init` is not called if the allocation fails.
+    ///
+    /// # Safety
+    ///
+    /// See [`new_pinned_with`](Arc::new_pinned_with).
+    pub unsafe fn try_new_pinned_with<F>(
+        alloc: A,
+        init: F,
+    ) -> Result<Pin<Arc<T, A>>, TryReserveError>
+    where
+        F: FnOnce(Pin<&mut MaybeUninit<T>>),
+    {
+        let mut arc = Arc::try_new_uninit_in(alloc)?;
+        init(Pin::new_unchecked(Arc::get_mut_unchecked(&mut arc)));
+        Ok(Pin::new_unchecked(arc.assume_init()))
+    }
+
     /// Returns the inner value, if the `Arc` has exactly one strong reference.
     ///
     /// Otherwise, an [`Err`] is returned with the same `Arc` that was
@@ -911,6 +1067,24 @@ impl<T, A: AllocRef> Arc<MaybeUninit<T>, A> {
     pub unsafe fn assume_init(self) -> Arc<T, A> {
         Arc::from_inner(mem::ManuallyDrop::new(self).ptr.cast())
     }
+
+    /// Initializes the value in place with `init` and converts to `Arc<T>`.
+    ///
+    /// Panics if `init` returns a reference to anything else than the slot.
+    fn init_with<F>(mut self, init: F) -> Arc<T, A>
+    where
+        F: for<'a> FnOnce(&'a mut MaybeUninit<T>) -> &'a mut T,
+    {
+        unsafe {
+            let slot = Arc::get_mut_unchecked(&mut self);
+            let value = slot.as_mut_ptr();
+            assert!(
+                ptr::eq(init(slot), value),
+                "`init` has to return the initialized slot"
+            );
+            self.assume_init()
+        }
+    }
 }
 
 impl<T, A: AllocRef> Arc<[MaybeUninit<T>], A> {
@@ -1329,10 +1503,8 @@ impl<T: ?Sized, A: AllocRef> Arc<T, A> {
 
         let ptr = match allocate(&alloc, layout) {
             Ok(ptr) => ptr,
-            Err(_) => {
-                return Err((TryReserveError::AllocError {
-                    layout,
-                }, alloc));
+            Err(err) => {
+                return Err((TryReserveError::alloc_error(layout).with_cause(err), alloc));
             },
         };
 
@@ -1696,6 +1868,41 @@ impl<T: ?Sized, A: AllocRef> Clone for Arc<T, A> {
     }
 }
 
+/// Cloning an `Arc` only increments the reference count, so `try_clone` never fails.
+impl<T: ?Sized, A: AllocRef> TryClone for Arc<T, A> {
+    #[inline]
+    fn try_clone(&self) -> Result<Self, TryReserveError> {
+        Ok(Self::clone(self))
+    }
+}
+
+/// Unlike `clone`, `clone_in` cannot share the allocation, as it has to live in `B`. It
+/// clones the contents into a new `Arc` with a strong count of one instead.
+impl<T: Clone, A: AllocRef, B: AllocRef> CloneIn<B> for Arc<T, A> {
+    type Cloned = Arc<T, B>;
+
+    fn clone_in(&self, a: B) -> Self::Cloned {
+        Arc::new_in((**self).clone(), a)
+    }
+
+    fn try_clone_in(&self, a: B) -> Result<Self::Cloned, TryReserveError> {
+        Arc::try_new_in((**self).clone(), a)
+    }
+}
+
+/// Clones the elements into a new `Arc<[T], B>`, see the `CloneIn` impl for `Arc<T, A>`.
+impl<T: Clone, A: AllocRef, B: AllocRef> CloneIn<B> for Arc<[T], A> {
+    type Cloned = Arc<[T], B>;
+
+    fn clone_in(&self, a: B) -> Self::Cloned {
+        FromIteratorIn::from_iter_in(self.iter().cloned(), a)
+    }
+
+    fn try_clone_in(&self, a: B) -> Result<Self::Cloned, TryReserveError> {
+        FromIteratorIn::try_from_iter_in(self.iter().cloned(), a)
+    }
+}
+
 //#[stable(feature = "rust1", since = "1.0.0")]
 impl<T: ?Sized, A: AllocRef> Deref for Arc<T, A> {
     type Target = T;
@@ -2827,13 +3034,28 @@ impl<T, A: AllocRef> From<Vec<T, A>> for Arc<[T], A> {
 }
 
 //#[stable(feature = "shared_from_cow", since = "1.45.0")]
-impl<'a, B> From<Cow<'a, B>> for Arc<B>
+impl<'a, B> From<liballoc::borrow::Cow<'a, B>> for Arc<B>
 where
     B: ToOwned + ?Sized,
     Arc<B>: From<&'a B> + From<B::Owned>,
 {
     #[inline]
-    fn from(cow: Cow<'a, B>) -> Arc<B> {
+    fn from(cow: liballoc::borrow::Cow<'a, B>) -> Arc<B> {
+        match cow {
+            liballoc::borrow::Cow::Borrowed(s) => Arc::from(s),
+            liballoc::borrow::Cow::Owned(s) => Arc::from(s),
+        }
+    }
+}
+
+impl<'a, B, A> From<Cow<'a, B, A>> for Arc<B>
+where
+    B: ToOwnedIn<A> + ?Sized,
+    A: AllocRef,
+    Arc<B>: From<&'a B> + From<<B as ToOwnedIn<A>>::Owned>,
+{
+    #[inline]
+    fn from(cow: Cow<'a, B, A>) -> Arc<B> {
         match cow {
             Cow::Borrowed(s) => Arc::from(s),
             Cow::Owned(s) => Arc::from(s),
@@ -3009,6 +3231,223 @@ impl<T: ?Sized, A: AllocRef> AsRef<T> for Arc<T, A> {
 //#[stable(feature = "pin", since = "1.33.0")]
 impl<T: ?Sized, A: AllocRef> Unpin for Arc<T, A> {}
 
+/// A uniquely owned [`Arc`].
+///
+/// A `UniqueArc` uses the same allocation layout as [`Arc`], but as long as it hasn't been shared
+/// it is the only strong owner of its value, so it can be mutated through [`DerefMut`] without any
+/// counter checks. Once the value is ready, [`into_shared`] turns it into an [`Arc`] in place.
+///
+/// [`Weak`] pointers can be created before the value is shared, much like in
+/// [`Arc::new_cyclic_in`]. Upgrading them returns [`None`] until [`into_shared`] is called.
+///
+/// [`into_shared`]: UniqueArc::into_shared
+///
+/// # Examples
+///
+/// ```
+/// use alloc_wg::sync::UniqueArc;
+///
+/// let mut unique = UniqueArc::new(vec![1, 2]);
+/// let weak = UniqueArc::downgrade(&unique);
+/// assert!(weak.upgrade().is_none());
+///
+/// unique.push(3);
+/// let shared = UniqueArc::into_shared(unique);
+/// assert_eq!(*weak.upgrade().unwrap(), [1, 2, 3]);
+/// assert_eq!(*shared, [1, 2, 3]);
+/// ```
+pub struct UniqueArc<T: ?Sized, A: AllocRef = Global> {
+    // The strong count stays at zero until `into_shared` is called, so weak pointers created in
+    // the meantime cannot be upgraded. The `UniqueArc` owns the implicit weak reference that is
+    // handed over to the strong references when sharing.
+    ptr: NonNull<ArcInner<T, A>>,
+    phantom: PhantomData<ArcInner<T, A>>,
+}
+
+unsafe impl<T: ?Sized + Sync + Send, A: AllocRef + Send> Send for UniqueArc<T, A> {}
+unsafe impl<T: ?Sized + Sync + Send, A: AllocRef> Sync for UniqueArc<T, A> {}
+
+impl<T> UniqueArc<T> {
+    /// Constructs a new `UniqueArc<T>`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use alloc_wg::sync::UniqueArc;
+    ///
+    /// let mut five = UniqueArc::new(4);
+    /// *five += 1;
+    /// assert_eq!(*five, 5);
+    /// ```
+    #[inline]
+    pub fn new(data: T) -> UniqueArc<T> {
+        UniqueArc::new_in(data, Global)
+    }
+}
+
+impl<T, A: AllocRef> UniqueArc<T, A> {
+    /// Constructs a new `UniqueArc<T, A>` in the provided allocator.
+    #[inline]
+    pub fn new_in(data: T, alloc: A) -> UniqueArc<T, A> {
+        UniqueArc::from_unique_inner(ArcInner::new_in(0, 1, data, alloc))
+    }
+
+    /// Same as `new_in` but returns errors instead of aborting.
+    #[inline]
+    pub fn try_new_in(data: T, alloc: A) -> Result<UniqueArc<T, A>, TryReserveError> {
+        Ok(UniqueArc::from_unique_inner(ArcInner::try_new_in(
+            0, 1, data, alloc,
+        )?))
+    }
+
+    /// Constructs a new atomically reference-counted slice with uninitialized contents, which
+    /// can be written to without `unsafe` pointer accesses before it's shared.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use alloc_wg::{alloc::Global, sync::UniqueArc};
+    ///
+    /// let mut values = UniqueArc::<u32>::new_uninit_slice_in(3, Global);
+    /// for (i, value) in values.iter_mut().enumerate() {
+    ///     *value = std::mem::MaybeUninit::new(i as u32 + 1);
+    /// }
+    /// let values = unsafe { values.assume_init() };
+    ///
+    /// assert_eq!(*UniqueArc::into_shared(values), [1, 2, 3]);
+    /// ```
+    pub fn new_uninit_slice_in(len: usize, alloc: A) -> UniqueArc<[MaybeUninit<T>], A> {
+        UniqueArc::from_unique_arc(Arc::new_uninit_slice_in(len, alloc))
+    }
+
+    /// Same as `new_uninit_slice_in` but returns errors instead of aborting.
+    pub fn try_new_uninit_slice_in(
+        len: usize,
+        alloc: A,
+    ) -> Result<UniqueArc<[MaybeUninit<T>], A>, TryReserveError> {
+        Ok(UniqueArc::from_unique_arc(Arc::try_new_uninit_slice_in(
+            len, alloc,
+        )?))
+    }
+}
+
+impl<T, A: AllocRef> UniqueArc<[MaybeUninit<T>], A> {
+    /// Converts to `UniqueArc<[T], A>`.
+    ///
+    /// # Safety
+    ///
+    /// As with [`MaybeUninit::assume_init`],
+    /// it is up to the caller to guarantee that the inner value
+    /// really is in an initialized state.
+    /// Calling this when the content is not yet fully initialized
+    /// causes immediate undefined behavior.
+    #[inline]
+    pub unsafe fn assume_init(self) -> UniqueArc<[T], A> {
+        UniqueArc::from_unique_inner(NonNull::new_unchecked(
+            mem::ManuallyDrop::new(self).ptr.as_ptr() as _,
+        ))
+    }
+}
+
+impl<T: ?Sized, A: AllocRef> UniqueArc<T, A> {
+    fn from_unique_inner(ptr: NonNull<ArcInner<T, A>>) -> Self {
+        Self {
+            ptr,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Takes over a freshly allocated `Arc`, which must neither be shared nor have any weak
+    /// pointers besides the implicit one.
+    fn from_unique_arc(arc: Arc<T, A>) -> Self {
+        let ptr = mem::ManuallyDrop::new(arc).ptr;
+        // Nobody else can observe the counter yet, so `Relaxed` is enough.
+        unsafe { ptr.as_ref().strong.store(0, Relaxed) };
+        Self::from_unique_inner(ptr)
+    }
+
+    fn inner(&self) -> &ArcInner<T, A> {
+        unsafe { self.ptr.as_ref() }
+    }
+
+    /// Creates a new [`Weak`] pointer to this allocation.
+    ///
+    /// The returned pointer can't be upgraded until the `UniqueArc` is turned into an [`Arc`] by
+    /// [`into_shared`].
+    ///
+    /// [`into_shared`]: UniqueArc::into_shared
+    pub fn downgrade(this: &Self) -> Weak<T, A> {
+        // Weak pointers of a `UniqueArc` can't be upgraded, so the weak count can't be locked
+        // by `is_unique` and a plain increment is enough.
+        let old_size = this.inner().weak.fetch_add(1, Relaxed);
+
+        // See comments in `Arc::clone` for why we do this (for `mem::forget`).
+        if old_size > MAX_REFCOUNT {
+            abort();
+        }
+
+        Weak { ptr: this.ptr }
+    }
+
+    /// Turns the `UniqueArc` into a shared [`Arc`] without reallocating.
+    ///
+    /// This only sets the strong count to one; no other counters are checked. [`Weak`]
+    /// pointers created by [`downgrade`] can be upgraded afterwards.
+    ///
+    /// [`downgrade`]: UniqueArc::downgrade
+    pub fn into_shared(this: Self) -> Arc<T, A> {
+        let this = mem::ManuallyDrop::new(this);
+
+        // The value has to be visible to any thread which upgrades a weak pointer, see
+        // `Arc::init_cyclic_in` for why `Release` is sufficient.
+        let prev_value = this.inner().strong.fetch_add(1, Release);
+        debug_assert_eq!(prev_value, 0, "No prior strong references should exist");
+
+        // The implicit weak reference of the `UniqueArc` becomes the one collectively owned by
+        // the strong references.
+        Arc::from_inner(this.ptr)
+    }
+}
+
+impl<T: ?Sized, A: AllocRef> Deref for UniqueArc<T, A> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.inner().data
+    }
+}
+
+impl<T: ?Sized, A: AllocRef> DerefMut for UniqueArc<T, A> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        // Weak pointers can't access the data while the strong count is zero, so this is the
+        // only reference to it.
+        unsafe { &mut (*self.ptr.as_ptr()).data }
+    }
+}
+
+impl<T: ?Sized, A: AllocRef> Drop for UniqueArc<T, A> {
+    fn drop(&mut self) {
+        unsafe {
+            ptr::drop_in_place(&mut (*self.ptr.as_ptr()).data);
+        }
+
+        // Release the implicit weak reference, which frees the memory if no other weak
+        // pointers are left.
+        drop(Weak { ptr: self.ptr });
+    }
+}
+
+impl<T: ?Sized + fmt::Debug, A: AllocRef> fmt::Debug for UniqueArc<T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<T: ?Sized, A: AllocRef> Unpin for UniqueArc<T, A> {}
+
 /// Get the offset within an `ArcInner` for
 /// a payload of type described by a pointer.
 ///
@@ -1,10 +1,14 @@
 use super::*;
 
+use std::cell::Cell;
 use std::clone::Clone;
 use std::convert::{From, TryInto};
+use std::marker::PhantomPinned;
 use std::mem::drop;
 use std::ops::Drop;
 use std::option::Option::{self, None, Some};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::rc::Rc;
 use std::sync::atomic::{
     self,
     Ordering::{Acquire, SeqCst},
@@ -14,11 +18,35 @@ use std::sync::Mutex;
 use std::thread;
 
 use crate::boxed::Box;
+use crate::sync::thin_arc::{StrongOnly, WithWeak};
 use crate::sync::Arc;
 use crate::vec::Vec;
 
 struct Canary(*mut atomic::AtomicUsize);
 
+/// Forwards to `Global`, counts the live allocations and fails once `fail` is set.
+#[derive(Default)]
+struct Counting {
+    live: Cell<usize>,
+    fail: Cell<bool>,
+}
+
+unsafe impl AllocRef for Counting {
+    fn alloc(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        if self.fail.get() {
+            return Err(AllocError);
+        }
+        let ptr = Global.alloc(layout)?;
+        self.live.set(self.live.get() + 1);
+        Ok(ptr)
+    }
+
+    unsafe fn dealloc(&self, ptr: NonNull<u8>, layout: Layout) {
+        self.live.set(self.live.get() - 1);
+        Global.dealloc(ptr, layout)
+    }
+}
+
 impl Drop for Canary {
     fn drop(&mut self) {
         unsafe {
@@ -558,3 +586,321 @@ fn test_arc_cyclic_two_refs() {
     assert_eq!(Arc::strong_count(&two_refs), 3);
     assert_eq!(Arc::weak_count(&two_refs), 2);
 }
+
+#[test]
+fn test_arc_alloc_error_details() {
+    let alloc = Counting::default();
+    alloc.fail.set(true);
+    let err = Arc::try_new_in(5_u64, &alloc).unwrap_err();
+    assert_eq!(err.cause(), Some(&AllocError));
+    // the layout covers the reference counts, not only the value
+    assert!(err.layout().unwrap().size() > mem::size_of::<u64>());
+    assert_eq!(alloc.live.get(), 0);
+}
+
+#[test]
+fn test_arc_clone_in() {
+    let arc = Arc::new(5);
+    let copy = arc.try_clone_in(Global).unwrap();
+    assert_eq!(*copy, 5);
+    assert_eq!(Arc::strong_count(&arc), 1);
+
+    let slice: Arc<[u8]> = Arc::from(&[1, 2, 3][..]);
+    assert_eq!(&*slice.clone_in(Global), &[1, 2, 3]);
+}
+
+#[test]
+fn test_arc_try_clone() {
+    let a = Arc::new(3);
+    let c = a.try_clone().unwrap();
+    assert!(Arc::ptr_eq(&a, &c));
+}
+
+/// A node of an intrusive, circular list, which points to itself when it is alone.
+struct Node {
+    value: u32,
+    next: *const Node,
+    _pin: PhantomPinned,
+}
+
+unsafe fn init_node(slot: Pin<&mut MaybeUninit<Node>>, value: u32) {
+    let this = slot.get_unchecked_mut().as_mut_ptr();
+    ptr::write(this, Node {
+        value,
+        next: this,
+        _pin: PhantomPinned,
+    });
+}
+
+#[test]
+fn test_arc_pinned_with() {
+    let alloc = Counting::default();
+    let node = unsafe { Arc::try_new_pinned_with(&alloc, |slot| init_node(slot, 2)) }.unwrap();
+    let other = Pin::clone(&node);
+    assert_eq!(other.next, &*node as *const Node);
+    assert_eq!(unsafe { (*other.next).value }, 2);
+    drop((node, other));
+    assert_eq!(alloc.live.get(), 0);
+
+    alloc.fail.set(true);
+    let called = Cell::new(false);
+    let result = unsafe {
+        Arc::try_new_pinned_with(&alloc, |slot| {
+            called.set(true);
+            init_node(slot, 3)
+        })
+    };
+    assert!(result.is_err());
+    assert!(!called.get());
+}
+
+#[test]
+fn test_arc_pinned_with_panic() {
+    let alloc = Counting::default();
+    let result = catch_unwind(AssertUnwindSafe(|| unsafe {
+        Arc::<Node, _>::new_pinned_with(&alloc, |_| panic!("init failed"))
+    }));
+    assert!(result.is_err());
+    assert_eq!(alloc.live.get(), 0);
+}
+
+const LARGE: usize = 16 << 20;
+
+/// Initializes a large array in place, which would overflow the stack in debug builds if it was
+/// built there first.
+fn fill_large(slot: &mut MaybeUninit<[u8; LARGE]>) -> &mut [u8; LARGE] {
+    unsafe {
+        ptr::write_bytes(slot.as_mut_ptr(), 1, 1);
+        slot.assume_init_mut()
+    }
+}
+
+#[test]
+fn test_arc_init_large() {
+    let arc = Arc::<[u8; LARGE], _>::init_in(Global, fill_large);
+    assert_eq!(arc[LARGE - 1], 1);
+}
+
+#[test]
+fn test_arc_new_with() {
+    let alloc = Counting::default();
+    let arc = Arc::try_new_with_in(|| [9_u64; 32], &alloc).unwrap();
+    let other = Arc::clone(&arc);
+    assert_eq!(other[31], 9);
+    drop((arc, other));
+    assert_eq!(alloc.live.get(), 0);
+}
+
+#[test]
+fn test_arc_init_not_called_on_failure() {
+    let alloc = Counting::default();
+    alloc.fail.set(true);
+    let called = Cell::new(false);
+    assert!(Arc::try_new_with_in(|| called.set(true), &alloc).is_err());
+    assert!(Arc::try_init_in(&alloc, |slot| slot.write(called.set(true))).is_err());
+    assert!(!called.get());
+}
+
+#[test]
+fn test_arc_init_panic_frees() {
+    let alloc = Counting::default();
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        Arc::<u32, _>::new_with_in(|| panic!("init failed"), &alloc)
+    }));
+    assert!(result.is_err());
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        Arc::<u32, _>::init_in(&alloc, |_| panic!("init failed"))
+    }));
+    assert!(result.is_err());
+    assert_eq!(alloc.live.get(), 0);
+}
+
+#[test]
+fn test_unique_arc_mutate_then_share() {
+    let alloc = Counting::default();
+    let mut unique = UniqueArc::new_in(vec![1, 2], &alloc);
+    unique.push(3);
+    assert_eq!(alloc.live.get(), 1);
+
+    let shared = UniqueArc::into_shared(unique);
+    assert_eq!(*shared, [1, 2, 3]);
+    assert_eq!(Arc::strong_count(&shared), 1);
+    assert_eq!(Arc::weak_count(&shared), 0);
+
+    let other = Arc::clone(&shared);
+    drop(shared);
+    assert_eq!(alloc.live.get(), 1);
+    drop(other);
+    assert_eq!(alloc.live.get(), 0);
+}
+
+#[test]
+fn test_unique_arc_weak_before_sharing() {
+    let alloc = Counting::default();
+    let unique = UniqueArc::new_in(5, &alloc);
+    let weak = UniqueArc::downgrade(&unique);
+    assert!(weak.upgrade().is_none());
+
+    let shared = UniqueArc::into_shared(unique);
+    assert_eq!(Arc::weak_count(&shared), 1);
+    assert_eq!(*weak.upgrade().unwrap(), 5);
+
+    drop(shared);
+    assert!(weak.upgrade().is_none());
+    assert_eq!(alloc.live.get(), 1);
+    drop(weak);
+    assert_eq!(alloc.live.get(), 0);
+}
+
+#[test]
+fn test_unique_arc_drop_unshared() {
+    let alloc = Counting::default();
+    let value = Rc::new(());
+
+    let unique = UniqueArc::new_in(Rc::clone(&value), &alloc);
+    let weak = UniqueArc::downgrade(&unique);
+    drop(unique);
+    assert_eq!(Rc::strong_count(&value), 1);
+    assert!(weak.upgrade().is_none());
+    assert_eq!(alloc.live.get(), 1);
+
+    drop(weak);
+    assert_eq!(alloc.live.get(), 0);
+}
+
+#[test]
+fn test_unique_arc_uninit_slice() {
+    let alloc = Counting::default();
+    let mut values = UniqueArc::<u32, _>::new_uninit_slice_in(4, &alloc);
+    for (i, value) in values.iter_mut().enumerate() {
+        *value = MaybeUninit::new(i as u32 * 2);
+    }
+    let values = unsafe { values.assume_init() };
+    assert_eq!(format!("{:?}", values), "[0, 2, 4, 6]");
+
+    let weak = UniqueArc::downgrade(&values);
+    let shared: Arc<[u32], _> = UniqueArc::into_shared(values);
+    assert_eq!(*weak.upgrade().unwrap(), [0, 2, 4, 6]);
+
+    drop(weak);
+    drop(shared);
+    assert_eq!(alloc.live.get(), 0);
+}
+
+#[test]
+fn test_unique_arc_try_new_fails() {
+    let alloc = Counting::default();
+    alloc.fail.set(true);
+    assert!(UniqueArc::try_new_in(1, &alloc).is_err());
+    assert!(UniqueArc::<u8, _>::try_new_uninit_slice_in(8, &alloc).is_err());
+
+    alloc.fail.set(false);
+    let unique = UniqueArc::try_new_in(1, &alloc).unwrap();
+    assert_eq!(*UniqueArc::into_shared(unique), 1);
+    assert_eq!(alloc.live.get(), 0);
+}
+
+/// Reports one item less than it yields.
+struct Liar<I>(I);
+
+impl<I: ExactSizeIterator> Iterator for Liar<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.0.len().saturating_sub(1);
+        (len, Some(len))
+    }
+}
+
+impl<I: ExactSizeIterator> ExactSizeIterator for Liar<I> {}
+
+#[test]
+fn test_thin_arc_single_word() {
+    assert_eq!(mem::size_of::<ThinArc<u8, u64>>(), mem::size_of::<usize>());
+    assert_eq!(
+        mem::size_of::<Option<ThinArc<u8, u64, Global, StrongOnly>>>(),
+        mem::size_of::<usize>()
+    );
+}
+
+#[test]
+fn test_thin_arc_header_and_slice() {
+    let alloc = Counting::default();
+    let arc = ThinArc::<_, _, _, StrongOnly>::from_header_and_iter_in("node", 1..4_u32, &alloc);
+    assert_eq!(*arc.header(), "node");
+    assert_eq!(arc.slice(), [1, 2, 3]);
+    assert_eq!(arc.len(), 3);
+
+    let other = arc.clone();
+    assert!(ThinArc::ptr_eq(&arc, &other));
+    assert_eq!(ThinArc::strong_count(&arc), 2);
+    assert_eq!(arc, other);
+    assert_eq!(
+        format!("{:?}", other),
+        r#"ThinArc { header: "node", slice: [1, 2, 3] }"#
+    );
+
+    drop(arc);
+    assert_eq!(alloc.live.get(), 1);
+    drop(other);
+    assert_eq!(alloc.live.get(), 0);
+}
+
+#[test]
+fn test_thin_arc_empty_slice() {
+    let arc: ThinArc<String, u8> = ThinArc::from_header_and_iter(String::from("leaf"), None);
+    assert!(arc.is_empty());
+    assert_eq!(arc.header(), "leaf");
+}
+
+#[test]
+fn test_thin_arc_weak() {
+    let alloc = Counting::default();
+    let value = Rc::new(());
+    let arc = ThinArc::<_, _, _, WithWeak>::from_header_and_iter_in(
+        Rc::clone(&value),
+        vec![Rc::clone(&value)],
+        &alloc,
+    );
+    let weak = ThinArc::downgrade(&arc);
+    assert_eq!(ThinArc::weak_count(&arc), 1);
+    assert!(ThinArc::ptr_eq(&weak.upgrade().unwrap(), &arc));
+
+    drop(arc);
+    assert_eq!(Rc::strong_count(&value), 1);
+    assert!(weak.upgrade().is_none());
+    assert_eq!(alloc.live.get(), 1);
+
+    drop(weak);
+    assert_eq!(alloc.live.get(), 0);
+}
+
+#[test]
+fn test_thin_arc_try_from_iter_fails() {
+    let alloc = Counting::default();
+    alloc.fail.set(true);
+    let err = ThinArc::<(), u64, _>::try_from_header_and_iter_in((), 0..8, &alloc).unwrap_err();
+    assert!(matches!(err, TryReserveError::AllocError { .. }));
+}
+
+#[test]
+fn test_thin_arc_lying_iterator() {
+    let alloc = Counting::default();
+    let value = Rc::new(());
+    let items = vec![Rc::clone(&value), Rc::clone(&value)];
+
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        ThinArc::<_, _, _, StrongOnly>::from_header_and_iter_in(
+            Rc::clone(&value),
+            Liar(items.into_iter()),
+            &alloc,
+        )
+    }));
+    assert!(result.is_err());
+    assert_eq!(Rc::strong_count(&value), 1);
+    assert_eq!(alloc.live.get(), 0);
+}
@@ -0,0 +1,571 @@
+//! A thread-safe reference-counted header followed by a slice, which is a single pointer wide.
+//!
+//! `Arc<[T], A>` is a fat pointer and its `ArcInner` knows nothing about additional data stored
+//! next to the slice. A [`ThinArc<H, T, A, C>`] stores the reference counts, the allocator, the
+//! length, a header `H` and the elements `[T]` in one allocation, so the pointer itself is only
+//! a single word. The reference counts are chosen with `C`: [`WithWeak`] supports [`ThinWeak`]
+//! pointers like [`Arc`] does, [`StrongOnly`] saves a word per allocation by omitting the weak
+//! count.
+//!
+//! # Examples
+//!
+//! ```
+//! use alloc_wg::sync::{thin_arc::StrongOnly, ThinArc};
+//! use core::mem::size_of;
+//!
+//! type Node = ThinArc<&'static str, u32, alloc_wg::alloc::Global, StrongOnly>;
+//!
+//! assert_eq!(size_of::<Node>(), size_of::<usize>());
+//! assert_eq!(size_of::<Option<Node>>(), size_of::<usize>());
+//!
+//! let node = Node::from_header_and_iter("add", vec![1, 2]);
+//! let other = node.clone();
+//! assert_eq!(*other.header(), "add");
+//! assert_eq!(other.slice(), [1, 2]);
+//! ```
+//!
+//! [`Arc`]: crate::sync::Arc
+//! [`ThinArc<H, T, A, C>`]: ThinArc
+
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::intrinsics::abort;
+use core::marker::PhantomData;
+use core::mem;
+use core::ptr::{self, NonNull};
+use core::slice;
+use core::sync::atomic;
+use core::sync::atomic::Ordering::{Acquire, Relaxed, Release};
+
+use super::MAX_REFCOUNT;
+use crate::alloc::{AllocRef, Global, Layout};
+use crate::collections::TryReserveError::{self, CapacityOverflow};
+use crate::handle_reserve_error;
+
+mod sealed {
+    use core::sync::atomic::AtomicUsize;
+
+    pub trait Sealed: Sized {
+        /// Counts for a single strong reference.
+        fn one() -> Self;
+
+        fn strong(&self) -> &AtomicUsize;
+
+        /// Releases the weak reference collectively held by the strong references after the
+        /// contents were dropped, returning `true` if the memory has to be freed.
+        fn release_strong_weak(&self) -> bool;
+    }
+}
+
+/// The reference counts stored in front of a [`ThinArc`].
+///
+/// This trait is sealed, it is implemented by [`WithWeak`] and [`StrongOnly`].
+pub trait RefCounts: sealed::Sealed {}
+
+/// Strong and weak reference counts, allowing [`ThinArc::downgrade`]. This is the default.
+pub struct WithWeak {
+    strong: atomic::AtomicUsize,
+    // Like in `Arc`, all strong references collectively hold one weak reference.
+    weak: atomic::AtomicUsize,
+}
+
+impl sealed::Sealed for WithWeak {
+    #[inline]
+    fn one() -> Self {
+        Self {
+            strong: atomic::AtomicUsize::new(1),
+            weak: atomic::AtomicUsize::new(1),
+        }
+    }
+
+    #[inline]
+    fn strong(&self) -> &atomic::AtomicUsize {
+        &self.strong
+    }
+
+    #[inline]
+    fn release_strong_weak(&self) -> bool {
+        if self.weak.fetch_sub(1, Release) == 1 {
+            acquire!(self.weak);
+            true
+        } else {
+            false
+        }
+    }
+}
+
+impl RefCounts for WithWeak {}
+
+/// A strong reference count only. The memory is freed together with the last `ThinArc`.
+pub struct StrongOnly {
+    strong: atomic::AtomicUsize,
+}
+
+impl sealed::Sealed for StrongOnly {
+    #[inline]
+    fn one() -> Self {
+        Self {
+            strong: atomic::AtomicUsize::new(1),
+        }
+    }
+
+    #[inline]
+    fn strong(&self) -> &atomic::AtomicUsize {
+        &self.strong
+    }
+
+    #[inline]
+    fn release_strong_weak(&self) -> bool {
+        true
+    }
+}
+
+impl RefCounts for StrongOnly {}
+
+/// The part in front of the elements. The elements follow at the offset used by `elements_ptr`.
+#[repr(C)]
+struct ThinArcInner<H, A, C> {
+    counts: C,
+    alloc: A,
+    len: usize,
+    header: H,
+}
+
+/// A thread-safe reference-counting pointer to a header `H` and a slice `[T]`, which is a single
+/// pointer wide.
+///
+/// See the [module documentation](self) for details.
+pub struct ThinArc<H, T, A: AllocRef = Global, C: RefCounts = WithWeak> {
+    ptr: NonNull<ThinArcInner<H, A, C>>,
+    marker: PhantomData<(H, T, A)>,
+}
+
+unsafe impl<H, T, A, C> Send for ThinArc<H, T, A, C>
+where
+    H: Sync + Send,
+    T: Sync + Send,
+    A: AllocRef + Send,
+    C: RefCounts,
+{
+}
+unsafe impl<H, T, A, C> Sync for ThinArc<H, T, A, C>
+where
+    H: Sync + Send,
+    T: Sync + Send,
+    A: AllocRef,
+    C: RefCounts,
+{
+}
+
+/// `ThinWeak` is a version of [`ThinArc`] that holds a non-owning reference to the allocation.
+///
+/// It is created by [`ThinArc::downgrade`] and is only available for [`WithWeak`] counts.
+pub struct ThinWeak<H, T, A: AllocRef = Global> {
+    ptr: NonNull<ThinArcInner<H, A, WithWeak>>,
+    marker: PhantomData<(H, T, A)>,
+}
+
+unsafe impl<H, T, A> Send for ThinWeak<H, T, A>
+where
+    H: Sync + Send,
+    T: Sync + Send,
+    A: AllocRef + Send,
+{
+}
+unsafe impl<H, T, A> Sync for ThinWeak<H, T, A>
+where
+    H: Sync + Send,
+    T: Sync + Send,
+    A: AllocRef,
+{
+}
+
+impl<H, T, C: RefCounts> ThinArc<H, T, Global, C> {
+    /// Constructs a new `ThinArc` from a header and the items of an iterator.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the allocation fails or if the iterator doesn't yield exactly as many items as
+    /// it reports.
+    #[inline]
+    pub fn from_header_and_iter<I>(header: H, items: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        Self::from_header_and_iter_in(header, items, Global)
+    }
+}
+
+impl<H, T, A: AllocRef, C: RefCounts> ThinArc<H, T, A, C> {
+    /// Like `from_header_and_iter` but parameterized over the choice of allocator for the
+    /// returned `ThinArc`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the allocation fails or if the iterator doesn't yield exactly as many items as
+    /// it reports.
+    pub fn from_header_and_iter_in<I>(header: H, items: I, alloc: A) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        handle_reserve_error(Self::try_from_header_and_iter_in(header, items, alloc))
+    }
+
+    /// Same as `from_header_and_iter_in` but returns errors instead of panicking.
+    ///
+    /// The length is taken from the iterator before allocating, so the items are never
+    /// collected into a temporary buffer.
+    ///
+    /// # Errors
+    ///
+    /// * `InvalidLayout` if the size of the allocation overflows `usize`.
+    /// * `CapacityOverflow` if the allocation would exceed `isize::MAX` bytes.
+    /// * `AllocError` on OOM
+    ///
+    /// # Panics
+    ///
+    /// Panics if the iterator doesn't yield exactly as many items as it reports.
+    pub fn try_from_header_and_iter_in<I>(
+        header: H,
+        items: I,
+        alloc: A,
+    ) -> Result<Self, TryReserveError>
+    where
+        I: IntoIterator<Item = T>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        let mut items = items.into_iter();
+        let len = items.len();
+        let layout = Self::layout(len)?;
+        let memory = alloc
+            .alloc(layout)
+            .map_err(|err| TryReserveError::alloc_error(layout).with_cause(err))?;
+        let ptr = memory.as_non_null_ptr().cast::<ThinArcInner<H, A, C>>();
+
+        // Frees the memory and drops the items written so far if the iterator panics or
+        // doesn't keep its promise.
+        let mut guard = InitGuard {
+            memory: memory.as_non_null_ptr(),
+            layout,
+            elements: unsafe { Self::elements_ptr(ptr) },
+            written: 0,
+            alloc,
+        };
+        for _ in 0..len {
+            let item = items
+                .next()
+                .expect("ExactSizeIterator yielded fewer items than it reported");
+            unsafe { guard.elements.add(guard.written).write(item) };
+            guard.written += 1;
+        }
+        assert!(
+            items.next().is_none(),
+            "ExactSizeIterator yielded more items than it reported"
+        );
+
+        let alloc = guard.into_alloc();
+        unsafe {
+            ptr.as_ptr().write(ThinArcInner {
+                counts: C::one(),
+                alloc,
+                len,
+                header,
+            });
+        }
+        Ok(Self {
+            ptr,
+            marker: PhantomData,
+        })
+    }
+
+    /// The layout of the header followed by `len` elements.
+    fn layout(len: usize) -> Result<Layout, TryReserveError> {
+        let array = Layout::array::<T>(len)?;
+        let (layout, _) = Layout::new::<ThinArcInner<H, A, C>>().extend(array)?;
+        let layout = layout.pad_to_align();
+        if mem::size_of::<usize>() < 8 && layout.size() > isize::MAX as usize {
+            Err(CapacityOverflow)
+        } else {
+            Ok(layout)
+        }
+    }
+
+    /// Returns the pointer to the first element of the allocation at `ptr`.
+    unsafe fn elements_ptr(ptr: NonNull<ThinArcInner<H, A, C>>) -> *mut T {
+        let header = Layout::new::<ThinArcInner<H, A, C>>();
+        let offset = header.size() + header.padding_needed_for(mem::align_of::<T>());
+        (ptr.as_ptr() as *mut u8).add(offset) as *mut T
+    }
+
+    /// Drops the elements and the header without freeing the memory.
+    unsafe fn drop_contents(ptr: NonNull<ThinArcInner<H, A, C>>) {
+        let inner = ptr.as_ptr();
+        ptr::drop_in_place(&mut (*inner).header);
+        ptr::drop_in_place(ptr::slice_from_raw_parts_mut(
+            Self::elements_ptr(ptr),
+            (*inner).len,
+        ));
+    }
+
+    /// Frees the memory after the contents were dropped.
+    unsafe fn dealloc(ptr: NonNull<ThinArcInner<H, A, C>>) {
+        let layout = Self::layout((*ptr.as_ptr()).len).unwrap();
+        let alloc = ptr::read(&(*ptr.as_ptr()).alloc);
+        alloc.dealloc(ptr.cast(), layout);
+    }
+
+    #[inline]
+    fn inner(&self) -> &ThinArcInner<H, A, C> {
+        // This unsafety is ok because while this arc is alive we're guaranteed
+        // that the inner pointer is valid.
+        unsafe { self.ptr.as_ref() }
+    }
+
+    /// Returns a reference to the header.
+    #[inline]
+    pub fn header(&self) -> &H {
+        &self.inner().header
+    }
+
+    /// Returns the elements following the header.
+    #[inline]
+    pub fn slice(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(Self::elements_ptr(self.ptr), self.len()) }
+    }
+
+    /// Returns the number of elements following the header.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.inner().len
+    }
+
+    /// Returns `true` if there are no elements following the header.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns a shared reference to the allocator backing this `ThinArc`.
+    #[inline]
+    pub fn alloc_ref(&self) -> &A {
+        &self.inner().alloc
+    }
+
+    /// Gets the number of strong (`ThinArc`) pointers to this allocation.
+    ///
+    /// # Safety
+    ///
+    /// This method by itself is safe, but using it correctly requires extra care.
+    /// Another thread can change the strong count at any time.
+    #[inline]
+    pub fn strong_count(this: &Self) -> usize {
+        this.inner().counts.strong().load(Acquire)
+    }
+
+    /// Returns `true` if the two `ThinArc`s point to the same allocation.
+    #[inline]
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        this.ptr == other.ptr
+    }
+}
+
+impl<H, T, A: AllocRef> ThinArc<H, T, A, WithWeak> {
+    /// Creates a new [`ThinWeak`] pointer to this allocation.
+    pub fn downgrade(this: &Self) -> ThinWeak<H, T, A> {
+        // Unlike `Arc`, a `ThinArc` never locks the weak count, so a plain increment is
+        // enough.
+        let old_size = this.inner().counts.weak.fetch_add(1, Relaxed);
+
+        // See comments in `Arc::clone` for why we do this (for `mem::forget`).
+        if old_size > MAX_REFCOUNT {
+            abort();
+        }
+
+        ThinWeak {
+            ptr: this.ptr,
+            marker: PhantomData,
+        }
+    }
+
+    /// Gets the number of [`ThinWeak`] pointers to this allocation.
+    ///
+    /// # Safety
+    ///
+    /// This method by itself is safe, but using it correctly requires extra care.
+    /// Another thread can change the weak count at any time.
+    #[inline]
+    pub fn weak_count(this: &Self) -> usize {
+        // The strong references collectively hold one weak reference.
+        this.inner().counts.weak.load(Acquire) - 1
+    }
+}
+
+impl<H, T, A: AllocRef, C: RefCounts> Clone for ThinArc<H, T, A, C> {
+    #[inline]
+    fn clone(&self) -> Self {
+        // Using a relaxed ordering is alright here, see `Arc::clone`.
+        let old_size = self.inner().counts.strong().fetch_add(1, Relaxed);
+
+        // See comments in `Arc::clone` for why we do this (for `mem::forget`).
+        if old_size > MAX_REFCOUNT {
+            abort();
+        }
+
+        Self {
+            ptr: self.ptr,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<H, T, A: AllocRef, C: RefCounts> Drop for ThinArc<H, T, A, C> {
+    /// Drops the `ThinArc`.
+    ///
+    /// This will decrement the strong reference count. If the strong reference count reaches
+    /// zero, the header and the elements are dropped. The memory is freed as soon as no
+    /// [`ThinWeak`] pointers are left.
+    fn drop(&mut self) {
+        // See `Arc::drop` for the memory orderings.
+        if self.inner().counts.strong().fetch_sub(1, Release) != 1 {
+            return;
+        }
+        acquire!(self.inner().counts.strong());
+
+        unsafe {
+            Self::drop_contents(self.ptr);
+            if self.inner().counts.release_strong_weak() {
+                Self::dealloc(self.ptr);
+            }
+        }
+    }
+}
+
+impl<H, T, A: AllocRef> ThinWeak<H, T, A> {
+    /// Attempts to upgrade the `ThinWeak` pointer to a [`ThinArc`].
+    ///
+    /// Returns [`None`] if the header and the elements have since been dropped.
+    pub fn upgrade(&self) -> Option<ThinArc<H, T, A, WithWeak>> {
+        // See `Weak::upgrade` for why this is a CAS loop and for the memory orderings.
+        let strong = &unsafe { self.ptr.as_ref() }.counts.strong;
+        let mut n = strong.load(Relaxed);
+
+        loop {
+            if n == 0 {
+                return None;
+            }
+
+            if n > MAX_REFCOUNT {
+                abort();
+            }
+
+            match strong.compare_exchange_weak(n, n + 1, Acquire, Relaxed) {
+                Ok(_) => {
+                    return Some(ThinArc {
+                        ptr: self.ptr,
+                        marker: PhantomData,
+                    });
+                }
+                Err(old) => n = old,
+            }
+        }
+    }
+}
+
+impl<H, T, A: AllocRef> Clone for ThinWeak<H, T, A> {
+    #[inline]
+    fn clone(&self) -> Self {
+        let old_size = unsafe { self.ptr.as_ref() }
+            .counts
+            .weak
+            .fetch_add(1, Relaxed);
+
+        // See comments in `Arc::clone` for why we do this (for `mem::forget`).
+        if old_size > MAX_REFCOUNT {
+            abort();
+        }
+
+        Self {
+            ptr: self.ptr,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<H, T, A: AllocRef> Drop for ThinWeak<H, T, A> {
+    fn drop(&mut self) {
+        let weak = &unsafe { self.ptr.as_ref() }.counts.weak;
+        if weak.fetch_sub(1, Release) == 1 {
+            acquire!(weak);
+            unsafe { ThinArc::<H, T, A, WithWeak>::dealloc(self.ptr) }
+        }
+    }
+}
+
+impl<H, T, A: AllocRef> fmt::Debug for ThinWeak<H, T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "(Weak)")
+    }
+}
+
+impl<H: fmt::Debug, T: fmt::Debug, A: AllocRef, C: RefCounts> fmt::Debug for ThinArc<H, T, A, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ThinArc")
+            .field("header", self.header())
+            .field("slice", &self.slice())
+            .finish()
+    }
+}
+
+impl<H, T, A0, A1, C0, C1> PartialEq<ThinArc<H, T, A1, C1>> for ThinArc<H, T, A0, C0>
+where
+    H: PartialEq,
+    T: PartialEq,
+    A0: AllocRef,
+    A1: AllocRef,
+    C0: RefCounts,
+    C1: RefCounts,
+{
+    #[inline]
+    fn eq(&self, other: &ThinArc<H, T, A1, C1>) -> bool {
+        self.header() == other.header() && self.slice() == other.slice()
+    }
+}
+
+impl<H: Eq, T: Eq, A: AllocRef, C: RefCounts> Eq for ThinArc<H, T, A, C> {}
+
+impl<H: Hash, T: Hash, A: AllocRef, C: RefCounts> Hash for ThinArc<H, T, A, C> {
+    fn hash<S: Hasher>(&self, state: &mut S) {
+        self.header().hash(state);
+        self.slice().hash(state);
+    }
+}
+
+impl<H, T, A: AllocRef, C: RefCounts> Unpin for ThinArc<H, T, A, C> {}
+
+/// Owns a `ThinArc` allocation while its elements are written.
+struct InitGuard<T, A: AllocRef> {
+    memory: NonNull<u8>,
+    layout: Layout,
+    elements: *mut T,
+    written: usize,
+    alloc: A,
+}
+
+impl<T, A: AllocRef> InitGuard<T, A> {
+    /// Gives up ownership of the allocation and returns the allocator.
+    fn into_alloc(self) -> A {
+        let this = mem::ManuallyDrop::new(self);
+        unsafe { ptr::read(&this.alloc) }
+    }
+}
+
+impl<T, A: AllocRef> Drop for InitGuard<T, A> {
+    fn drop(&mut self) {
+        unsafe {
+            ptr::drop_in_place(ptr::slice_from_raw_parts_mut(self.elements, self.written));
+            self.alloc.dealloc(self.memory, self.layout);
+        }
+    }
+}
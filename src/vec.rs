@@ -69,11 +69,11 @@
 //! [`vec!`]: ../macro.vec.html
 
 use crate::{
-    alloc::{handle_alloc_error, AllocRef, Global},
+    alloc::{AllocRef, Global},
+    borrow::{Borrow, BorrowMut},
     boxed::Box,
-    capacity_overflow,
-    clone::CloneIn,
-    collections::TryReserveError::{self, AllocError, CapacityOverflow},
+    clone::{CloneIn, TryClone},
+    collections::{TryPushError, TryReserveError},
     handle_reserve_error,
     iter::{FromIteratorIn, TryExtend},
     raw_vec::RawVec,
@@ -84,8 +84,8 @@ use core::{
     fmt,
     hash::{self, Hash},
     intrinsics::assume,
-    iter::{FromIterator, FusedIterator, TrustedLen},
-    mem,
+    iter::{FromIterator, FusedIterator, InPlaceIterable, SourceIter, TrustedLen},
+    mem::{self, MaybeUninit},
     ops::{
         self,
         Bound::{Excluded, Included, Unbounded},
@@ -97,6 +97,18 @@ use core::{
     slice::{self, SliceIndex},
 };
 
+pub mod array_vec;
+pub mod growth;
+pub mod small_vec;
+pub mod thin_vec;
+
+pub use self::{
+    array_vec::ArrayVec,
+    growth::{Doubling, Exact, Factor, Fibonacci, GrowthPolicy, PageRounded},
+    small_vec::SmallVec,
+    thin_vec::ThinVec,
+};
+
 /// A contiguous growable array type, written `Vec<T>` but pronounced 'vector'.
 ///
 /// # Examples
@@ -330,8 +342,8 @@ use core::{
 /// [`insert`]: Self::insert()
 /// [`reserve`]: Self::reserve
 /// [owned slice]: crate::boxed::Box
-pub struct Vec<T, A: AllocRef = Global> {
-    buf: RawVec<T, A>,
+pub struct Vec<T, A: AllocRef = Global, G: GrowthPolicy = Doubling> {
+    buf: RawVec<T, A, G>,
     len: usize,
 }
 
@@ -406,6 +418,42 @@ impl<T> Vec<T> {
         Self::with_capacity_in(capacity, Global)
     }
 
+    /// Constructs a new, empty `Vec<T>` with the specified capacity, with the buffer being filled
+    /// with `0` bytes.
+    ///
+    /// The zeroed memory is requested from the allocator, which can often hand out fresh pages
+    /// without writing to them. The zeroed elements are not part of the vector yet. If an
+    /// all-zero bit pattern is a valid `T`, they can be added with [`set_len`].
+    ///
+    /// [`set_len`]: Vec::set_len()
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use alloc_wg::vec::Vec;
+    ///
+    /// let mut vec = Vec::<u64>::with_capacity_zeroed(1024);
+    /// assert_eq!(vec.len(), 0);
+    ///
+    /// // SAFETY: the buffer is zeroed and zero is a valid `u64`.
+    /// unsafe { vec.set_len(1024) };
+    /// assert!(vec.iter().all(|&x| x == 0));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// * if the requested capacity exceeds `usize::MAX` bytes.
+    /// * on 32-bit platforms if the requested capacity exceeds `isize::MAX` bytes.
+    ///
+    /// # Aborts
+    ///
+    /// * on OOM
+    #[inline]
+    #[must_use]
+    pub fn with_capacity_zeroed(capacity: usize) -> Self {
+        Self::with_capacity_zeroed_in(capacity, Global)
+    }
+
     /// Creates a `Vec<T>` directly from the raw components of another vector.
     ///
     /// # Safety
@@ -472,7 +520,7 @@ impl<T> Vec<T> {
     }
 }
 
-impl<T, A: AllocRef> Vec<T, A> {
+impl<T, A: AllocRef, G: GrowthPolicy> Vec<T, A, G> {
     /// Like `new` but parameterized over the choice of allocator for the returned `Vec`.
     #[inline]
     pub fn new_in(a: A) -> Self {
@@ -505,7 +553,7 @@ impl<T, A: AllocRef> Vec<T, A> {
     ///
     /// # Errors
     ///
-    /// * `CapacityOverflow` if the requested capacity exceeds `usize::MAX` bytes.
+    /// * `InvalidLayout` if the requested capacity exceeds `usize::MAX` bytes.
     /// * `CapacityOverflow` on 32-bit platforms if the requested capacity exceeds `isize::MAX` bytes.
     /// * `AllocError` on OOM
     #[inline]
@@ -519,6 +567,37 @@ impl<T, A: AllocRef> Vec<T, A> {
         })
     }
 
+    /// Like `with_capacity_zeroed` but parameterized over the choice of allocator for the
+    /// returned `Vec`.
+    ///
+    /// # Panics
+    ///
+    /// * if the requested capacity exceeds `usize::MAX` bytes.
+    /// * on 32-bit platforms if the requested capacity exceeds `isize::MAX` bytes.
+    #[inline]
+    pub fn with_capacity_zeroed_in(capacity: usize, a: A) -> Self {
+        Self {
+            buf: RawVec::with_capacity_zeroed_in(capacity, a),
+            len: 0,
+        }
+    }
+
+    /// Like `with_capacity_zeroed` but parameterized over the choice of allocator for the
+    /// returned `Vec`.
+    ///
+    /// # Errors
+    ///
+    /// * `InvalidLayout` if the requested capacity exceeds `usize::MAX` bytes.
+    /// * `CapacityOverflow` on 32-bit platforms if the requested capacity exceeds `isize::MAX` bytes.
+    /// * `AllocError` on OOM
+    #[inline]
+    pub fn try_with_capacity_zeroed_in(capacity: usize, a: A) -> Result<Self, TryReserveError> {
+        Ok(Self {
+            buf: RawVec::try_with_capacity_zeroed_in(capacity, a)?,
+            len: 0,
+        })
+    }
+
     /// Like `from_raw_parts` but parameterized over the choice of allocator for the returned
     /// `Vec`.
     /// # Safety
@@ -529,6 +608,29 @@ impl<T, A: AllocRef> Vec<T, A> {
             len: length,
         }
     }
+}
+
+impl<T, A: AllocRef, G: GrowthPolicy> Vec<T, A, G> {
+    /// Switches the vector to the [`GrowthPolicy`] `H`. This neither allocates nor moves the
+    /// elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use alloc_wg::vec::{Factor, Vec};
+    ///
+    /// let mut v: Vec<u8, _, Factor<3, 2>> = Vec::with_capacity(10).with_policy();
+    /// v.extend_from_slice(&[0; 11]);
+    /// assert_eq!(v.capacity(), 15);
+    /// ```
+    #[inline]
+    pub fn with_policy<H: GrowthPolicy>(self) -> Vec<T, A, H> {
+        let me = mem::ManuallyDrop::new(self);
+        Vec {
+            buf: unsafe { ptr::read(&me.buf) }.with_policy(),
+            len: me.len,
+        }
+    }
 
     /// Decomposes a `Vec<T>` into its raw components.
     ///
@@ -714,10 +816,37 @@ impl<T, A: AllocRef> Vec<T, A> {
         self.buf.try_reserve_exact(self.len, additional)
     }
 
+    /// Tries to reserve the minimum capacity for exactly `additional` more elements without
+    /// moving the buffer, so pointers to the elements stay valid.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the capacity overflows, or if the allocator cannot extend the buffer
+    /// at its current address. Only allocators implementing [`ReallocInPlace`] can do so, and an
+    /// unallocated vector can never grow in place.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use alloc_wg::vec::Vec;
+    ///
+    /// let mut v: Vec<u8> = Vec::with_capacity(10);
+    /// v.try_reserve_in_place(10)?;
+    /// // `Global` cannot extend a block without moving it
+    /// assert!(v.try_reserve_in_place(11).is_err());
+    /// # Ok::<(), alloc_wg::collections::TryReserveError>(())
+    /// ```
+    ///
+    /// [`ReallocInPlace`]: crate::alloc::ReallocInPlace
+    pub fn try_reserve_in_place(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.buf.try_grow_in_place(self.len, additional)
+    }
+
     /// Shrinks the capacity of the vector as much as possible.
     ///
     /// It will drop down as close as possible to the length but the allocator
     /// may still inform the vector that there is space for a few more elements.
+    /// The growth policy may keep more capacity, see [`GrowthPolicy::shrink`].
     ///
     /// # Examples
     ///
@@ -735,15 +864,13 @@ impl<T, A: AllocRef> Vec<T, A> {
     ///
     /// Panics if the reallocation fails.
     pub fn shrink_to_fit(&mut self) {
-        if self.capacity() != self.len {
-            self.buf.shrink_to_fit(self.len);
-        }
+        handle_reserve_error(self.try_shrink_to_fit())
     }
 
     /// Same as `shrink_to_fit` but returns errors instead of panicking.
     pub fn try_shrink_to_fit(&mut self) -> Result<(), TryReserveError> {
         if self.capacity() != self.len {
-            self.buf.try_shrink_to_fit(self.len)?;
+            self.buf.try_shrink_amortized(self.len)?;
         }
         Ok(())
     }
@@ -775,12 +902,13 @@ impl<T, A: AllocRef> Vec<T, A> {
     /// * Panics if the given amount is *larger* than the current capacity.
     /// * Panics if the reallocation fails.
     pub fn shrink_to(&mut self, min_capacity: usize) {
-        self.buf.shrink_to_fit(cmp::max(self.len, min_capacity));
+        handle_reserve_error(self.try_shrink_to(min_capacity))
     }
 
     /// Same as `shrink_to` but returns errors instead of panicking.
     pub fn try_shrink_to(&mut self, min_capacity: usize) -> Result<(), TryReserveError> {
-        self.buf.try_shrink_to_fit(cmp::max(self.len, min_capacity))
+        self.buf
+            .try_shrink_amortized(cmp::max(self.len, min_capacity))
     }
 
     /// Converts the vector into [`Box<[T]>`][owned slice].
@@ -816,17 +944,15 @@ impl<T, A: AllocRef> Vec<T, A> {
     ///
     /// Panics if the reallocation fails.
     pub fn into_boxed_slice(self) -> Box<[T], A> {
-        match self.try_into_boxed_slice() {
-            Err(CapacityOverflow) => capacity_overflow(),
-            Err(AllocError { layout }) => handle_alloc_error(layout),
-            Ok(boxed) => boxed,
-        }
+        handle_reserve_error(self.try_into_boxed_slice())
     }
 
     /// Same as `into_boxed_slice` but returns errors instead of panicking.
     pub fn try_into_boxed_slice(mut self) -> Result<Box<[T], A>, TryReserveError> {
         unsafe {
-            self.try_shrink_to_fit()?;
+            if self.capacity() != self.len {
+                self.buf.try_shrink_to_fit(self.len)?;
+            }
             let len = self.len;
             let buf = ptr::read(&self.buf);
             mem::forget(self);
@@ -834,6 +960,46 @@ impl<T, A: AllocRef> Vec<T, A> {
         }
     }
 
+    /// Moves the vector into the allocator `alloc` without cloning its elements.
+    ///
+    /// If `alloc` can adopt memory from `A` (see [`AdoptAlloc`]), the buffer is handed over as
+    /// is. Otherwise, the elements are moved bitwise into a new buffer with a capacity of
+    /// `self.len()` and the old buffer is freed with `A`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the new buffer cannot be allocated. The vector is handed back
+    /// unchanged together with the error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use alloc_wg::{alloc::Global, vec};
+    ///
+    /// let v = vec![1, 2, 3];
+    /// let ptr = v.as_ptr();
+    /// let v = v.transfer_in(Global)?;
+    /// assert_eq!(v, [1, 2, 3]);
+    /// assert_eq!(v.as_ptr(), ptr);
+    /// # Ok::<(), alloc_wg::collections::TryReserveError>(())
+    /// ```
+    ///
+    /// [`AdoptAlloc`]: crate::alloc::AdoptAlloc
+    pub fn transfer_in<B: AllocRef>(
+        mut self,
+        alloc: B,
+    ) -> Result<Vec<T, B, G>, TryPushError<Self>> {
+        let len = self.len;
+        match unsafe { self.buf.try_transfer_in(len, alloc) } {
+            Ok(buf) => {
+                // the elements have been moved out
+                self.len = 0;
+                Ok(Vec { buf, len })
+            }
+            Err(err) => Err(TryPushError::new(self, err)),
+        }
+    }
+
     /// Shortens the vector, keeping the first `len` elements and dropping
     /// the rest.
     ///
@@ -1095,6 +1261,45 @@ impl<T, A: AllocRef> Vec<T, A> {
         self.len = new_len;
     }
 
+    /// Returns the remaining spare capacity of the vector as a slice of `MaybeUninit<T>`.
+    ///
+    /// The returned slice can be used to fill the vector with data (e.g. by reading from a file)
+    /// before marking the data as initialized using the [`set_len`] method.
+    ///
+    /// [`set_len`]: Vec::set_len()
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(maybe_uninit_extra)]
+    /// use alloc_wg::vec::Vec;
+    ///
+    /// // Allocate vector big enough for 10 elements.
+    /// let mut v = Vec::with_capacity(10);
+    ///
+    /// // Fill in the first 3 elements.
+    /// let uninit = v.spare_capacity_mut();
+    /// uninit[0].write(0);
+    /// uninit[1].write(1);
+    /// uninit[2].write(2);
+    ///
+    /// // Mark the first 3 elements of the vector as being initialized.
+    /// unsafe {
+    ///     v.set_len(3);
+    /// }
+    ///
+    /// assert_eq!(&v, &[0, 1, 2]);
+    /// ```
+    #[inline]
+    pub fn spare_capacity_mut(&mut self) -> &mut [MaybeUninit<T>] {
+        unsafe {
+            slice::from_raw_parts_mut(
+                self.as_mut_ptr().add(self.len) as *mut MaybeUninit<T>,
+                self.buf.capacity() - self.len,
+            )
+        }
+    }
+
     /// Removes an element from the vector and returns it.
     ///
     /// The removed element is replaced by the last element of the vector.
@@ -1150,21 +1355,24 @@ impl<T, A: AllocRef> Vec<T, A> {
     /// assert_eq!(vec, [1, 4, 2, 3, 5]);
     /// ```
     pub fn insert(&mut self, index: usize, element: T) {
-        match self.try_insert(index, element) {
-            Err(CapacityOverflow) => capacity_overflow(),
-            Err(AllocError { layout, .. }) => handle_alloc_error(layout),
-            Ok(()) => { /* yay */ }
-        }
+        handle_reserve_error(
+            self.try_insert(index, element)
+                .map_err(TryPushError::into_error),
+        );
     }
 
     /// Same as `insert` but returns errors instead of panicking
-    pub fn try_insert(&mut self, index: usize, element: T) -> Result<(), TryReserveError> {
+    ///
+    /// On failure, `element` is handed back inside the [`TryPushError`].
+    pub fn try_insert(&mut self, index: usize, element: T) -> Result<(), TryPushError<T>> {
         let len = self.len();
         assert!(index <= len);
 
         // space for the new element
         if len == self.buf.capacity() {
-            self.try_reserve(1)?;
+            if let Err(error) = self.try_reserve(1) {
+                return Err(TryPushError::new(element, error));
+            }
         }
 
         unsafe {
@@ -1332,11 +1540,7 @@ impl<T, A: AllocRef> Vec<T, A> {
     /// Panics if the reallocation fails.
     #[inline]
     pub fn push(&mut self, value: T) {
-        match self.try_push(value) {
-            Err(CapacityOverflow) => capacity_overflow(),
-            Err(AllocError { layout, .. }) => handle_alloc_error(layout),
-            Ok(()) => { /* yay */ }
-        }
+        handle_reserve_error(self.try_push(value).map_err(TryPushError::into_error));
     }
 
     unsafe fn push_unchecked(&mut self, value: T)
@@ -1351,12 +1555,16 @@ impl<T, A: AllocRef> Vec<T, A> {
     }
 
     /// Same as `push` but returns errors instead of panicking
+    ///
+    /// On failure, `value` is handed back inside the [`TryPushError`].
     #[inline]
-    pub fn try_push(&mut self, value: T) -> Result<(), TryReserveError> {
+    pub fn try_push(&mut self, value: T) -> Result<(), TryPushError<T>> {
         // This will panic or abort if we would allocate > isize::MAX bytes
         // or if the length increment would overflow for zero-sized types.
         if self.len == self.buf.capacity() {
-            self.try_reserve(1)?;
+            if let Err(error) = self.try_reserve(1) {
+                return Err(TryPushError::new(value, error));
+            }
         }
         unsafe {
             self.push_unchecked(value);
@@ -1364,6 +1572,82 @@ impl<T, A: AllocRef> Vec<T, A> {
         Ok(())
     }
 
+    /// Appends an element to the back of a collection, which is initialized in place by `init`.
+    ///
+    /// The memory is reserved before `init` is called, and `init` receives the uninitialized slot
+    /// inside the vector, so even large elements are never built on the stack. It has to return
+    /// the slot as initialized reference, e.g. by calling [`MaybeUninit::assume_init_mut`]. If
+    /// `init` panics, the vector is left unchanged.
+    ///
+    /// # Panics
+    ///
+    /// * if the new capacity exceeds `isize::MAX` bytes.
+    /// * if `init` returns a reference to anything else than the slot.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(maybe_uninit_ref)]
+    /// use alloc_wg::vec::Vec;
+    /// use core::ptr;
+    ///
+    /// let mut vec = Vec::<[u8; 4096]>::new();
+    /// vec.push_with(|slot| unsafe {
+    ///     ptr::write_bytes(slot.as_mut_ptr(), 7, 1);
+    ///     slot.assume_init_mut()
+    /// });
+    /// assert_eq!(vec[0][4095], 7);
+    /// ```
+    ///
+    /// [`MaybeUninit::assume_init_mut`]: core::mem::MaybeUninit::assume_init_mut
+    #[inline]
+    pub fn push_with<F>(&mut self, init: F)
+    where
+        F: for<'a> FnOnce(&'a mut MaybeUninit<T>) -> &'a mut T,
+    {
+        handle_reserve_error(self.try_emplace_with(init).map(|_| ()))
+    }
+
+    /// Same as `push_with` but returns errors instead of panicking, and returns a reference to
+    /// the new element.
+    ///
+    /// `init` is not called if the reservation fails.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `init` returns a reference to anything else than the slot.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(maybe_uninit_extra)]
+    /// use alloc_wg::vec;
+    ///
+    /// let mut vec = vec![1, 2];
+    /// *vec.try_emplace_with(|slot| slot.write(3))? += 1;
+    /// assert_eq!(vec, [1, 2, 4]);
+    /// # Ok::<(), alloc_wg::collections::TryReserveError>(())
+    /// ```
+    pub fn try_emplace_with<F>(&mut self, init: F) -> Result<&mut T, TryReserveError>
+    where
+        F: for<'a> FnOnce(&'a mut MaybeUninit<T>) -> &'a mut T,
+    {
+        if self.len == self.buf.capacity() {
+            self.try_reserve(1)?;
+        }
+        unsafe {
+            let len = self.len;
+            let slot = self.as_mut_ptr().add(len);
+            assert!(
+                ptr::eq(init(&mut *(slot as *mut MaybeUninit<T>)), slot),
+                "`init` has to return the initialized slot"
+            );
+            // NB can't overflow since we would have had to alloc the address space
+            self.len = len + 1;
+            Ok(&mut *slot)
+        }
+    }
+
     /// Removes the last element from a vector and returns it, or [`None`][] if it
     /// is empty.
     ///
@@ -1411,11 +1695,7 @@ impl<T, A: AllocRef> Vec<T, A> {
     /// Panics if the reallocation fails.
     #[inline]
     pub fn append(&mut self, other: &mut Self) {
-        match self.try_append(other) {
-            Err(CapacityOverflow) => capacity_overflow(),
-            Err(AllocError { layout, .. }) => handle_alloc_error(layout),
-            Ok(()) => { /* yay */ }
-        }
+        handle_reserve_error(self.try_append(other));
     }
 
     /// Same as `append` but returns errors instead of panicking.
@@ -1467,7 +1747,7 @@ impl<T, A: AllocRef> Vec<T, A> {
     /// v.drain(..);
     /// assert_eq!(v, &[]);
     /// ```
-    pub fn drain<R>(&mut self, range: R) -> Drain<'_, T, A>
+    pub fn drain<R>(&mut self, range: R) -> Drain<'_, T, A, G>
     where
         R: RangeBounds<usize>,
     {
@@ -1590,11 +1870,7 @@ impl<T, A: AllocRef> Vec<T, A> {
     where
         A: Clone,
     {
-        match self.try_split_off(at) {
-            Err(CapacityOverflow) => capacity_overflow(),
-            Err(AllocError { layout, .. }) => handle_alloc_error(layout),
-            Ok(vec) => vec,
-        }
+        handle_reserve_error(self.try_split_off(at))
     }
 
     /// Same as `split_off` but returns errors instead of panicking.
@@ -1606,7 +1882,7 @@ impl<T, A: AllocRef> Vec<T, A> {
         assert!(at <= self.len(), "`at` out of bounds");
 
         let other_len = self.len - at;
-        let mut other = Self::try_with_capacity_in(other_len, self.alloc_ref().clone())?;
+        let mut other: Self = Vec::try_with_capacity_in(other_len, self.alloc_ref().clone())?;
 
         // Unsafely `set_len` and copy items to `other`.
         unsafe {
@@ -1659,11 +1935,7 @@ impl<T, A: AllocRef> Vec<T, A> {
     where
         F: FnMut() -> T,
     {
-        match self.try_resize_with(new_len, f) {
-            Err(CapacityOverflow) => capacity_overflow(),
-            Err(AllocError { layout, .. }) => handle_alloc_error(layout),
-            Ok(()) => { /* yay */ }
-        }
+        handle_reserve_error(self.try_resize_with(new_len, f));
     }
 
     /// Same as `resize_with` but returns errors instead of panicking.
@@ -1745,7 +2017,7 @@ impl<T, A: AllocRef> Vec<T, A> {
     }
 }
 
-impl<T: Clone, A: AllocRef> Vec<T, A> {
+impl<T: Clone, A: AllocRef, G: GrowthPolicy> Vec<T, A, G> {
     /// Resizes the `Vec` in-place so that `len` is equal to `new_len`.
     ///
     /// If `new_len` is greater than `len`, the `Vec` is extended by the
@@ -1862,7 +2134,7 @@ impl<T, F: FnMut() -> T> ExtendWith<T> for ExtendFunc<F> {
     }
 }
 
-impl<T, A: AllocRef> Vec<T, A> {
+impl<T, A: AllocRef, G: GrowthPolicy> Vec<T, A, G> {
     /// Same as `extend_with` but returns errors instead of panicking.
     fn try_extend_with<E: ExtendWith<T>>(
         &mut self,
@@ -1930,7 +2202,7 @@ impl Drop for SetLenOnDrop<'_> {
     }
 }
 
-impl<T: PartialEq, A: AllocRef> Vec<T, A> {
+impl<T: PartialEq, A: AllocRef, G: GrowthPolicy> Vec<T, A, G> {
     /// Removes consecutive repeated elements in the vector according to the
     /// [`PartialEq`] trait implementation.
     ///
@@ -2124,7 +2396,7 @@ unsafe impl<T: ?Sized> IsZero for Option<Box<T>> {
 // Common trait implementations for Vec
 ////////////////////////////////////////////////////////////////////////////////
 
-impl<T: Clone, A> Clone for Vec<T, A>
+impl<T: Clone, A, G: GrowthPolicy> Clone for Vec<T, A, G>
 where
     A: AllocRef + Clone,
 {
@@ -2135,12 +2407,22 @@ where
     }
 }
 
+impl<T: Clone, A, G: GrowthPolicy> TryClone for Vec<T, A, G>
+where
+    A: AllocRef + Clone,
+{
+    #[inline]
+    fn try_clone(&self) -> Result<Self, TryReserveError> {
+        self.try_clone_in(self.alloc_ref().clone())
+    }
+}
+
 #[allow(clippy::use_self)]
-impl<T: Clone, A: AllocRef, B: AllocRef> CloneIn<B> for Vec<T, A> {
-    type Cloned = Vec<T, B>;
+impl<T: Clone, A: AllocRef, B: AllocRef, G: GrowthPolicy> CloneIn<B> for Vec<T, A, G> {
+    type Cloned = Vec<T, B, G>;
 
     fn clone_in(&self, a: B) -> Self::Cloned {
-        let mut v = Vec::with_capacity_in(self.len(), a);
+        let mut v: Self::Cloned = Vec::with_capacity_in(self.len(), a);
 
         self.iter()
             .cloned()
@@ -2149,7 +2431,7 @@ impl<T: Clone, A: AllocRef, B: AllocRef> CloneIn<B> for Vec<T, A> {
     }
 
     fn try_clone_in(&self, a: B) -> Result<Self::Cloned, TryReserveError> {
-        let mut v = Vec::try_with_capacity_in(self.len(), a)?;
+        let mut v: Self::Cloned = Vec::try_with_capacity_in(self.len(), a)?;
 
         self.iter()
             .cloned()
@@ -2158,14 +2440,14 @@ impl<T: Clone, A: AllocRef, B: AllocRef> CloneIn<B> for Vec<T, A> {
     }
 }
 
-impl<T: Hash, A: AllocRef> Hash for Vec<T, A> {
+impl<T: Hash, A: AllocRef, G: GrowthPolicy> Hash for Vec<T, A, G> {
     #[inline]
     fn hash<H: hash::Hasher>(&self, state: &mut H) {
         Hash::hash(&**self, state)
     }
 }
 
-impl<T, A: AllocRef, I: SliceIndex<[T]>> Index<I> for Vec<T, A> {
+impl<T, A: AllocRef, G: GrowthPolicy, I: SliceIndex<[T]>> Index<I> for Vec<T, A, G> {
     type Output = I::Output;
 
     #[inline]
@@ -2175,7 +2457,7 @@ impl<T, A: AllocRef, I: SliceIndex<[T]>> Index<I> for Vec<T, A> {
     }
 }
 
-impl<T, A: AllocRef, I: SliceIndex<[T]>> IndexMut<I> for Vec<T, A> {
+impl<T, A: AllocRef, G: GrowthPolicy, I: SliceIndex<[T]>> IndexMut<I> for Vec<T, A, G> {
     #[inline]
     #[must_use]
     fn index_mut(&mut self, index: I) -> &mut Self::Output {
@@ -2183,7 +2465,7 @@ impl<T, A: AllocRef, I: SliceIndex<[T]>> IndexMut<I> for Vec<T, A> {
     }
 }
 
-impl<T, A: AllocRef> ops::Deref for Vec<T, A> {
+impl<T, A: AllocRef, G: GrowthPolicy> ops::Deref for Vec<T, A, G> {
     type Target = [T];
 
     #[must_use]
@@ -2192,7 +2474,7 @@ impl<T, A: AllocRef> ops::Deref for Vec<T, A> {
     }
 }
 
-impl<T, A: AllocRef> ops::DerefMut for Vec<T, A> {
+impl<T, A: AllocRef, G: GrowthPolicy> ops::DerefMut for Vec<T, A, G> {
     #[must_use]
     fn deref_mut(&mut self) -> &mut [T] {
         unsafe { slice::from_raw_parts_mut(self.as_mut_ptr(), self.len) }
@@ -2203,24 +2485,24 @@ impl<T> FromIterator<T> for Vec<T> {
     #[inline]
     #[must_use]
     fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
-        <Self as SpecExtend<T, I::IntoIter, Global>>::from_iter_in(iter.into_iter(), Global)
+        <Self as SpecFromIter<T, I::IntoIter, Global>>::from_iter_in(iter.into_iter(), Global)
     }
 }
 
-impl<T, A: AllocRef> FromIteratorIn<T, A> for Vec<T, A> {
+impl<T, A: AllocRef, G: GrowthPolicy> FromIteratorIn<T, A> for Vec<T, A, G> {
     #[inline]
     #[must_use]
     fn from_iter_in<I: IntoIterator<Item = T>>(iter: I, a: A) -> Self {
-        <Self as SpecExtend<T, I::IntoIter, A>>::from_iter_in(iter.into_iter(), a)
+        <Self as SpecFromIter<T, I::IntoIter, A>>::from_iter_in(iter.into_iter(), a)
     }
 
     #[inline]
     fn try_from_iter_in<I: IntoIterator<Item = T>>(iter: I, a: A) -> Result<Self, TryReserveError> {
-        <Self as SpecExtend<T, I::IntoIter, A>>::try_from_iter_in(iter.into_iter(), a)
+        <Self as SpecFromIter<T, I::IntoIter, A>>::try_from_iter_in(iter.into_iter(), a)
     }
 }
 
-impl<T, A: AllocRef> IntoIterator for Vec<T, A> {
+impl<T, A: AllocRef, G: GrowthPolicy> IntoIterator for Vec<T, A, G> {
     type Item = T;
     type IntoIter = IntoIter<T, A>;
 
@@ -2261,7 +2543,7 @@ impl<T, A: AllocRef> IntoIterator for Vec<T, A> {
     }
 }
 
-impl<'a, T, A: AllocRef> IntoIterator for &'a Vec<T, A> {
+impl<'a, T, A: AllocRef, G: GrowthPolicy> IntoIterator for &'a Vec<T, A, G> {
     type Item = &'a T;
     type IntoIter = slice::Iter<'a, T>;
 
@@ -2271,7 +2553,7 @@ impl<'a, T, A: AllocRef> IntoIterator for &'a Vec<T, A> {
     }
 }
 
-impl<'a, T, A: AllocRef> IntoIterator for &'a mut Vec<T, A> {
+impl<'a, T, A: AllocRef, G: GrowthPolicy> IntoIterator for &'a mut Vec<T, A, G> {
     type Item = &'a mut T;
     type IntoIter = slice::IterMut<'a, T>;
 
@@ -2280,7 +2562,7 @@ impl<'a, T, A: AllocRef> IntoIterator for &'a mut Vec<T, A> {
     }
 }
 
-impl<T, A> Extend<T> for Vec<T, A>
+impl<T, A, G: GrowthPolicy> Extend<T> for Vec<T, A, G>
 where
     A: AllocRef,
 {
@@ -2290,7 +2572,7 @@ where
     }
 }
 
-impl<T, A: AllocRef> TryExtend<T> for Vec<T, A> {
+impl<T, A: AllocRef, G: GrowthPolicy> TryExtend<T> for Vec<T, A, G> {
     type Err = TryReserveError;
 
     #[inline]
@@ -2315,10 +2597,11 @@ pub(crate) trait SpecExtend<T, I, A: AllocRef>: Sized {
     fn try_spec_extend(&mut self, iter: I) -> Result<(), TryReserveError>;
 }
 
-impl<T, I, A> SpecExtend<T, I, A> for Vec<T, A>
+impl<T, I, A, G> SpecExtend<T, I, A> for Vec<T, A, G>
 where
     I: Iterator<Item = T>,
     A: AllocRef,
+    G: GrowthPolicy,
 {
     default fn try_from_iter_in(mut iter: I, a: A) -> Result<Self, TryReserveError> {
         // Unroll the first iteration, as the vector is going to be
@@ -2327,10 +2610,10 @@ where
         // vector being full in the few subsequent loop iterations.
         // So we get better branch prediction.
         let mut vector = match iter.next() {
-            None => return Ok(Self::new_in(a)),
+            None => return Ok(Vec::new_in(a)),
             Some(element) => {
                 let (lower, _) = iter.size_hint();
-                let mut vector = Self::try_with_capacity_in(lower.saturating_add(1), a)?;
+                let mut vector: Self = Vec::try_with_capacity_in(lower.saturating_add(1), a)?;
                 unsafe {
                     ptr::write(vector.get_unchecked_mut(0), element);
                     vector.set_len(1);
@@ -2347,12 +2630,12 @@ where
     }
 }
 
-impl<T, I, A: AllocRef> SpecExtend<T, I, A> for Vec<T, A>
+impl<T, I, A: AllocRef, G: GrowthPolicy> SpecExtend<T, I, A> for Vec<T, A, G>
 where
     I: TrustedLen<Item = T>,
 {
     default fn try_from_iter_in(iter: I, a: A) -> Result<Self, TryReserveError> {
-        let mut vector = Self::new_in(a);
+        let mut vector: Self = Vec::new_in(a);
         vector.try_spec_extend(iter)?;
         Ok(vector)
     }
@@ -2387,7 +2670,7 @@ where
     }
 }
 
-impl<T, A: AllocRef> SpecExtend<T, IntoIter<T, A>, A> for Vec<T, A> {
+impl<T, A: AllocRef, G: GrowthPolicy> SpecExtend<T, IntoIter<T, A>, A> for Vec<T, A, G> {
     fn try_from_iter_in(mut iter: IntoIter<T, A>, alloc: A) -> Result<Self, TryReserveError> {
         // A common case is passing a vector into a function which immediately
         // re-collects into a vector. We can short circuit this if the IntoIter
@@ -2395,7 +2678,7 @@ impl<T, A: AllocRef> SpecExtend<T, IntoIter<T, A>, A> for Vec<T, A> {
         let ptr: *const T = iter.buf.ptr();
         if ptr == iter.ptr {
             unsafe {
-                let vec = Self::from_raw_parts_in(
+                let vec = Vec::from_raw_parts_in(
                     iter.buf.ptr(),
                     iter.len(),
                     iter.buf.capacity(),
@@ -2405,7 +2688,7 @@ impl<T, A: AllocRef> SpecExtend<T, IntoIter<T, A>, A> for Vec<T, A> {
                 Ok(vec)
             }
         } else {
-            let mut vector = Self::new_in(alloc);
+            let mut vector: Self = Vec::new_in(alloc);
             vector.try_spec_extend(iter)?;
             Ok(vector)
         }
@@ -2420,7 +2703,7 @@ impl<T, A: AllocRef> SpecExtend<T, IntoIter<T, A>, A> for Vec<T, A> {
     }
 }
 
-impl<'a, T: 'a, I, A: AllocRef> SpecExtend<&'a T, I, A> for Vec<T, A>
+impl<'a, T: 'a, I, A: AllocRef, G: GrowthPolicy> SpecExtend<&'a T, I, A> for Vec<T, A, G>
 where
     I: Iterator<Item = &'a T>,
     T: Clone,
@@ -2434,7 +2717,8 @@ where
     }
 }
 
-impl<'a, T: 'a, A: AllocRef> SpecExtend<&'a T, slice::Iter<'a, T>, A> for Vec<T, A>
+impl<'a, T: 'a, A: AllocRef, G: GrowthPolicy> SpecExtend<&'a T, slice::Iter<'a, T>, A>
+    for Vec<T, A, G>
 where
     T: Copy,
 {
@@ -2450,7 +2734,128 @@ where
     }
 }
 
-impl<T, A: AllocRef> Vec<T, A> {
+/// Specialization trait used by `FromIterator` and `FromIteratorIn`. It sits on top of
+/// `SpecExtend` so the in-place specialization does not overlap with the `TrustedLen` one.
+pub(crate) trait SpecFromIter<T, I, A: AllocRef>: Sized {
+    #[inline]
+    fn from_iter_in(iter: I, a: A) -> Self {
+        handle_reserve_error(Self::try_from_iter_in(iter, a))
+    }
+
+    fn try_from_iter_in(iter: I, a: A) -> Result<Self, TryReserveError>;
+}
+
+impl<T, I, A, G> SpecFromIter<T, I, A> for Vec<T, A, G>
+where
+    I: Iterator<Item = T>,
+    A: AllocRef,
+    G: GrowthPolicy,
+{
+    default fn try_from_iter_in(iter: I, a: A) -> Result<Self, TryReserveError> {
+        <Self as SpecExtend<T, I, A>>::try_from_iter_in(iter, a)
+    }
+}
+
+/// Collects `vec::IntoIter` pipelines such as `v.into_iter().map(f).filter(g)` into the
+/// allocation of the source vector, as long as the output element has the same size and
+/// alignment as the source element.
+///
+/// The resulting vector keeps the source allocator; the allocator passed to `collect_in` is
+/// dropped instead.
+impl<T, I, S, A, G> SpecFromIter<T, I, A> for Vec<T, A, G>
+where
+    I: Iterator<Item = T> + InPlaceIterable + SourceIter<Source = S>,
+    S: AsIntoIter<Alloc = A>,
+    A: AllocRef,
+    G: GrowthPolicy,
+{
+    #[allow(clippy::cast_sign_loss)]
+    default fn try_from_iter_in(mut iterator: I, a: A) -> Result<Self, TryReserveError> {
+        // Requirements which cannot be expressed through trait bounds:
+        // - no ZSTs, as there is no allocation to reuse
+        // - equal size and alignment, as required by the `AllocRef` contract
+        if mem::size_of::<T>() == 0
+            || mem::size_of::<T>() != mem::size_of::<S::Item>()
+            || mem::align_of::<T>() != mem::align_of::<S::Item>()
+        {
+            return <Self as SpecExtend<T, I, A>>::try_from_iter_in(iterator, a);
+        }
+
+        let (dst_buf, dst_end, cap) = unsafe {
+            let inner = iterator.as_inner().as_into_iter();
+            (
+                inner.buf.ptr() as *mut T,
+                inner.end as *const T,
+                inner.buf.capacity(),
+            )
+        };
+
+        // `try_fold` only takes `&mut self` and lets us thread the write pointer through it.
+        let sink = InPlaceDrop {
+            inner: dst_buf,
+            dst: dst_buf,
+        };
+        let sink = iterator
+            .try_fold::<_, _, Result<_, !>>(sink, write_in_place_with_drop(dst_end))
+            .unwrap();
+        // iteration succeeded, keep the written elements
+        let dst = mem::ManuallyDrop::new(sink).dst;
+
+        let src = unsafe { iterator.as_inner().as_into_iter() };
+        debug_assert_eq!(src.buf.ptr() as *mut T, dst_buf);
+        // drop the remaining source elements and take the allocation out of the iterator, which
+        // is left with an empty buffer in the passed allocator
+        src.drop_remaining();
+        let alloc = src.forget_allocation(a);
+
+        unsafe {
+            let len = offset_from(dst, dst_buf) as usize;
+            Ok(Vec::from_raw_parts_in(dst_buf, len, cap, alloc))
+        }
+    }
+}
+
+impl<T, A: AllocRef, G: GrowthPolicy> SpecFromIter<T, IntoIter<T, A>, A> for Vec<T, A, G> {
+    fn try_from_iter_in(iter: IntoIter<T, A>, a: A) -> Result<Self, TryReserveError> {
+        <Self as SpecExtend<T, IntoIter<T, A>, A>>::try_from_iter_in(iter, a)
+    }
+}
+
+/// Drops the elements written so far if collecting in place panics.
+struct InPlaceDrop<T> {
+    inner: *mut T,
+    dst: *mut T,
+}
+
+impl<T> Drop for InPlaceDrop<T> {
+    #[allow(clippy::cast_sign_loss)]
+    fn drop(&mut self) {
+        unsafe {
+            let len = offset_from(self.dst, self.inner) as usize;
+            ptr::drop_in_place(slice::from_raw_parts_mut(self.inner, len));
+        }
+    }
+}
+
+fn write_in_place_with_drop<T>(
+    src_end: *const T,
+) -> impl FnMut(InPlaceDrop<T>, T) -> Result<InPlaceDrop<T>, !> {
+    move |mut sink, item| {
+        // `InPlaceIterable` guarantees the write pointer never overtakes the read pointer, all
+        // we can check here is that it stays in range.
+        debug_assert!(
+            sink.dst as *const T <= src_end,
+            "InPlaceIterable contract violation"
+        );
+        unsafe {
+            ptr::write(sink.dst, item);
+            sink.dst = sink.dst.add(1);
+        }
+        Ok(sink)
+    }
+}
+
+impl<T, A: AllocRef, G: GrowthPolicy> Vec<T, A, G> {
     fn try_extend_desugared<I: Iterator<Item = T>>(
         &mut self,
         mut iterator: I,
@@ -2513,7 +2918,7 @@ impl<T, A: AllocRef> Vec<T, A> {
     /// assert_eq!(u, &[1, 2]);
     /// ```
     #[inline]
-    pub fn splice<R, I>(&mut self, range: R, replace_with: I) -> Splice<'_, I::IntoIter, A>
+    pub fn splice<R, I>(&mut self, range: R, replace_with: I) -> Splice<'_, I::IntoIter, A, G>
     where
         A: AllocRef,
         R: RangeBounds<usize>,
@@ -2572,7 +2977,7 @@ impl<T, A: AllocRef> Vec<T, A> {
     /// assert_eq!(evens, vec![2, 4, 6, 8, 14]);
     /// assert_eq!(odds, vec![1, 3, 5, 9, 11, 13, 15]);
     /// ```
-    pub fn drain_filter<F>(&mut self, filter: F) -> DrainFilter<'_, T, F, A>
+    pub fn drain_filter<F>(&mut self, filter: F) -> DrainFilter<'_, T, F, A, G>
     where
         A: AllocRef,
         F: FnMut(&mut T) -> bool,
@@ -2601,7 +3006,7 @@ impl<T, A: AllocRef> Vec<T, A> {
 /// append the entire slice at once.
 ///
 /// [`copy_from_slice`]: ../../std/primitive.slice.html#method.copy_from_slice
-impl<'a, T: 'a + Copy, A> Extend<&'a T> for Vec<T, A>
+impl<'a, T: 'a + Copy, A, G: GrowthPolicy> Extend<&'a T> for Vec<T, A, G>
 where
     A: AllocRef,
 {
@@ -2610,7 +3015,7 @@ where
     }
 }
 
-impl<'a, T: 'a + Copy, A> TryExtend<&'a T> for Vec<T, A>
+impl<'a, T: 'a + Copy, A, G: GrowthPolicy> TryExtend<&'a T> for Vec<T, A, G>
 where
     A: AllocRef,
 {
@@ -2637,14 +3042,14 @@ macro_rules! __impl_slice_eq1 {
     }
 }
 
-__impl_slice_eq1! { [A, B] Vec<T, A>, Vec<U, B>, A: AllocRef, B: AllocRef }
-__impl_slice_eq1! { [A] Vec<T, A>, &[U], A: AllocRef }
-__impl_slice_eq1! { [A] Vec<T, A>, &mut [U], A: AllocRef }
+__impl_slice_eq1! { [A, B, G, H] Vec<T, A, G>, Vec<U, B, H>, A: AllocRef, B: AllocRef, G: GrowthPolicy, H: GrowthPolicy }
+__impl_slice_eq1! { [A, G] Vec<T, A, G>, &[U], A: AllocRef, G: GrowthPolicy }
+__impl_slice_eq1! { [A, G] Vec<T, A, G>, &mut [U], A: AllocRef, G: GrowthPolicy }
 // __impl_slice_eq1! { [] Cow<'_, [A]>, &[B], A: Clone }
 // __impl_slice_eq1! { [] Cow<'_, [A]>, &mut [B], A: Clone }
 // __impl_slice_eq1! { [] Cow<'_, [A]>, Vec<B>, A: Clone }
-__impl_slice_eq1! { [A, const N: usize] Vec<T, A>, [U; N], A: AllocRef }
-__impl_slice_eq1! { [A, const N: usize] Vec<T, A>, &[U; N], A: AllocRef }
+__impl_slice_eq1! { [A, G, const N: usize] Vec<T, A, G>, [U; N], A: AllocRef, G: GrowthPolicy }
+__impl_slice_eq1! { [A, G, const N: usize] Vec<T, A, G>, &[U; N], A: AllocRef, G: GrowthPolicy }
 
 // NOTE: some less important impls are omitted to reduce code bloat
 // FIXME(Centril): Reconsider this?
@@ -2654,18 +3059,25 @@ __impl_slice_eq1! { [A, const N: usize] Vec<T, A>, &[U; N], A: AllocRef }
 //__impl_slice_eq1! { [const N: usize] Cow<'a, [A]>, &mut [B; N], [B; N]: LengthAtMost32 }
 
 /// Implements comparison of vectors, lexicographically.
-impl<T: PartialOrd, A: AllocRef, B: AllocRef> PartialOrd<Vec<T, B>> for Vec<T, A> {
+impl<T, A, B, G, H> PartialOrd<Vec<T, B, H>> for Vec<T, A, G>
+where
+    T: PartialOrd,
+    A: AllocRef,
+    B: AllocRef,
+    G: GrowthPolicy,
+    H: GrowthPolicy,
+{
     #[inline]
     #[must_use]
-    fn partial_cmp(&self, other: &Vec<T, B>) -> Option<Ordering> {
+    fn partial_cmp(&self, other: &Vec<T, B, H>) -> Option<Ordering> {
         PartialOrd::partial_cmp(&**self, &**other)
     }
 }
 
-impl<T: Eq, A: AllocRef> Eq for Vec<T, A> {}
+impl<T: Eq, A: AllocRef, G: GrowthPolicy> Eq for Vec<T, A, G> {}
 
 /// Implements ordering of vectors, lexicographically.
-impl<T: Ord, A: AllocRef> Ord for Vec<T, A> {
+impl<T: Ord, A: AllocRef, G: GrowthPolicy> Ord for Vec<T, A, G> {
     #[inline]
     #[must_use]
     fn cmp(&self, other: &Self) -> Ordering {
@@ -2673,7 +3085,7 @@ impl<T: Ord, A: AllocRef> Ord for Vec<T, A> {
     }
 }
 
-unsafe impl<#[may_dangle] T, A: AllocRef> Drop for Vec<T, A> {
+unsafe impl<#[may_dangle] T, A: AllocRef, G: GrowthPolicy> Drop for Vec<T, A, G> {
     fn drop(&mut self) {
         unsafe {
             // use drop for [T]
@@ -2683,51 +3095,65 @@ unsafe impl<#[may_dangle] T, A: AllocRef> Drop for Vec<T, A> {
     }
 }
 
-impl<T, A: AllocRef> Default for Vec<T, A>
+impl<T, A: AllocRef, G: GrowthPolicy> Default for Vec<T, A, G>
 where
     A: Default,
 {
     /// Creates an empty `Vec<T>`.
     #[must_use]
     fn default() -> Self {
-        Self::new_in(A::default())
+        Vec::new_in(A::default())
     }
 }
 
-impl<T: fmt::Debug, A: AllocRef> fmt::Debug for Vec<T, A> {
+impl<T: fmt::Debug, A: AllocRef, G: GrowthPolicy> fmt::Debug for Vec<T, A, G> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         fmt::Debug::fmt(&**self, f)
     }
 }
 
-impl<T, A: AllocRef> AsRef<Vec<T, A>> for Vec<T, A> {
+impl<T, A: AllocRef, G: GrowthPolicy> AsRef<Vec<T, A, G>> for Vec<T, A, G> {
     #[must_use]
     fn as_ref(&self) -> &Self {
         self
     }
 }
 
-impl<T, A: AllocRef> AsMut<Vec<T, A>> for Vec<T, A> {
+impl<T, A: AllocRef, G: GrowthPolicy> AsMut<Vec<T, A, G>> for Vec<T, A, G> {
     #[must_use]
     fn as_mut(&mut self) -> &mut Self {
         self
     }
 }
 
-impl<T, A: AllocRef> AsRef<[T]> for Vec<T, A> {
+impl<T, A: AllocRef, G: GrowthPolicy> AsRef<[T]> for Vec<T, A, G> {
     #[must_use]
     fn as_ref(&self) -> &[T] {
         self
     }
 }
 
-impl<T, A: AllocRef> AsMut<[T]> for Vec<T, A> {
+impl<T, A: AllocRef, G: GrowthPolicy> AsMut<[T]> for Vec<T, A, G> {
     #[must_use]
     fn as_mut(&mut self) -> &mut [T] {
         self
     }
 }
 
+impl<T, A: AllocRef, G: GrowthPolicy> Borrow<[T]> for Vec<T, A, G> {
+    #[inline]
+    fn borrow(&self) -> &[T] {
+        &self[..]
+    }
+}
+
+impl<T, A: AllocRef, G: GrowthPolicy> BorrowMut<[T]> for Vec<T, A, G> {
+    #[inline]
+    fn borrow_mut(&mut self) -> &mut [T] {
+        &mut self[..]
+    }
+}
+
 impl<T: Clone> From<&[T]> for Vec<T> {
     #[must_use]
     fn from(s: &[T]) -> Self {
@@ -2763,11 +3189,21 @@ impl<T: Clone> From<&mut [T]> for Vec<T> {
 //     }
 // }
 
+impl<T, A: AllocRef> From<Box<[T], A>> for Vec<T, A> {
+    /// Converts a boxed slice into a vector without reallocating.
+    fn from(b: Box<[T], A>) -> Self {
+        let len = b.len();
+        let (ptr, alloc) = Box::into_raw_alloc(b);
+        unsafe { Self::from_raw_parts_in(ptr as *mut T, len, len, alloc) }
+    }
+}
+
 // note: test pulls in libstd, which causes errors here
 #[cfg(not(test))]
-impl<T> From<Vec<T>> for Box<[T]> {
+impl<T, A: AllocRef, G: GrowthPolicy> From<Vec<T, A, G>> for Box<[T], A> {
+    /// Converts a vector into a boxed slice, shrinking the allocation to the length.
     #[must_use]
-    fn from(v: Vec<T>) -> Self {
+    fn from(v: Vec<T, A, G>) -> Self {
         v.into_boxed_slice()
     }
 }
@@ -2869,6 +3305,24 @@ impl<T, A: AllocRef> IntoIter<T, A> {
     pub fn as_mut_slice(&mut self) -> &mut [T] {
         unsafe { slice::from_raw_parts_mut(self.ptr as *mut T, self.len()) }
     }
+
+    /// Drops the remaining elements without touching the allocation.
+    fn drop_remaining(&mut self) {
+        unsafe {
+            ptr::drop_in_place(self.as_mut_slice());
+        }
+        self.ptr = self.end;
+    }
+
+    /// Hands out the allocator owning the buffer and replaces the buffer with an empty one in
+    /// `alloc`, so dropping the iterator no longer frees it. The remaining elements must have
+    /// been dropped or moved out already.
+    fn forget_allocation(&mut self, alloc: A) -> A {
+        let buf = mem::replace(&mut self.buf, RawVec::new_in(alloc));
+        self.ptr = self.buf.ptr();
+        self.end = self.buf.ptr();
+        buf.leak().1
+    }
 }
 
 unsafe impl<T: Send, A: Send + AllocRef> Send for IntoIter<T, A> {}
@@ -2943,6 +3397,36 @@ impl<T, A: AllocRef> ExactSizeIterator for IntoIter<T, A> {}
 
 impl<T, A: AllocRef> FusedIterator for IntoIter<T, A> {}
 
+unsafe impl<T, A: AllocRef> SourceIter for IntoIter<T, A> {
+    type Source = Self;
+
+    #[inline]
+    unsafe fn as_inner(&mut self) -> &mut Self::Source {
+        self
+    }
+}
+
+unsafe impl<T, A: AllocRef> InPlaceIterable for IntoIter<T, A> {}
+
+/// Gives the in-place collect specialization access to the `IntoIter` at the root of an
+/// iterator pipeline.
+#[doc(hidden)]
+pub unsafe trait AsIntoIter {
+    type Item;
+    type Alloc: AllocRef;
+
+    fn as_into_iter(&mut self) -> &mut IntoIter<Self::Item, Self::Alloc>;
+}
+
+unsafe impl<T, A: AllocRef> AsIntoIter for IntoIter<T, A> {
+    type Item = T;
+    type Alloc = A;
+
+    fn as_into_iter(&mut self) -> &mut IntoIter<T, A> {
+        self
+    }
+}
+
 impl<T: Clone> Clone for IntoIter<T> {
     #[must_use]
     fn clone(&self) -> Self {
@@ -2967,23 +3451,23 @@ impl<T, A: AllocRef> Drop for IntoIter<T, A> {
 ///
 /// [`drain`]: struct.Vec.html#method.drain
 /// [`Vec`]: struct.Vec.html
-pub struct Drain<'a, T, A: AllocRef = Global> {
+pub struct Drain<'a, T, A: AllocRef = Global, G: GrowthPolicy = Doubling> {
     /// Index of tail to preserve
     tail_start: usize,
     /// Length of tail
     tail_len: usize,
     /// Current remaining range to remove
     iter: slice::Iter<'a, T>,
-    vec: NonNull<Vec<T, A>>,
+    vec: NonNull<Vec<T, A, G>>,
 }
 
-impl<T: fmt::Debug, A: AllocRef> fmt::Debug for Drain<'_, T, A> {
+impl<T: fmt::Debug, A: AllocRef, G: GrowthPolicy> fmt::Debug for Drain<'_, T, A, G> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_tuple("Drain").field(&self.iter.as_slice()).finish()
     }
 }
 
-impl<T, A: AllocRef> Drain<'_, T, A> {
+impl<T, A: AllocRef, G: GrowthPolicy> Drain<'_, T, A, G> {
     /// Returns the remaining items of this iterator as a slice.
     ///
     /// # Examples
@@ -3002,10 +3486,10 @@ impl<T, A: AllocRef> Drain<'_, T, A> {
     }
 }
 
-unsafe impl<T: Sync, A: AllocRef> Sync for Drain<'_, T, A> {}
-unsafe impl<T: Send, A: AllocRef> Send for Drain<'_, T, A> {}
+unsafe impl<T: Sync, A: AllocRef, G: GrowthPolicy> Sync for Drain<'_, T, A, G> {}
+unsafe impl<T: Send, A: AllocRef, G: GrowthPolicy> Send for Drain<'_, T, A, G> {}
 
-impl<T, A: AllocRef> Iterator for Drain<'_, T, A> {
+impl<T, A: AllocRef, G: GrowthPolicy> Iterator for Drain<'_, T, A, G> {
     type Item = T;
 
     #[inline]
@@ -3019,14 +3503,14 @@ impl<T, A: AllocRef> Iterator for Drain<'_, T, A> {
     }
 }
 
-impl<T, A: AllocRef> DoubleEndedIterator for Drain<'_, T, A> {
+impl<T, A: AllocRef, G: GrowthPolicy> DoubleEndedIterator for Drain<'_, T, A, G> {
     #[inline]
     fn next_back(&mut self) -> Option<T> {
         self.iter.next_back().map(|elt| unsafe { ptr::read(elt) })
     }
 }
 
-impl<T, A: AllocRef> Drop for Drain<'_, T, A> {
+impl<T, A: AllocRef, G: GrowthPolicy> Drop for Drain<'_, T, A, G> {
     fn drop(&mut self) {
         // exhaust self first
         self.for_each(drop);
@@ -3048,9 +3532,9 @@ impl<T, A: AllocRef> Drop for Drain<'_, T, A> {
     }
 }
 
-impl<T, A: AllocRef> ExactSizeIterator for Drain<'_, T, A> {}
+impl<T, A: AllocRef, G: GrowthPolicy> ExactSizeIterator for Drain<'_, T, A, G> {}
 
-impl<T, A: AllocRef> FusedIterator for Drain<'_, T, A> {}
+impl<T, A: AllocRef, G: GrowthPolicy> FusedIterator for Drain<'_, T, A, G> {}
 
 /// A splicing iterator for `Vec`.
 ///
@@ -3060,17 +3544,19 @@ impl<T, A: AllocRef> FusedIterator for Drain<'_, T, A> {}
 /// [`splice()`]: struct.Vec.html#method.splice
 /// [`Vec`]: struct.Vec.html
 #[derive(Debug)]
-pub struct Splice<'a, I: Iterator + 'a, A = Global>
+pub struct Splice<'a, I: Iterator + 'a, A = Global, G = Doubling>
 where
     A: AllocRef,
+    G: GrowthPolicy,
 {
-    drain: Drain<'a, I::Item, A>,
+    drain: Drain<'a, I::Item, A, G>,
     replace_with: I,
 }
 
-impl<I: Iterator, A> Iterator for Splice<'_, I, A>
+impl<I: Iterator, A, G> Iterator for Splice<'_, I, A, G>
 where
     A: AllocRef,
+    G: GrowthPolicy,
 {
     type Item = I::Item;
 
@@ -3083,20 +3569,27 @@ where
     }
 }
 
-impl<I: Iterator, A> DoubleEndedIterator for Splice<'_, I, A>
+impl<I: Iterator, A, G> DoubleEndedIterator for Splice<'_, I, A, G>
 where
     A: AllocRef,
+    G: GrowthPolicy,
 {
     fn next_back(&mut self) -> Option<Self::Item> {
         self.drain.next_back()
     }
 }
 
-impl<I: Iterator, A> ExactSizeIterator for Splice<'_, I, A> where A: AllocRef {}
+impl<I: Iterator, A, G> ExactSizeIterator for Splice<'_, I, A, G>
+where
+    A: AllocRef,
+    G: GrowthPolicy,
+{
+}
 
-impl<I: Iterator, A> Drop for Splice<'_, I, A>
+impl<I: Iterator, A, G> Drop for Splice<'_, I, A, G>
 where
     A: AllocRef,
+    G: GrowthPolicy,
 {
     fn drop(&mut self) {
         self.drain.by_ref().for_each(drop);
@@ -3140,9 +3633,10 @@ where
 }
 
 /// Private helper methods for `Splice::drop`
-impl<T, A> Drain<'_, T, A>
+impl<T, A, G> Drain<'_, T, A, G>
 where
     A: AllocRef,
+    G: GrowthPolicy,
 {
     /// The range from `self.vec.len` to `self.tail_start` contains elements
     /// that have been moved out.
@@ -3182,11 +3676,11 @@ where
 
 /// An iterator produced by calling `drain_filter` on Vec.
 // #[derive(Debug)]
-pub struct DrainFilter<'a, T, F, A: AllocRef = Global>
+pub struct DrainFilter<'a, T, F, A: AllocRef = Global, G: GrowthPolicy = Doubling>
 where
     F: FnMut(&mut T) -> bool,
 {
-    vec: &'a mut Vec<T, A>,
+    vec: &'a mut Vec<T, A, G>,
     /// The index of the item that will be inspected by the next call to `next`.
     idx: usize,
     /// The number of items that have been drained (removed) thus far.
@@ -3203,7 +3697,7 @@ where
     panic_flag: bool,
 }
 
-impl<T, F, A: AllocRef> Iterator for DrainFilter<'_, T, F, A>
+impl<T, F, A: AllocRef, G: GrowthPolicy> Iterator for DrainFilter<'_, T, F, A, G>
 where
     F: FnMut(&mut T) -> bool,
 {
@@ -3240,19 +3734,19 @@ where
     }
 }
 
-impl<T, F, A: AllocRef> Drop for DrainFilter<'_, T, F, A>
+impl<T, F, A: AllocRef, G: GrowthPolicy> Drop for DrainFilter<'_, T, F, A, G>
 where
     F: FnMut(&mut T) -> bool,
 {
     fn drop(&mut self) {
-        struct BackshiftOnDrop<'a, 'b, T, F, A: AllocRef = Global>
+        struct BackshiftOnDrop<'a, 'b, T, F, A: AllocRef, G: GrowthPolicy>
         where
             F: FnMut(&mut T) -> bool,
         {
-            drain: &'b mut DrainFilter<'a, T, F, A>,
+            drain: &'b mut DrainFilter<'a, T, F, A, G>,
         }
 
-        impl<T, F, A: AllocRef> Drop for BackshiftOnDrop<'_, '_, T, F, A>
+        impl<T, F, A: AllocRef, G: GrowthPolicy> Drop for BackshiftOnDrop<'_, '_, T, F, A, G>
         where
             F: FnMut(&mut T) -> bool,
         {
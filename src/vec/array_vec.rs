@@ -0,0 +1,858 @@
+//! A vector with a fixed capacity which never allocates, written `ArrayVec<T, N>`.
+//!
+//! Instead of reallocating, operations which would exceed the capacity return a
+//! [`CapacityError`] holding the rejected element. This makes `ArrayVec` usable without any
+//! allocator, while [`into_vec_in`] moves the elements onto the heap when needed.
+//!
+//! # Examples
+//!
+//! ```
+//! use alloc_wg::vec::ArrayVec;
+//!
+//! let mut v: ArrayVec<i32, 2> = ArrayVec::new();
+//! v.push(1);
+//! v.push(2);
+//!
+//! let err = v.try_push(3).unwrap_err();
+//! assert_eq!(err.into_element(), 3);
+//! assert_eq!(v, [1, 2]);
+//! ```
+//!
+//! [`CapacityError`]: crate::collections::CapacityError
+//! [`into_vec_in`]: ArrayVec::into_vec_in()
+
+use super::{partition_dedup_by, Vec};
+use crate::{
+    alloc::{AllocRef, Global},
+    collections::{CapacityError, TryReserveError},
+    handle_reserve_error,
+    iter::TryExtend,
+};
+use core::{
+    cmp::Ordering,
+    convert::TryFrom,
+    fmt,
+    hash::{self, Hash},
+    iter::{FromIterator, FusedIterator},
+    mem::{self, MaybeUninit},
+    ops::{
+        self,
+        Bound::{Excluded, Included, Unbounded},
+        RangeBounds,
+    },
+    ptr::{self, NonNull},
+    slice,
+};
+
+/// A contiguous array type with a fixed capacity of `N` elements.
+///
+/// `ArrayVec` mirrors the API of [`Vec`], but all elements are stored inline. Fallible methods
+/// return a [`CapacityError`] instead of a [`TryReserveError`], handing back the element that
+/// did not fit.
+///
+/// # Examples
+///
+/// ```
+/// use alloc_wg::vec::ArrayVec;
+///
+/// let mut vec: ArrayVec<_, 4> = ArrayVec::new();
+/// vec.push(1);
+/// vec.push(2);
+///
+/// assert_eq!(vec.len(), 2);
+/// assert_eq!(vec.capacity(), 4);
+/// assert_eq!(vec.pop(), Some(2));
+/// ```
+///
+/// [`Vec`]: crate::vec::Vec
+/// [`CapacityError`]: crate::collections::CapacityError
+/// [`TryReserveError`]: crate::collections::TryReserveError
+pub struct ArrayVec<T, const N: usize> {
+    buf: [MaybeUninit<T>; N],
+    len: usize,
+}
+
+impl<T, const N: usize> ArrayVec<T, N> {
+    /// Constructs a new, empty `ArrayVec<T, N>`.
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            buf: MaybeUninit::uninit_array(),
+            len: 0,
+        }
+    }
+
+    /// Returns the number of elements the vector can hold, which is always `N`.
+    #[inline]
+    #[must_use]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Returns the number of elements which can still be pushed onto the vector.
+    #[inline]
+    #[must_use]
+    pub fn remaining_capacity(&self) -> usize {
+        N - self.len
+    }
+
+    /// Returns the number of elements in the vector.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the vector contains no elements.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns `true` if the vector cannot hold any more elements.
+    #[inline]
+    #[must_use]
+    pub fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    /// Extracts a slice containing the entire vector.
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        self
+    }
+
+    /// Extracts a mutable slice of the entire vector.
+    #[inline]
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        self
+    }
+
+    /// Returns a raw pointer to the vector's buffer.
+    #[inline]
+    pub fn as_ptr(&self) -> *const T {
+        self.buf.as_ptr() as *const T
+    }
+
+    /// Returns an unsafe mutable pointer to the vector's buffer.
+    #[inline]
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        self.buf.as_mut_ptr() as *mut T
+    }
+
+    /// Forces the length of the vector to `new_len`.
+    ///
+    /// # Safety
+    ///
+    /// - `new_len` must be less than or equal to `N`.
+    /// - The elements at `old_len..new_len` must be initialized.
+    #[inline]
+    pub unsafe fn set_len(&mut self, new_len: usize) {
+        debug_assert!(new_len <= N);
+        self.len = new_len;
+    }
+
+    /// Appends an element to the back of the vector.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the vector is already full.
+    #[inline]
+    pub fn push(&mut self, value: T) {
+        if let Err(err) = self.try_push(value) {
+            panic_capacity(err)
+        }
+    }
+
+    /// Same as `push` but hands back the element instead of panicking.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use alloc_wg::vec::ArrayVec;
+    ///
+    /// let mut vec: ArrayVec<_, 1> = ArrayVec::new();
+    /// assert!(vec.try_push(1).is_ok());
+    /// assert_eq!(vec.try_push(2).unwrap_err().into_element(), 2);
+    /// ```
+    #[inline]
+    pub fn try_push(&mut self, value: T) -> Result<(), CapacityError<T>> {
+        if self.len == N {
+            return Err(CapacityError::new(value));
+        }
+        unsafe {
+            self.push_unchecked(value);
+        }
+        Ok(())
+    }
+
+    unsafe fn push_unchecked(&mut self, value: T) {
+        debug_assert!(self.len < N);
+        ptr::write(self.as_mut_ptr().add(self.len), value);
+        self.len += 1;
+    }
+
+    /// Removes the last element from the vector and returns it, or [`None`] if it
+    /// is empty.
+    #[inline]
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            None
+        } else {
+            unsafe {
+                self.len -= 1;
+                Some(ptr::read(self.as_ptr().add(self.len)))
+            }
+        }
+    }
+
+    /// Inserts an element at position `index` within the vector, shifting all
+    /// elements after it to the right.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len` or if the vector is full.
+    pub fn insert(&mut self, index: usize, element: T) {
+        if let Err(err) = self.try_insert(index, element) {
+            panic_capacity(err)
+        }
+    }
+
+    /// Same as `insert` but hands back the element instead of panicking if the vector is full.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`.
+    pub fn try_insert(&mut self, index: usize, element: T) -> Result<(), CapacityError<T>> {
+        let len = self.len;
+        assert!(index <= len);
+        if len == N {
+            return Err(CapacityError::new(element));
+        }
+
+        unsafe {
+            let p = self.as_mut_ptr().add(index);
+            ptr::copy(p, p.offset(1), len - index);
+            ptr::write(p, element);
+            self.len = len + 1;
+        }
+        Ok(())
+    }
+
+    /// Removes and returns the element at position `index` within the vector,
+    /// shifting all elements after it to the left.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn remove(&mut self, index: usize) -> T {
+        let len = self.len;
+        assert!(index < len);
+        unsafe {
+            let ptr = self.as_mut_ptr().add(index);
+            let ret = ptr::read(ptr);
+            ptr::copy(ptr.offset(1), ptr, len - index - 1);
+            self.len = len - 1;
+            ret
+        }
+    }
+
+    /// Removes an element from the vector and returns it.
+    ///
+    /// The removed element is replaced by the last element of the vector.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn swap_remove(&mut self, index: usize) -> T {
+        let len = self.len;
+        assert!(index < len);
+        unsafe {
+            let last = ptr::read(self.as_ptr().add(len - 1));
+            let hole = self.as_mut_ptr().add(index);
+            self.len = len - 1;
+            ptr::replace(hole, last)
+        }
+    }
+
+    /// Shortens the vector, keeping the first `len` elements and dropping
+    /// the rest.
+    ///
+    /// If `len` is greater than the vector's current length, this has no
+    /// effect.
+    pub fn truncate(&mut self, len: usize) {
+        unsafe {
+            if len > self.len {
+                return;
+            }
+            let remaining_len = self.len - len;
+            let s = ptr::slice_from_raw_parts_mut(self.as_mut_ptr().add(len), remaining_len);
+            self.len = len;
+            ptr::drop_in_place(s);
+        }
+    }
+
+    /// Clears the vector, removing all values.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.truncate(0)
+    }
+
+    /// Retains only the elements specified by the predicate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use alloc_wg::vec::ArrayVec;
+    ///
+    /// let mut vec: ArrayVec<_, 4> = (1..=4).collect();
+    /// vec.retain(|&x| x % 2 == 0);
+    /// assert_eq!(vec, [2, 4]);
+    /// ```
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let len = self.len;
+        let mut del = 0;
+        {
+            let v = &mut **self;
+
+            for i in 0..len {
+                if !f(&v[i]) {
+                    del += 1;
+                } else if del > 0 {
+                    v.swap(i - del, i);
+                }
+            }
+        }
+        if del > 0 {
+            self.truncate(len - del);
+        }
+    }
+
+    /// Removes all but the first of consecutive elements in the vector that resolve to the same
+    /// key.
+    #[inline]
+    pub fn dedup_by_key<F, K>(&mut self, mut key: F)
+    where
+        F: FnMut(&mut T) -> K,
+        K: PartialEq,
+    {
+        self.dedup_by(|a, b| key(a) == key(b))
+    }
+
+    /// Removes all but the first of consecutive elements in the vector satisfying a given equality
+    /// relation.
+    pub fn dedup_by<F>(&mut self, same_bucket: F)
+    where
+        F: FnMut(&mut T, &mut T) -> bool,
+    {
+        let len = {
+            let (dedup, _) = partition_dedup_by(self.as_mut_slice(), same_bucket);
+            dedup.len()
+        };
+        self.truncate(len);
+    }
+
+    /// Creates a draining iterator that removes the specified range in the vector
+    /// and yields the removed items.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the starting point is greater than the end point or if
+    /// the end point is greater than the length of the vector.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use alloc_wg::vec::ArrayVec;
+    ///
+    /// let mut v: ArrayVec<_, 3> = (1..=3).collect();
+    /// let u: Vec<_> = v.drain(1..).collect();
+    /// assert_eq!(v, [1]);
+    /// assert_eq!(u, [2, 3]);
+    /// ```
+    pub fn drain<R>(&mut self, range: R) -> Drain<'_, T, N>
+    where
+        R: RangeBounds<usize>,
+    {
+        let len = self.len;
+        let start = match range.start_bound() {
+            Included(&n) => n,
+            Excluded(&n) => n + 1,
+            Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Included(&n) => n + 1,
+            Excluded(&n) => n,
+            Unbounded => len,
+        };
+        assert!(start <= end);
+        assert!(end <= len);
+
+        unsafe {
+            // set self.vec length's to start, to be safe in case Drain is leaked
+            self.len = start;
+            let range_slice = slice::from_raw_parts_mut(self.as_mut_ptr().add(start), end - start);
+            Drain {
+                tail_start: end,
+                tail_len: len - end,
+                iter: range_slice.iter(),
+                vec: NonNull::from(self),
+            }
+        }
+    }
+
+    /// Returns the underlying array if the vector is full, or `self` otherwise.
+    pub fn into_inner(self) -> Result<[T; N], Self> {
+        if self.len == N {
+            unsafe {
+                let array = ptr::read(self.buf.as_ptr() as *const [T; N]);
+                mem::forget(self);
+                Ok(array)
+            }
+        } else {
+            Err(self)
+        }
+    }
+
+    /// Moves the elements into a [`Vec<T, A>`] allocated from `a`, with a capacity of exactly
+    /// `len` elements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the allocation fails.
+    ///
+    /// [`Vec<T, A>`]: crate::vec::Vec
+    pub fn into_vec_in<A: AllocRef>(self, a: A) -> Vec<T, A> {
+        handle_reserve_error(self.try_into_vec_in(a))
+    }
+
+    /// Same as `into_vec_in` but returns errors instead of panicking.
+    pub fn try_into_vec_in<A: AllocRef>(mut self, a: A) -> Result<Vec<T, A>, TryReserveError> {
+        let mut vec = Vec::try_with_capacity_in(self.len, a)?;
+        unsafe {
+            ptr::copy_nonoverlapping(self.as_ptr(), vec.as_mut_ptr(), self.len);
+            vec.set_len(self.len);
+            self.len = 0;
+        }
+        Ok(vec)
+    }
+}
+
+impl<T: Clone, const N: usize> ArrayVec<T, N> {
+    /// Clones and appends all elements in a slice to the vector.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the vector does not have room for all elements.
+    pub fn extend_from_slice(&mut self, other: &[T]) {
+        if let Err(err) = self.try_extend_from_slice(other) {
+            panic_capacity(err)
+        }
+    }
+
+    /// Same as `extend_from_slice` but returns an error instead of panicking.
+    ///
+    /// Nothing is appended if the elements do not fit.
+    pub fn try_extend_from_slice(&mut self, other: &[T]) -> Result<(), CapacityError> {
+        if other.len() > self.remaining_capacity() {
+            return Err(CapacityError::new(()));
+        }
+        for element in other {
+            unsafe { self.push_unchecked(element.clone()) }
+        }
+        Ok(())
+    }
+}
+
+impl<T: PartialEq, const N: usize> ArrayVec<T, N> {
+    /// Removes consecutive repeated elements in the vector according to the
+    /// [`PartialEq`] trait implementation.
+    #[inline]
+    pub fn dedup(&mut self) {
+        self.dedup_by(|a, b| a == b)
+    }
+}
+
+#[cold]
+#[inline(never)]
+fn panic_capacity<T>(err: CapacityError<T>) -> ! {
+    panic!("{}", err)
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Common trait implementations for ArrayVec
+////////////////////////////////////////////////////////////////////////////////
+
+impl<T, const N: usize> ops::Deref for ArrayVec<T, N> {
+    type Target = [T];
+
+    #[must_use]
+    fn deref(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.as_ptr(), self.len) }
+    }
+}
+
+impl<T, const N: usize> ops::DerefMut for ArrayVec<T, N> {
+    #[must_use]
+    fn deref_mut(&mut self) -> &mut [T] {
+        unsafe { slice::from_raw_parts_mut(self.as_mut_ptr(), self.len) }
+    }
+}
+
+impl<T, const N: usize> Drop for ArrayVec<T, N> {
+    fn drop(&mut self) {
+        self.clear()
+    }
+}
+
+impl<T: Clone, const N: usize> Clone for ArrayVec<T, N> {
+    fn clone(&self) -> Self {
+        let mut vec = Self::new();
+        for element in self.iter() {
+            unsafe { vec.push_unchecked(element.clone()) }
+        }
+        vec
+    }
+}
+
+impl<T, const N: usize> Default for ArrayVec<T, N> {
+    /// Creates an empty `ArrayVec<T, N>`.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for ArrayVec<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<T: Hash, const N: usize> Hash for ArrayVec<T, N> {
+    #[inline]
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        Hash::hash(&**self, state)
+    }
+}
+
+impl<T, const N: usize> AsRef<[T]> for ArrayVec<T, N> {
+    fn as_ref(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T, const N: usize> AsMut<[T]> for ArrayVec<T, N> {
+    fn as_mut(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+macro_rules! __impl_slice_eq1 {
+    ([$($vars:tt)*] $lhs:ty, $rhs:ty) => {
+        impl<T, U, $($vars)*> PartialEq<$rhs> for $lhs
+        where
+            T: PartialEq<U>,
+        {
+            #[inline]
+            fn eq(&self, other: &$rhs) -> bool { self[..] == other[..] }
+        }
+    }
+}
+
+__impl_slice_eq1! { [const N: usize, const M: usize] ArrayVec<T, N>, ArrayVec<U, M> }
+__impl_slice_eq1! { [const N: usize] ArrayVec<T, N>, &[U] }
+__impl_slice_eq1! { [const N: usize] ArrayVec<T, N>, &mut [U] }
+__impl_slice_eq1! { [const N: usize, const M: usize] ArrayVec<T, N>, [U; M] }
+__impl_slice_eq1! { [const N: usize, const M: usize] ArrayVec<T, N>, &[U; M] }
+
+impl<T: Eq, const N: usize> Eq for ArrayVec<T, N> {}
+
+impl<T: PartialOrd, const N: usize> PartialOrd for ArrayVec<T, N> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        PartialOrd::partial_cmp(&**self, &**other)
+    }
+}
+
+impl<T: Ord, const N: usize> Ord for ArrayVec<T, N> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        Ord::cmp(&**self, &**other)
+    }
+}
+
+impl<T, const N: usize> From<[T; N]> for ArrayVec<T, N> {
+    fn from(array: [T; N]) -> Self {
+        let array = mem::ManuallyDrop::new(array);
+        unsafe {
+            Self {
+                buf: ptr::read(&*array as *const [T; N] as *const [MaybeUninit<T>; N]),
+                len: N,
+            }
+        }
+    }
+}
+
+impl<T: Clone, const N: usize> TryFrom<&[T]> for ArrayVec<T, N> {
+    type Error = CapacityError;
+
+    fn try_from(slice: &[T]) -> Result<Self, Self::Error> {
+        let mut vec = Self::new();
+        vec.try_extend_from_slice(slice)?;
+        Ok(vec)
+    }
+}
+
+impl<T, const N: usize> From<ArrayVec<T, N>> for Vec<T> {
+    fn from(vec: ArrayVec<T, N>) -> Self {
+        vec.into_vec_in(Global)
+    }
+}
+
+impl<T, const N: usize> FromIterator<T> for ArrayVec<T, N> {
+    /// Collects the iterator into an `ArrayVec`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the iterator yields more than `N` elements.
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut vec = Self::new();
+        vec.extend(iter);
+        vec
+    }
+}
+
+impl<T, const N: usize> Extend<T> for ArrayVec<T, N> {
+    /// Extends the vector with the contents of an iterator.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the vector is full before the iterator is exhausted.
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        if let Err(err) = self.try_extend(iter) {
+            panic_capacity(err)
+        }
+    }
+}
+
+impl<'a, T: 'a + Copy, const N: usize> Extend<&'a T> for ArrayVec<T, N> {
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().cloned())
+    }
+}
+
+impl<T, const N: usize> TryExtend<T> for ArrayVec<T, N> {
+    type Err = CapacityError<T>;
+
+    /// Pushes elements until the vector is full. The first element which does not fit is
+    /// returned in the error, the rest of the iterator is not consumed.
+    fn try_extend<I: IntoIterator<Item = T>>(&mut self, iter: I) -> Result<(), Self::Err> {
+        for element in iter {
+            self.try_push(element)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T, const N: usize> IntoIterator for ArrayVec<T, N> {
+    type Item = T;
+    type IntoIter = IntoIter<T, N>;
+
+    /// Creates a consuming iterator, that is, one that moves each value out of
+    /// the vector (from start to end).
+    #[inline]
+    fn into_iter(mut self) -> IntoIter<T, N> {
+        let end = self.len;
+        self.len = 0;
+        IntoIter {
+            vec: self,
+            start: 0,
+            end,
+        }
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a ArrayVec<T, N> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> slice::Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a mut ArrayVec<T, N> {
+    type Item = &'a mut T;
+    type IntoIter = slice::IterMut<'a, T>;
+
+    fn into_iter(self) -> slice::IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Iterators
+////////////////////////////////////////////////////////////////////////////////
+
+/// An iterator that moves out of an `ArrayVec`.
+///
+/// This `struct` is created by the `into_iter` method on [`ArrayVec`].
+pub struct IntoIter<T, const N: usize> {
+    // `vec.len` is zero, the remaining elements are `start..end`
+    vec: ArrayVec<T, N>,
+    start: usize,
+    end: usize,
+}
+
+impl<T, const N: usize> IntoIter<T, N> {
+    /// Returns the remaining items of this iterator as a slice.
+    #[must_use]
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.vec.as_ptr().add(self.start), self.end - self.start) }
+    }
+
+    /// Returns the remaining items of this iterator as a mutable slice.
+    #[must_use]
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        unsafe {
+            slice::from_raw_parts_mut(self.vec.as_mut_ptr().add(self.start), self.end - self.start)
+        }
+    }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for IntoIter<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("IntoIter").field(&self.as_slice()).finish()
+    }
+}
+
+impl<T, const N: usize> Iterator for IntoIter<T, N> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        if self.start == self.end {
+            None
+        } else {
+            unsafe {
+                let item = ptr::read(self.vec.as_ptr().add(self.start));
+                self.start += 1;
+                Some(item)
+            }
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let exact = self.end - self.start;
+        (exact, Some(exact))
+    }
+}
+
+impl<T, const N: usize> DoubleEndedIterator for IntoIter<T, N> {
+    #[inline]
+    fn next_back(&mut self) -> Option<T> {
+        if self.start == self.end {
+            None
+        } else {
+            unsafe {
+                self.end -= 1;
+                Some(ptr::read(self.vec.as_ptr().add(self.end)))
+            }
+        }
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for IntoIter<T, N> {}
+
+impl<T, const N: usize> FusedIterator for IntoIter<T, N> {}
+
+impl<T, const N: usize> Drop for IntoIter<T, N> {
+    fn drop(&mut self) {
+        // destroy the remaining elements
+        for _x in self.by_ref() {}
+    }
+}
+
+/// A draining iterator for `ArrayVec`.
+///
+/// This `struct` is created by the [`drain`] method on [`ArrayVec`].
+///
+/// [`drain`]: ArrayVec::drain()
+pub struct Drain<'a, T, const N: usize> {
+    /// Index of tail to preserve
+    tail_start: usize,
+    /// Length of tail
+    tail_len: usize,
+    /// Current remaining range to remove
+    iter: slice::Iter<'a, T>,
+    vec: NonNull<ArrayVec<T, N>>,
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for Drain<'_, T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Drain").field(&self.iter.as_slice()).finish()
+    }
+}
+
+impl<T, const N: usize> Drain<'_, T, N> {
+    /// Returns the remaining items of this iterator as a slice.
+    #[must_use]
+    pub fn as_slice(&self) -> &[T] {
+        self.iter.as_slice()
+    }
+}
+
+unsafe impl<T: Sync, const N: usize> Sync for Drain<'_, T, N> {}
+unsafe impl<T: Send, const N: usize> Send for Drain<'_, T, N> {}
+
+impl<T, const N: usize> Iterator for Drain<'_, T, N> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        self.iter.next().map(|elt| unsafe { ptr::read(elt) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T, const N: usize> DoubleEndedIterator for Drain<'_, T, N> {
+    #[inline]
+    fn next_back(&mut self) -> Option<T> {
+        self.iter.next_back().map(|elt| unsafe { ptr::read(elt) })
+    }
+}
+
+impl<T, const N: usize> Drop for Drain<'_, T, N> {
+    fn drop(&mut self) {
+        // exhaust self first
+        self.for_each(drop);
+
+        if self.tail_len > 0 {
+            unsafe {
+                let source_vec = self.vec.as_mut();
+                // memmove back untouched tail, update to new length
+                let start = source_vec.len;
+                let tail = self.tail_start;
+                if tail != start {
+                    let src = source_vec.as_ptr().add(tail);
+                    let dst = source_vec.as_mut_ptr().add(start);
+                    ptr::copy(src, dst, self.tail_len);
+                }
+                source_vec.len = start + self.tail_len;
+            }
+        }
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for Drain<'_, T, N> {}
+
+impl<T, const N: usize> FusedIterator for Drain<'_, T, N> {}
@@ -0,0 +1,171 @@
+//! Growth policies deciding how [`Vec`] and [`RawVec`] resize their buffer.
+//!
+//! A policy is selected with the third type parameter of [`Vec<T, A, G>`] and defaults to
+//! [`Doubling`]. Policies are stateless types, so they add nothing to the size of a vector.
+//!
+//! ```
+//! #![feature(allocator_api)]
+//!
+//! use alloc_wg::{
+//!     alloc::Global,
+//!     vec::{Exact, Vec},
+//! };
+//!
+//! let mut v: Vec<u32, _, Exact> = Vec::new_in(Global);
+//! v.push(1);
+//! v.push(2);
+//! assert_eq!(v.capacity(), 2);
+//! ```
+//!
+//! [`Vec`]: crate::vec::Vec
+//! [`Vec<T, A, G>`]: crate::vec::Vec
+//! [`RawVec`]: crate::raw_vec::RawVec
+
+use core::cmp;
+
+/// Decides the capacity a buffer grows or shrinks to.
+///
+/// Only amortized operations like `push`, `reserve` and `shrink_to_fit` consult the policy.
+/// Exact operations like `reserve_exact` and `into_boxed_slice` always use the requested
+/// capacity.
+pub trait GrowthPolicy {
+    /// Returns the new capacity for a buffer of `capacity` elements of `elem_size` bytes, which
+    /// has to hold at least `required` elements.
+    ///
+    /// The result is clamped to be at least `required`. `elem_size` is never zero.
+    fn grow(capacity: usize, required: usize, elem_size: usize) -> usize;
+
+    /// Returns the capacity `shrink_to_fit` shrinks a buffer of `capacity` elements to, when it
+    /// has to hold at least `required` elements.
+    ///
+    /// The result is clamped to `required..=capacity`. By default, the buffer is shrunk to
+    /// `required`.
+    #[inline]
+    fn shrink(capacity: usize, required: usize, elem_size: usize) -> usize {
+        let _ = (capacity, elem_size);
+        required
+    }
+}
+
+/// Doubles the capacity, skipping tiny capacities. This is the default policy.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Doubling;
+
+impl GrowthPolicy for Doubling {
+    #[inline]
+    fn grow(capacity: usize, required: usize, elem_size: usize) -> usize {
+        // This guarantees exponential growth. The doubling cannot overflow
+        // because `cap <= isize::MAX` and the type of `cap` is `usize`.
+        let cap = cmp::max(capacity * 2, required);
+
+        // Tiny Vecs are dumb. Skip to:
+        // - 8 if the element size is 1, because any heap allocators is likely
+        //   to round up a request of less than 8 bytes to at least 8 bytes.
+        // - 4 if elements are moderate-sized (<= 1 KiB).
+        // - 1 otherwise, to avoid wasting too much space for very short Vecs.
+        let min_non_zero_cap = if elem_size == 1 {
+            8
+        } else if elem_size <= 1024 {
+            4
+        } else {
+            1
+        };
+        cmp::max(min_non_zero_cap, cap)
+    }
+}
+
+/// Grows to exactly the required capacity. Useful for arena-backed vectors.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Exact;
+
+impl GrowthPolicy for Exact {
+    #[inline]
+    fn grow(_capacity: usize, required: usize, _elem_size: usize) -> usize {
+        required
+    }
+}
+
+/// Multiplies the capacity by `NUM / DEN`, e.g. `Factor<3, 2>` grows by 1.5x.
+///
+/// A ratio of at most 1, like `Factor<1, 2>`, never exceeds the current capacity, so the buffer
+/// grows to exactly the required capacity. Pushing elements one by one then reallocates every
+/// time, which takes quadratic time, just like [`Exact`].
+///
+/// A zero denominator is rejected when the policy is used:
+///
+/// ```compile_fail
+/// #![feature(allocator_api)]
+///
+/// use alloc_wg::{
+///     alloc::Global,
+///     vec::{Factor, Vec},
+/// };
+///
+/// let mut v: Vec<u8, _, Factor<1, 0>> = Vec::new_in(Global);
+/// v.push(1);
+/// ```
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Factor<const NUM: usize, const DEN: usize>;
+
+impl<const NUM: usize, const DEN: usize> Factor<NUM, DEN> {
+    const NON_ZERO_DEN: () = assert!(DEN != 0, "`Factor` needs a non-zero denominator");
+}
+
+impl<const NUM: usize, const DEN: usize> GrowthPolicy for Factor<NUM, DEN> {
+    #[inline]
+    fn grow(capacity: usize, required: usize, _elem_size: usize) -> usize {
+        let _: () = Self::NON_ZERO_DEN;
+        // Divide first, so only a capacity close to `usize::MAX` saturates.
+        let cap = (capacity / DEN)
+            .saturating_mul(NUM)
+            .saturating_add(capacity % DEN * NUM / DEN);
+        cmp::max(cap, required)
+    }
+}
+
+/// Grows in steps of whole pages of 4 KiB. Shrinking keeps the buffer page-rounded as well.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct PageRounded;
+
+impl PageRounded {
+    /// The size of a page in bytes.
+    pub const PAGE_SIZE: usize = 4096;
+
+    fn round(required: usize, elem_size: usize) -> usize {
+        let bytes = required.saturating_mul(elem_size);
+        let pages = bytes / Self::PAGE_SIZE + (bytes % Self::PAGE_SIZE != 0) as usize;
+        pages.saturating_mul(Self::PAGE_SIZE) / elem_size
+    }
+}
+
+impl GrowthPolicy for PageRounded {
+    #[inline]
+    fn grow(_capacity: usize, required: usize, elem_size: usize) -> usize {
+        Self::round(required, elem_size)
+    }
+
+    #[inline]
+    fn shrink(_capacity: usize, required: usize, elem_size: usize) -> usize {
+        Self::round(required, elem_size)
+    }
+}
+
+/// Grows to the next Fibonacci number, which is a factor of about 1.6.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Fibonacci;
+
+impl GrowthPolicy for Fibonacci {
+    fn grow(capacity: usize, required: usize, _elem_size: usize) -> usize {
+        let (mut a, mut b) = (1_usize, 1_usize);
+        while b <= capacity || b < required {
+            match a.checked_add(b) {
+                Some(next) => {
+                    a = b;
+                    b = next;
+                }
+                None => return required,
+            }
+        }
+        b
+    }
+}
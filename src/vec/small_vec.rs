@@ -0,0 +1,1500 @@
+//! A vector with inline storage for a small number of elements, written
+//! `SmallVec<T, N>`.
+//!
+//! A `SmallVec<T, N>` stores up to `N` elements directly inside the struct and only
+//! allocates once it grows beyond that. After it has *spilled* onto the heap it behaves like
+//! a [`Vec<T, A>`], and converting between the two reuses the heap buffer.
+//!
+//! # Examples
+//!
+//! ```
+//! use alloc_wg::vec::SmallVec;
+//!
+//! let mut v: SmallVec<i32, 4> = SmallVec::new();
+//! v.push(1);
+//! v.push(2);
+//! assert!(!v.spilled());
+//!
+//! v.extend(3..=5);
+//! assert!(v.spilled());
+//! assert_eq!(v, [1, 2, 3, 4, 5]);
+//! ```
+//!
+//! [`Vec<T, A>`]: crate::vec::Vec
+
+use super::{partition_dedup_by, Vec};
+use crate::{
+    alloc::{AllocRef, Global, Layout},
+    boxed::Box,
+    clone::CloneIn,
+    collections::{
+        TryPushError,
+        TryReserveError::{self, CapacityOverflow},
+    },
+    handle_reserve_error,
+    iter::{FromIteratorIn, TryExtend},
+    raw_vec::RawVec,
+};
+use core::{
+    cmp::Ordering,
+    fmt,
+    hash::{self, Hash},
+    iter::{FromIterator, FusedIterator},
+    mem::{self, MaybeUninit},
+    ops::{
+        self,
+        Bound::{Excluded, Included, Unbounded},
+        RangeBounds,
+    },
+    ptr::{self, NonNull},
+    slice,
+};
+
+/// A contiguous growable array type which stores up to `N` elements inline.
+///
+/// As long as the length does not exceed `N`, no memory is allocated. Once more space is
+/// required, the elements are moved into a buffer allocated from `A` and the vector is said to
+/// have *spilled*. A spilled `SmallVec` does not move back into its inline storage unless
+/// [`shrink_to_fit`] is called.
+///
+/// Zero-sized types never need to allocate and are therefore never stored inline.
+///
+/// # Examples
+///
+/// ```
+/// use alloc_wg::vec::SmallVec;
+///
+/// let mut vec: SmallVec<_, 2> = SmallVec::new();
+/// vec.push(1);
+/// vec.push(2);
+/// assert_eq!(vec.capacity(), 2);
+///
+/// vec.push(3);
+/// assert!(vec.capacity() >= 3);
+/// assert_eq!(vec.pop(), Some(3));
+/// assert_eq!(vec, [1, 2]);
+/// ```
+///
+/// [`shrink_to_fit`]: Self::shrink_to_fit()
+pub struct SmallVec<T, const N: usize, A: AllocRef = Global> {
+    inline: MaybeUninit<[T; N]>,
+    // `buf.capacity() == 0` means the elements are stored in `inline`. The allocator is kept
+    // here even while unused, so spilling can happen without requiring `A: Clone`.
+    buf: RawVec<T, A>,
+    len: usize,
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Inherent methods
+////////////////////////////////////////////////////////////////////////////////
+
+impl<T, const N: usize> SmallVec<T, N> {
+    /// Constructs a new, empty `SmallVec<T, N>`.
+    ///
+    /// The vector will not allocate until more than `N` elements are pushed onto it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #![allow(unused_mut)]
+    /// use alloc_wg::vec::SmallVec;
+    ///
+    /// let mut vec: SmallVec<i32, 8> = SmallVec::new();
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::new_in(Global)
+    }
+
+    /// Constructs a new, empty `SmallVec<T, N>` with at least the specified capacity.
+    ///
+    /// If `capacity` does not exceed `N`, this does not allocate.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the requested capacity exceeds `isize::MAX` bytes.
+    #[inline]
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_in(capacity, Global)
+    }
+}
+
+impl<T, const N: usize, A: AllocRef> SmallVec<T, N, A> {
+    /// Like `new` but parameterized over the choice of allocator for the returned `SmallVec`.
+    #[inline]
+    pub fn new_in(a: A) -> Self {
+        Self {
+            inline: MaybeUninit::uninit(),
+            buf: RawVec::new_in(a),
+            len: 0,
+        }
+    }
+
+    /// Like `with_capacity` but parameterized over the choice of allocator for the returned
+    /// `SmallVec`.
+    ///
+    /// # Panics
+    ///
+    /// * if the requested capacity exceeds `usize::MAX` bytes.
+    /// * on 32-bit platforms if the requested capacity exceeds `isize::MAX` bytes.
+    #[inline]
+    pub fn with_capacity_in(capacity: usize, a: A) -> Self {
+        handle_reserve_error(Self::try_with_capacity_in(capacity, a))
+    }
+
+    /// Like `with_capacity` but parameterized over the choice of allocator for the returned
+    /// `SmallVec`.
+    ///
+    /// # Errors
+    ///
+    /// * `InvalidLayout` if the requested capacity exceeds `usize::MAX` bytes.
+    /// * `CapacityOverflow` on 32-bit platforms if the requested capacity exceeds `isize::MAX` bytes.
+    /// * `AllocError` on OOM
+    #[inline]
+    pub fn try_with_capacity_in(capacity: usize, a: A) -> Result<Self, TryReserveError> {
+        let buf = if capacity > N {
+            RawVec::try_with_capacity_in(capacity, a)?
+        } else {
+            RawVec::new_in(a)
+        };
+        Ok(Self {
+            inline: MaybeUninit::uninit(),
+            buf,
+            len: 0,
+        })
+    }
+
+    /// Returns the number of elements which can be stored without allocating.
+    #[inline]
+    #[must_use]
+    pub fn inline_size(&self) -> usize {
+        N
+    }
+
+    /// Returns `true` if the elements are stored in a heap allocation instead of inline.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use alloc_wg::vec::SmallVec;
+    ///
+    /// let mut vec: SmallVec<_, 1> = SmallVec::new();
+    /// vec.push(1);
+    /// assert!(!vec.spilled());
+    /// vec.push(2);
+    /// assert!(vec.spilled());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn spilled(&self) -> bool {
+        self.buf.capacity() != 0
+    }
+
+    /// Returns the number of elements the vector can hold without reallocating.
+    ///
+    /// This is `N` as long as the vector has not spilled.
+    #[inline]
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        if self.spilled() {
+            self.buf.capacity()
+        } else {
+            N
+        }
+    }
+
+    /// Reserves capacity for at least `additional` more elements to be inserted.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity exceeds `isize::MAX` bytes.
+    pub fn reserve(&mut self, additional: usize) {
+        handle_reserve_error(self.try_reserve(additional));
+    }
+
+    /// Reserves the minimum capacity for exactly `additional` more elements to be inserted.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity exceeds `isize::MAX` bytes.
+    pub fn reserve_exact(&mut self, additional: usize) {
+        handle_reserve_error(self.try_reserve_exact(additional));
+    }
+
+    /// Same as `reserve` but returns errors instead of panicking.
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an error
+    /// is returned.
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.try_grow(self.len, additional, false)
+    }
+
+    /// Same as `reserve_exact` but returns errors instead of panicking.
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an error
+    /// is returned.
+    pub fn try_reserve_exact(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.try_grow(self.len, additional, true)
+    }
+
+    /// Makes sure there is room for `used + additional` elements, spilling onto the heap if
+    /// needed. The first `used` slots are moved over when spilling, which includes any hole
+    /// left by `Drain`.
+    fn try_grow(
+        &mut self,
+        used: usize,
+        additional: usize,
+        exact: bool,
+    ) -> Result<(), TryReserveError> {
+        if self.spilled() {
+            return if exact {
+                self.buf.try_reserve_exact(used, additional)
+            } else {
+                self.buf.try_reserve(used, additional)
+            };
+        }
+        if additional <= N.wrapping_sub(used) {
+            return Ok(());
+        }
+        let required = used.checked_add(additional).ok_or(CapacityOverflow)?;
+        if exact {
+            self.buf.try_reserve_exact(0, required)?;
+        } else {
+            self.buf.try_reserve(0, required)?;
+        }
+        unsafe {
+            ptr::copy_nonoverlapping(self.inline.as_ptr() as *const T, self.buf.ptr(), used);
+        }
+        Ok(())
+    }
+
+    /// Shrinks the capacity of the vector as much as possible.
+    ///
+    /// If the vector has spilled and its length does not exceed `N`, the elements are moved
+    /// back inline and the heap buffer is freed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the reallocation fails.
+    pub fn shrink_to_fit(&mut self) {
+        handle_reserve_error(self.try_shrink_to_fit());
+    }
+
+    /// Same as `shrink_to_fit` but returns errors instead of panicking.
+    ///
+    /// # Errors
+    ///
+    /// If the allocator reports a failure while shrinking the heap buffer, then an error is
+    /// returned and the vector is left unchanged.
+    pub fn try_shrink_to_fit(&mut self) -> Result<(), TryReserveError> {
+        if !self.spilled() || mem::size_of::<T>() == 0 {
+            return Ok(());
+        }
+        if self.len <= N {
+            unsafe { self.unspill() };
+            Ok(())
+        } else if self.buf.capacity() != self.len {
+            self.buf.try_shrink_to_fit(self.len)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Moves the elements back into the inline storage and frees the heap buffer.
+    ///
+    /// # Safety
+    ///
+    /// The vector must have spilled, `T` must not be zero-sized and `len` must not exceed `N`.
+    unsafe fn unspill(&mut self) {
+        debug_assert!(self.spilled() && self.len <= N);
+        let heap = self.buf.ptr();
+        ptr::copy_nonoverlapping(heap, self.inline.as_mut_ptr() as *mut T, self.len);
+
+        let (memory, alloc) = ptr::read(&self.buf).leak();
+        ptr::write(&mut self.buf, RawVec::new_in(alloc));
+        if let Some(memory) = memory {
+            let layout = Layout::from_size_align_unchecked(memory.len(), mem::align_of::<T>());
+            self.buf.alloc().dealloc(memory.as_non_null_ptr(), layout);
+        }
+    }
+
+    /// Converts the vector into [`Box<[T], A>`][owned slice].
+    ///
+    /// The elements are always moved into a heap allocation of exactly `len` elements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the allocation fails.
+    ///
+    /// [owned slice]: crate::boxed::Box
+    pub fn into_boxed_slice(self) -> Box<[T], A> {
+        handle_reserve_error(self.try_into_boxed_slice())
+    }
+
+    /// Same as `into_boxed_slice` but returns errors instead of panicking.
+    pub fn try_into_boxed_slice(self) -> Result<Box<[T], A>, TryReserveError> {
+        self.try_into_vec()?.try_into_boxed_slice()
+    }
+
+    /// Converts the vector into a [`Vec<T, A>`].
+    ///
+    /// If the vector has spilled, the heap buffer is reused. Otherwise a buffer of exactly
+    /// `len` elements is allocated.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the allocation fails.
+    ///
+    /// [`Vec<T, A>`]: crate::vec::Vec
+    pub fn into_vec(self) -> Vec<T, A> {
+        handle_reserve_error(self.try_into_vec())
+    }
+
+    /// Same as `into_vec` but returns errors instead of panicking.
+    pub fn try_into_vec(mut self) -> Result<Vec<T, A>, TryReserveError> {
+        if !self.spilled() && self.len > 0 {
+            self.buf.try_reserve_exact(0, self.len)?;
+            unsafe {
+                ptr::copy_nonoverlapping(
+                    self.inline.as_ptr() as *const T,
+                    self.buf.ptr(),
+                    self.len,
+                );
+            }
+        }
+        unsafe {
+            let len = self.len;
+            let buf = ptr::read(&self.buf);
+            mem::forget(self);
+            Ok(Vec { buf, len })
+        }
+    }
+
+    /// Shortens the vector, keeping the first `len` elements and dropping
+    /// the rest.
+    ///
+    /// If `len` is greater than the vector's current length, this has no
+    /// effect.
+    pub fn truncate(&mut self, len: usize) {
+        unsafe {
+            if len > self.len {
+                return;
+            }
+            let remaining_len = self.len - len;
+            let s = ptr::slice_from_raw_parts_mut(self.as_mut_ptr().add(len), remaining_len);
+            self.len = len;
+            ptr::drop_in_place(s);
+        }
+    }
+
+    /// Extracts a slice containing the entire vector.
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        self
+    }
+
+    /// Extracts a mutable slice of the entire vector.
+    #[inline]
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        self
+    }
+
+    /// Returns a raw pointer to the vector's buffer.
+    ///
+    /// The pointer is invalidated when the vector is moved while it has not spilled, or when it
+    /// reallocates.
+    #[inline]
+    pub fn as_ptr(&self) -> *const T {
+        if self.spilled() {
+            self.buf.ptr()
+        } else {
+            self.inline.as_ptr() as *const T
+        }
+    }
+
+    /// Returns an unsafe mutable pointer to the vector's buffer.
+    ///
+    /// The pointer is invalidated when the vector is moved while it has not spilled, or when it
+    /// reallocates.
+    #[inline]
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        if self.spilled() {
+            self.buf.ptr()
+        } else {
+            self.inline.as_mut_ptr() as *mut T
+        }
+    }
+
+    /// Forces the length of the vector to `new_len`.
+    ///
+    /// # Safety
+    ///
+    /// - `new_len` must be less than or equal to [`capacity()`].
+    /// - The elements at `old_len..new_len` must be initialized.
+    ///
+    /// [`capacity()`]: Self::capacity()
+    #[inline]
+    pub unsafe fn set_len(&mut self, new_len: usize) {
+        debug_assert!(new_len <= self.capacity());
+        self.len = new_len;
+    }
+
+    /// Removes an element from the vector and returns it.
+    ///
+    /// The removed element is replaced by the last element of the vector.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn swap_remove(&mut self, index: usize) -> T {
+        let len = self.len;
+        assert!(index < len);
+        unsafe {
+            let last = ptr::read(self.as_ptr().add(len - 1));
+            let hole = self.as_mut_ptr().add(index);
+            self.len = len - 1;
+            ptr::replace(hole, last)
+        }
+    }
+
+    /// Inserts an element at position `index` within the vector, shifting all
+    /// elements after it to the right.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len` or if the reallocation fails.
+    pub fn insert(&mut self, index: usize, element: T) {
+        handle_reserve_error(
+            self.try_insert(index, element)
+                .map_err(TryPushError::into_error),
+        );
+    }
+
+    /// Same as `insert` but returns errors instead of panicking
+    ///
+    /// On failure, `element` is handed back inside the [`TryPushError`].
+    pub fn try_insert(&mut self, index: usize, element: T) -> Result<(), TryPushError<T>> {
+        let len = self.len;
+        assert!(index <= len);
+
+        if len == self.capacity() {
+            if let Err(error) = self.try_reserve(1) {
+                return Err(TryPushError::new(element, error));
+            }
+        }
+
+        unsafe {
+            let p = self.as_mut_ptr().add(index);
+            ptr::copy(p, p.offset(1), len - index);
+            ptr::write(p, element);
+            self.len = len + 1;
+        }
+        Ok(())
+    }
+
+    /// Removes and returns the element at position `index` within the vector,
+    /// shifting all elements after it to the left.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn remove(&mut self, index: usize) -> T {
+        let len = self.len;
+        assert!(index < len);
+        unsafe {
+            let ptr = self.as_mut_ptr().add(index);
+            let ret = ptr::read(ptr);
+            ptr::copy(ptr.offset(1), ptr, len - index - 1);
+            self.len = len - 1;
+            ret
+        }
+    }
+
+    /// Retains only the elements specified by the predicate.
+    ///
+    /// In other words, remove all elements `e` such that `f(&e)` returns `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use alloc_wg::vec::SmallVec;
+    ///
+    /// let mut vec: SmallVec<_, 4> = (1..=4).collect();
+    /// vec.retain(|&x| x % 2 == 0);
+    /// assert_eq!(vec, [2, 4]);
+    /// ```
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.drain_filter(|x| !f(x));
+    }
+
+    /// Removes all but the first of consecutive elements in the vector that resolve to the same
+    /// key.
+    #[inline]
+    pub fn dedup_by_key<F, K>(&mut self, mut key: F)
+    where
+        F: FnMut(&mut T) -> K,
+        K: PartialEq,
+    {
+        self.dedup_by(|a, b| key(a) == key(b))
+    }
+
+    /// Removes all but the first of consecutive elements in the vector satisfying a given equality
+    /// relation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use alloc_wg::vec::SmallVec;
+    ///
+    /// let mut vec: SmallVec<_, 8> = ["foo", "bar", "Bar", "baz", "bar"].iter().copied().collect();
+    ///
+    /// vec.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+    ///
+    /// assert_eq!(vec, ["foo", "bar", "baz", "bar"]);
+    /// ```
+    pub fn dedup_by<F>(&mut self, same_bucket: F)
+    where
+        F: FnMut(&mut T, &mut T) -> bool,
+    {
+        let len = {
+            let (dedup, _) = partition_dedup_by(self.as_mut_slice(), same_bucket);
+            dedup.len()
+        };
+        self.truncate(len);
+    }
+
+    /// Appends an element to the back of a collection.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the reallocation fails.
+    #[inline]
+    pub fn push(&mut self, value: T) {
+        handle_reserve_error(self.try_push(value).map_err(TryPushError::into_error));
+    }
+
+    /// Same as `push` but returns errors instead of panicking
+    ///
+    /// On failure, `value` is handed back inside the [`TryPushError`].
+    #[inline]
+    pub fn try_push(&mut self, value: T) -> Result<(), TryPushError<T>> {
+        if self.len == self.capacity() {
+            if let Err(error) = self.try_reserve(1) {
+                return Err(TryPushError::new(value, error));
+            }
+        }
+        unsafe {
+            self.push_unchecked(value);
+        }
+        Ok(())
+    }
+
+    unsafe fn push_unchecked(&mut self, value: T) {
+        let len = self.len;
+        debug_assert!(self.capacity() > len);
+        ptr::write(self.as_mut_ptr().add(len), value);
+        self.len = len + 1;
+    }
+
+    /// Removes the last element from a vector and returns it, or [`None`] if it
+    /// is empty.
+    #[inline]
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            None
+        } else {
+            unsafe {
+                self.len -= 1;
+                Some(ptr::read(self.as_ptr().add(self.len)))
+            }
+        }
+    }
+
+    /// Moves all the elements of `other` into `Self`, leaving `other` empty.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the reallocation fails.
+    #[inline]
+    pub fn append(&mut self, other: &mut Self) {
+        handle_reserve_error(self.try_append(other));
+    }
+
+    /// Same as `append` but returns errors instead of panicking.
+    pub fn try_append(&mut self, other: &mut Self) -> Result<(), TryReserveError> {
+        let count = other.len;
+        self.try_reserve(count)?;
+        unsafe {
+            ptr::copy_nonoverlapping(other.as_ptr(), self.as_mut_ptr().add(self.len), count);
+            self.len += count;
+            other.len = 0;
+        }
+        Ok(())
+    }
+
+    /// Creates a draining iterator that removes the specified range in the vector
+    /// and yields the removed items.
+    ///
+    /// The element range is removed even if the iterator is only partially consumed or not
+    /// consumed at all.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the starting point is greater than the end point or if
+    /// the end point is greater than the length of the vector.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use alloc_wg::vec::SmallVec;
+    ///
+    /// let mut v: SmallVec<_, 4> = (1..=3).collect();
+    /// let u: Vec<_> = v.drain(1..).collect();
+    /// assert_eq!(v, [1]);
+    /// assert_eq!(u, [2, 3]);
+    /// ```
+    pub fn drain<R>(&mut self, range: R) -> Drain<'_, T, N, A>
+    where
+        R: RangeBounds<usize>,
+    {
+        let len = self.len;
+        let start = match range.start_bound() {
+            Included(&n) => n,
+            Excluded(&n) => n + 1,
+            Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Included(&n) => n + 1,
+            Excluded(&n) => n,
+            Unbounded => len,
+        };
+        assert!(start <= end);
+        assert!(end <= len);
+
+        unsafe {
+            // set self.vec length's to start, to be safe in case Drain is leaked
+            self.len = start;
+            let range_slice = slice::from_raw_parts_mut(self.as_mut_ptr().add(start), end - start);
+            Drain {
+                tail_start: end,
+                tail_len: len - end,
+                iter: range_slice.iter(),
+                vec: NonNull::from(self),
+            }
+        }
+    }
+
+    /// Creates a splicing iterator that replaces the specified range in the vector
+    /// with the given `replace_with` iterator and yields the removed items.
+    /// `replace_with` does not need to be the same length as `range`.
+    ///
+    /// The input iterator `replace_with` is only consumed when the `Splice` value is dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the starting point is greater than the end point or if
+    /// the end point is greater than the length of the vector.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use alloc_wg::vec::SmallVec;
+    ///
+    /// let mut v: SmallVec<_, 4> = (1..=3).collect();
+    /// let new = [7, 8, 9];
+    /// let u: Vec<_> = v.splice(..2, new.iter().cloned()).collect();
+    /// assert_eq!(v, [7, 8, 9, 3]);
+    /// assert_eq!(u, [1, 2]);
+    /// ```
+    #[inline]
+    pub fn splice<R, I>(&mut self, range: R, replace_with: I) -> Splice<'_, I::IntoIter, N, A>
+    where
+        R: RangeBounds<usize>,
+        I: IntoIterator<Item = T>,
+    {
+        Splice {
+            drain: self.drain(range),
+            replace_with: replace_with.into_iter(),
+        }
+    }
+
+    /// Creates an iterator which uses a closure to determine if an element should be removed.
+    ///
+    /// If the closure returns true, then the element is removed and yielded.
+    /// If the closure returns false, the element will remain in the vector and will not be yielded
+    /// by the iterator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use alloc_wg::vec::SmallVec;
+    ///
+    /// let mut numbers: SmallVec<_, 16> = [1, 2, 3, 4, 5, 6, 8, 9].iter().copied().collect();
+    ///
+    /// let evens = numbers.drain_filter(|x| *x % 2 == 0).collect::<Vec<_>>();
+    ///
+    /// assert_eq!(evens, [2, 4, 6, 8]);
+    /// assert_eq!(numbers, [1, 3, 5, 9]);
+    /// ```
+    pub fn drain_filter<F>(&mut self, filter: F) -> DrainFilter<'_, T, F, N, A>
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        let old_len = self.len;
+
+        // Guard against us getting leaked (leak amplification)
+        self.len = 0;
+
+        DrainFilter {
+            vec: self,
+            idx: 0,
+            del: 0,
+            old_len,
+            pred: filter,
+            panic_flag: false,
+        }
+    }
+
+    /// Clears the vector, removing all values.
+    ///
+    /// Note that this method has no effect on the allocated capacity
+    /// of the vector.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.truncate(0)
+    }
+
+    /// Returns the number of elements in the vector.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the vector contains no elements.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Resizes the `SmallVec` in-place so that `len` is equal to `new_len`, filling any new
+    /// slots with the values returned by calling the closure `f`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the reallocation fails.
+    pub fn resize_with<F>(&mut self, new_len: usize, f: F)
+    where
+        F: FnMut() -> T,
+    {
+        handle_reserve_error(self.try_resize_with(new_len, f));
+    }
+
+    /// Same as `resize_with` but returns errors instead of panicking.
+    pub fn try_resize_with<F>(&mut self, new_len: usize, mut f: F) -> Result<(), TryReserveError>
+    where
+        F: FnMut() -> T,
+    {
+        let len = self.len;
+        if new_len > len {
+            self.try_reserve(new_len - len)?;
+            for _ in len..new_len {
+                unsafe { self.push_unchecked(f()) }
+            }
+        } else {
+            self.truncate(new_len);
+        }
+        Ok(())
+    }
+
+    /// Returns a shared reference to the allocator backing this `SmallVec`.
+    #[inline]
+    pub fn alloc_ref(&self) -> &A {
+        self.buf.alloc()
+    }
+
+    /// Returns a mutable reference to the allocator backing this `SmallVec`.
+    #[inline]
+    pub fn alloc_ref_mut(&mut self) -> &mut A {
+        self.buf.alloc_mut()
+    }
+}
+
+impl<T: Clone, const N: usize, A: AllocRef> SmallVec<T, N, A> {
+    /// Resizes the `SmallVec` in-place so that `len` is equal to `new_len`, filling any new
+    /// slots with clones of `value`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the reallocation fails.
+    pub fn resize(&mut self, new_len: usize, value: T) {
+        handle_reserve_error(self.try_resize(new_len, value));
+    }
+
+    /// Same as `resize` but returns errors instead of panicking.
+    pub fn try_resize(&mut self, new_len: usize, value: T) -> Result<(), TryReserveError> {
+        self.try_resize_with(new_len, || value.clone())
+    }
+
+    /// Clones and appends all elements in a slice to the `SmallVec`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the reallocation fails.
+    pub fn extend_from_slice(&mut self, other: &[T]) {
+        handle_reserve_error(self.try_extend_from_slice(other));
+    }
+
+    /// Same as `extend_from_slice` but returns errors instead of panicking.
+    pub fn try_extend_from_slice(&mut self, other: &[T]) -> Result<(), TryReserveError> {
+        self.try_extend(other.iter().cloned())
+    }
+}
+
+impl<T: PartialEq, const N: usize, A: AllocRef> SmallVec<T, N, A> {
+    /// Removes consecutive repeated elements in the vector according to the
+    /// [`PartialEq`] trait implementation.
+    #[inline]
+    pub fn dedup(&mut self) {
+        self.dedup_by(|a, b| a == b)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Common trait implementations for SmallVec
+////////////////////////////////////////////////////////////////////////////////
+
+impl<T, const N: usize, A: AllocRef> ops::Deref for SmallVec<T, N, A> {
+    type Target = [T];
+
+    #[must_use]
+    fn deref(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.as_ptr(), self.len) }
+    }
+}
+
+impl<T, const N: usize, A: AllocRef> ops::DerefMut for SmallVec<T, N, A> {
+    #[must_use]
+    fn deref_mut(&mut self) -> &mut [T] {
+        unsafe { slice::from_raw_parts_mut(self.as_mut_ptr(), self.len) }
+    }
+}
+
+unsafe impl<#[may_dangle] T, const N: usize, A: AllocRef> Drop for SmallVec<T, N, A> {
+    fn drop(&mut self) {
+        unsafe {
+            // use drop for [T]
+            ptr::drop_in_place(&mut self[..]);
+        }
+        // RawVec handles deallocation
+    }
+}
+
+impl<T: Clone, const N: usize, A> Clone for SmallVec<T, N, A>
+where
+    A: AllocRef + Clone,
+{
+    #[must_use]
+    fn clone(&self) -> Self {
+        self.clone_in(self.alloc_ref().clone())
+    }
+}
+
+#[allow(clippy::use_self)]
+impl<T: Clone, const N: usize, A: AllocRef, B: AllocRef> CloneIn<B> for SmallVec<T, N, A> {
+    type Cloned = SmallVec<T, N, B>;
+
+    fn clone_in(&self, a: B) -> Self::Cloned {
+        let mut v = SmallVec::with_capacity_in(self.len, a);
+        self.iter()
+            .cloned()
+            .for_each(|element| unsafe { v.push_unchecked(element) });
+        v
+    }
+
+    fn try_clone_in(&self, a: B) -> Result<Self::Cloned, TryReserveError> {
+        let mut v = SmallVec::try_with_capacity_in(self.len, a)?;
+        self.iter()
+            .cloned()
+            .for_each(|element| unsafe { v.push_unchecked(element) });
+        Ok(v)
+    }
+}
+
+impl<T, const N: usize, A: AllocRef> From<Vec<T, A>> for SmallVec<T, N, A> {
+    /// Converts a `Vec` into a `SmallVec`, reusing its heap buffer.
+    ///
+    /// The resulting vector is spilled unless `vec` did not allocate.
+    fn from(vec: Vec<T, A>) -> Self {
+        unsafe {
+            let len = vec.len;
+            let buf = ptr::read(&vec.buf);
+            mem::forget(vec);
+            Self {
+                inline: MaybeUninit::uninit(),
+                buf,
+                len,
+            }
+        }
+    }
+}
+
+impl<T, const N: usize, A: AllocRef> From<SmallVec<T, N, A>> for Vec<T, A> {
+    /// Converts a `SmallVec` into a `Vec`, reusing the heap buffer if it has spilled.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the allocation fails.
+    fn from(vec: SmallVec<T, N, A>) -> Self {
+        vec.into_vec()
+    }
+}
+
+impl<T: Hash, const N: usize, A: AllocRef> Hash for SmallVec<T, N, A> {
+    #[inline]
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        Hash::hash(&**self, state)
+    }
+}
+
+impl<T: fmt::Debug, const N: usize, A: AllocRef> fmt::Debug for SmallVec<T, N, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<T, const N: usize, A: AllocRef> Default for SmallVec<T, N, A>
+where
+    A: Default,
+{
+    /// Creates an empty `SmallVec<T, N>`.
+    #[must_use]
+    fn default() -> Self {
+        Self::new_in(A::default())
+    }
+}
+
+impl<T, const N: usize, A: AllocRef> AsRef<[T]> for SmallVec<T, N, A> {
+    fn as_ref(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T, const N: usize, A: AllocRef> AsMut<[T]> for SmallVec<T, N, A> {
+    fn as_mut(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+macro_rules! __impl_slice_eq1 {
+    ([$($vars:tt)*] $lhs:ty, $rhs:ty, $($constraints:tt)*) => {
+        impl<T, U, $($vars)*> PartialEq<$rhs> for $lhs
+        where
+            T: PartialEq<U>,
+            $($constraints)*
+        {
+            #[inline]
+            fn eq(&self, other: &$rhs) -> bool { self[..] == other[..] }
+        }
+    }
+}
+
+__impl_slice_eq1! { [A, B, const N: usize, const M: usize] SmallVec<T, N, A>, SmallVec<U, M, B>, A: AllocRef, B: AllocRef }
+__impl_slice_eq1! { [A, B, const N: usize] SmallVec<T, N, A>, Vec<U, B>, A: AllocRef, B: AllocRef }
+__impl_slice_eq1! { [A, const N: usize] SmallVec<T, N, A>, &[U], A: AllocRef }
+__impl_slice_eq1! { [A, const N: usize] SmallVec<T, N, A>, &mut [U], A: AllocRef }
+__impl_slice_eq1! { [A, const N: usize, const M: usize] SmallVec<T, N, A>, [U; M], A: AllocRef }
+__impl_slice_eq1! { [A, const N: usize, const M: usize] SmallVec<T, N, A>, &[U; M], A: AllocRef }
+
+impl<T: Eq, const N: usize, A: AllocRef> Eq for SmallVec<T, N, A> {}
+
+impl<T: PartialOrd, const N: usize, A: AllocRef> PartialOrd for SmallVec<T, N, A> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        PartialOrd::partial_cmp(&**self, &**other)
+    }
+}
+
+impl<T: Ord, const N: usize, A: AllocRef> Ord for SmallVec<T, N, A> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        Ord::cmp(&**self, &**other)
+    }
+}
+
+impl<T, const N: usize> FromIterator<T> for SmallVec<T, N> {
+    #[inline]
+    #[must_use]
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::from_iter_in(iter, Global)
+    }
+}
+
+impl<T, const N: usize, A: AllocRef> FromIteratorIn<T, A> for SmallVec<T, N, A> {
+    fn from_iter_in<I: IntoIterator<Item = T>>(iter: I, a: A) -> Self {
+        let mut vec = Self::new_in(a);
+        vec.extend(iter);
+        vec
+    }
+
+    fn try_from_iter_in<I: IntoIterator<Item = T>>(iter: I, a: A) -> Result<Self, TryReserveError> {
+        let mut vec = Self::new_in(a);
+        vec.try_extend(iter)?;
+        Ok(vec)
+    }
+}
+
+impl<T, const N: usize, A: AllocRef> Extend<T> for SmallVec<T, N, A> {
+    #[inline]
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        crate::handle_reserve_error(self.try_extend(iter))
+    }
+}
+
+impl<'a, T: 'a + Copy, const N: usize, A: AllocRef> Extend<&'a T> for SmallVec<T, N, A> {
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().cloned())
+    }
+}
+
+impl<T, const N: usize, A: AllocRef> TryExtend<T> for SmallVec<T, N, A> {
+    type Err = TryReserveError;
+
+    fn try_extend<I: IntoIterator<Item = T>>(&mut self, iter: I) -> Result<(), Self::Err> {
+        let mut iterator = iter.into_iter();
+        while let Some(element) = iterator.next() {
+            if self.len == self.capacity() {
+                let (lower, _) = iterator.size_hint();
+                self.try_reserve(lower.saturating_add(1))?;
+            }
+            unsafe {
+                self.push_unchecked(element);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'a, T: 'a + Copy, const N: usize, A: AllocRef> TryExtend<&'a T> for SmallVec<T, N, A> {
+    type Err = TryReserveError;
+
+    fn try_extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) -> Result<(), Self::Err> {
+        self.try_extend(iter.into_iter().cloned())
+    }
+}
+
+impl<T, const N: usize, A: AllocRef> IntoIterator for SmallVec<T, N, A> {
+    type Item = T;
+    type IntoIter = IntoIter<T, N, A>;
+
+    /// Creates a consuming iterator, that is, one that moves each value out of
+    /// the vector (from start to end).
+    #[inline]
+    fn into_iter(mut self) -> IntoIter<T, N, A> {
+        let end = self.len;
+        self.len = 0;
+        IntoIter {
+            vec: self,
+            start: 0,
+            end,
+        }
+    }
+}
+
+impl<'a, T, const N: usize, A: AllocRef> IntoIterator for &'a SmallVec<T, N, A> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> slice::Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T, const N: usize, A: AllocRef> IntoIterator for &'a mut SmallVec<T, N, A> {
+    type Item = &'a mut T;
+    type IntoIter = slice::IterMut<'a, T>;
+
+    fn into_iter(self) -> slice::IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Iterators
+////////////////////////////////////////////////////////////////////////////////
+
+/// An iterator that moves out of a `SmallVec`.
+///
+/// This `struct` is created by the `into_iter` method on [`SmallVec`].
+pub struct IntoIter<T, const N: usize, A: AllocRef = Global> {
+    // `vec.len` is zero, the remaining elements are `start..end`
+    vec: SmallVec<T, N, A>,
+    start: usize,
+    end: usize,
+}
+
+impl<T, const N: usize, A: AllocRef> IntoIter<T, N, A> {
+    /// Returns the remaining items of this iterator as a slice.
+    #[must_use]
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.vec.as_ptr().add(self.start), self.end - self.start) }
+    }
+
+    /// Returns the remaining items of this iterator as a mutable slice.
+    #[must_use]
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        unsafe {
+            slice::from_raw_parts_mut(self.vec.as_mut_ptr().add(self.start), self.end - self.start)
+        }
+    }
+}
+
+impl<T: fmt::Debug, const N: usize, A: AllocRef> fmt::Debug for IntoIter<T, N, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("IntoIter").field(&self.as_slice()).finish()
+    }
+}
+
+impl<T, const N: usize, A: AllocRef> Iterator for IntoIter<T, N, A> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        if self.start == self.end {
+            None
+        } else {
+            unsafe {
+                let item = ptr::read(self.vec.as_ptr().add(self.start));
+                self.start += 1;
+                Some(item)
+            }
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let exact = self.end - self.start;
+        (exact, Some(exact))
+    }
+}
+
+impl<T, const N: usize, A: AllocRef> DoubleEndedIterator for IntoIter<T, N, A> {
+    #[inline]
+    fn next_back(&mut self) -> Option<T> {
+        if self.start == self.end {
+            None
+        } else {
+            unsafe {
+                self.end -= 1;
+                Some(ptr::read(self.vec.as_ptr().add(self.end)))
+            }
+        }
+    }
+}
+
+impl<T, const N: usize, A: AllocRef> ExactSizeIterator for IntoIter<T, N, A> {}
+
+impl<T, const N: usize, A: AllocRef> FusedIterator for IntoIter<T, N, A> {}
+
+impl<T, const N: usize, A: AllocRef> Drop for IntoIter<T, N, A> {
+    fn drop(&mut self) {
+        // destroy the remaining elements
+        for _x in self.by_ref() {}
+
+        // `SmallVec` handles deallocation
+    }
+}
+
+/// A draining iterator for `SmallVec`.
+///
+/// This `struct` is created by the [`drain`] method on [`SmallVec`].
+///
+/// [`drain`]: SmallVec::drain()
+pub struct Drain<'a, T, const N: usize, A: AllocRef = Global> {
+    /// Index of tail to preserve
+    tail_start: usize,
+    /// Length of tail
+    tail_len: usize,
+    /// Current remaining range to remove
+    iter: slice::Iter<'a, T>,
+    vec: NonNull<SmallVec<T, N, A>>,
+}
+
+impl<T: fmt::Debug, const N: usize, A: AllocRef> fmt::Debug for Drain<'_, T, N, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Drain").field(&self.iter.as_slice()).finish()
+    }
+}
+
+impl<T, const N: usize, A: AllocRef> Drain<'_, T, N, A> {
+    /// Returns the remaining items of this iterator as a slice.
+    #[must_use]
+    pub fn as_slice(&self) -> &[T] {
+        self.iter.as_slice()
+    }
+
+    /// The range from `self.vec.len` to `self.tail_start` contains elements
+    /// that have been moved out.
+    /// Fill that range as much as possible with new elements from the `replace_with` iterator.
+    /// Returns `true` if we filled the entire range. (`replace_with.next()` didn’t return `None`.)
+    unsafe fn fill<I: Iterator<Item = T>>(&mut self, replace_with: &mut I) -> bool {
+        let vec = self.vec.as_mut();
+        let range_start = vec.len;
+        let range_end = self.tail_start;
+        let range_slice =
+            slice::from_raw_parts_mut(vec.as_mut_ptr().add(range_start), range_end - range_start);
+
+        for place in range_slice {
+            if let Some(new_item) = replace_with.next() {
+                ptr::write(place, new_item);
+                vec.len += 1;
+            } else {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Makes room for inserting more elements before the tail.
+    unsafe fn move_tail(&mut self, extra_capacity: usize) {
+        let vec = self.vec.as_mut();
+        let used_capacity = self.tail_start + self.tail_len;
+        crate::handle_reserve_error(vec.try_grow(used_capacity, extra_capacity, false));
+
+        let new_tail_start = self.tail_start + extra_capacity;
+        let src = vec.as_ptr().add(self.tail_start);
+        let dst = vec.as_mut_ptr().add(new_tail_start);
+        ptr::copy(src, dst, self.tail_len);
+        self.tail_start = new_tail_start;
+    }
+}
+
+unsafe impl<T: Sync, const N: usize, A: AllocRef> Sync for Drain<'_, T, N, A> {}
+unsafe impl<T: Send, const N: usize, A: AllocRef> Send for Drain<'_, T, N, A> {}
+
+impl<T, const N: usize, A: AllocRef> Iterator for Drain<'_, T, N, A> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        self.iter.next().map(|elt| unsafe { ptr::read(elt) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T, const N: usize, A: AllocRef> DoubleEndedIterator for Drain<'_, T, N, A> {
+    #[inline]
+    fn next_back(&mut self) -> Option<T> {
+        self.iter.next_back().map(|elt| unsafe { ptr::read(elt) })
+    }
+}
+
+impl<T, const N: usize, A: AllocRef> Drop for Drain<'_, T, N, A> {
+    fn drop(&mut self) {
+        // exhaust self first
+        self.for_each(drop);
+
+        if self.tail_len > 0 {
+            unsafe {
+                let source_vec = self.vec.as_mut();
+                // memmove back untouched tail, update to new length
+                let start = source_vec.len;
+                let tail = self.tail_start;
+                if tail != start {
+                    let src = source_vec.as_ptr().add(tail);
+                    let dst = source_vec.as_mut_ptr().add(start);
+                    ptr::copy(src, dst, self.tail_len);
+                }
+                source_vec.len = start + self.tail_len;
+            }
+        }
+    }
+}
+
+impl<T, const N: usize, A: AllocRef> ExactSizeIterator for Drain<'_, T, N, A> {}
+
+impl<T, const N: usize, A: AllocRef> FusedIterator for Drain<'_, T, N, A> {}
+
+/// A splicing iterator for `SmallVec`.
+///
+/// This struct is created by the [`splice()`] method on [`SmallVec`]. See its
+/// documentation for more.
+///
+/// [`splice()`]: SmallVec::splice()
+#[derive(Debug)]
+pub struct Splice<'a, I: Iterator + 'a, const N: usize, A: AllocRef = Global> {
+    drain: Drain<'a, I::Item, N, A>,
+    replace_with: I,
+}
+
+impl<I: Iterator, const N: usize, A: AllocRef> Iterator for Splice<'_, I, N, A> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.drain.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.drain.size_hint()
+    }
+}
+
+impl<I: Iterator, const N: usize, A: AllocRef> DoubleEndedIterator for Splice<'_, I, N, A> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.drain.next_back()
+    }
+}
+
+impl<I: Iterator, const N: usize, A: AllocRef> ExactSizeIterator for Splice<'_, I, N, A> {}
+
+impl<I: Iterator, const N: usize, A: AllocRef> Drop for Splice<'_, I, N, A> {
+    fn drop(&mut self) {
+        self.drain.by_ref().for_each(drop);
+
+        unsafe {
+            if self.drain.tail_len == 0 {
+                self.drain.vec.as_mut().extend(self.replace_with.by_ref());
+                return;
+            }
+
+            // First fill the range left by drain().
+            if !self.drain.fill(&mut self.replace_with) {
+                return;
+            }
+
+            // There may be more elements. Use the lower bound as an estimate.
+            let (lower_bound, _upper_bound) = self.replace_with.size_hint();
+            if lower_bound > 0 {
+                self.drain.move_tail(lower_bound);
+                if !self.drain.fill(&mut self.replace_with) {
+                    return;
+                }
+            }
+
+            // Collect any remaining elements.
+            // This is a zero-length vector which does not allocate if `lower_bound` was exact.
+            let mut collected = Vec::new();
+            collected.extend(self.replace_with.by_ref());
+            let mut collected = collected.into_iter();
+            // Now we have an exact count.
+            if collected.len() > 0 {
+                self.drain.move_tail(collected.len());
+                let filled = self.drain.fill(&mut collected);
+                debug_assert!(filled);
+                debug_assert_eq!(collected.len(), 0);
+            }
+        }
+        // Let `Drain::drop` move the tail back if necessary and restore `vec.len`.
+    }
+}
+
+/// An iterator produced by calling `drain_filter` on `SmallVec`.
+pub struct DrainFilter<'a, T, F, const N: usize, A: AllocRef = Global>
+where
+    F: FnMut(&mut T) -> bool,
+{
+    vec: &'a mut SmallVec<T, N, A>,
+    /// The index of the item that will be inspected by the next call to `next`.
+    idx: usize,
+    /// The number of items that have been drained (removed) thus far.
+    del: usize,
+    /// The original length of `vec` prior to draining.
+    old_len: usize,
+    /// The filter test predicate.
+    pred: F,
+    /// A flag that indicates a panic has occured in the filter test prodicate.
+    panic_flag: bool,
+}
+
+impl<T, F, const N: usize, A: AllocRef> Iterator for DrainFilter<'_, T, F, N, A>
+where
+    F: FnMut(&mut T) -> bool,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        unsafe {
+            while self.idx < self.old_len {
+                let i = self.idx;
+                let v = slice::from_raw_parts_mut(self.vec.as_mut_ptr(), self.old_len);
+                self.panic_flag = true;
+                let drained = (self.pred)(&mut v[i]);
+                self.panic_flag = false;
+                // Update the index *after* the predicate is called. If the index
+                // is updated prior and the predicate panics, the element at this
+                // index would be leaked.
+                self.idx += 1;
+                if drained {
+                    self.del += 1;
+                    return Some(ptr::read(&v[i]));
+                } else if self.del > 0 {
+                    let del = self.del;
+                    let src: *const T = &v[i];
+                    let dst: *mut T = &mut v[i - del];
+                    ptr::copy_nonoverlapping(src, dst, 1);
+                }
+            }
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.old_len - self.idx))
+    }
+}
+
+impl<T, F, const N: usize, A: AllocRef> Drop for DrainFilter<'_, T, F, N, A>
+where
+    F: FnMut(&mut T) -> bool,
+{
+    fn drop(&mut self) {
+        struct BackshiftOnDrop<'a, 'b, T, F, const N: usize, A: AllocRef>
+        where
+            F: FnMut(&mut T) -> bool,
+        {
+            drain: &'b mut DrainFilter<'a, T, F, N, A>,
+        }
+
+        impl<T, F, const N: usize, A: AllocRef> Drop for BackshiftOnDrop<'_, '_, T, F, N, A>
+        where
+            F: FnMut(&mut T) -> bool,
+        {
+            fn drop(&mut self) {
+                unsafe {
+                    if self.drain.idx < self.drain.old_len && self.drain.del > 0 {
+                        // Backshift the unprocessed elements so the last drained item is
+                        // not dropped twice, see `vec::DrainFilter`.
+                        let ptr = self.drain.vec.as_mut_ptr();
+                        let src = ptr.add(self.drain.idx);
+                        let dst = src.sub(self.drain.del);
+                        let tail_len = self.drain.old_len - self.drain.idx;
+                        src.copy_to(dst, tail_len);
+                    }
+                    self.drain.vec.len = self.drain.old_len - self.drain.del;
+                }
+            }
+        }
+
+        let backshift = BackshiftOnDrop { drain: self };
+
+        // Attempt to consume any remaining elements if the filter predicate
+        // has not yet panicked. We'll backshift any remaining elements
+        // whether we've already panicked or if the consumption here panics.
+        if !backshift.drain.panic_flag {
+            backshift.drain.for_each(drop);
+        }
+    }
+}
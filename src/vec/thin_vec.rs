@@ -0,0 +1,1025 @@
+//! A vector which is a single pointer wide, written `ThinVec<T, A>`.
+//!
+//! The length, the capacity and the allocator are stored in a header in front of the elements,
+//! similar to how [`Arc`] keeps its allocator inside `ArcInner`. An empty `ThinVec` created by
+//! [`ThinVec::new`] points to a shared static header and does not allocate.
+//!
+//! This makes `ThinVec` a good fit for structs with many vectors which are usually empty.
+//!
+//! # Examples
+//!
+//! ```
+//! use alloc_wg::vec::ThinVec;
+//! use core::mem::size_of;
+//!
+//! assert_eq!(size_of::<ThinVec<u8>>(), size_of::<usize>());
+//! assert_eq!(size_of::<Option<ThinVec<u8>>>(), size_of::<usize>());
+//!
+//! let mut v = ThinVec::new();
+//! v.push(1);
+//! v.push(2);
+//! assert_eq!(v, [1, 2]);
+//! ```
+//!
+//! [`Arc`]: crate::sync::Arc
+//! [`ThinVec::new`]: ThinVec::new()
+
+use super::{Doubling, GrowthPolicy, Vec};
+use crate::{
+    alloc::{AllocRef, Global, Layout},
+    clone::CloneIn,
+    collections::{
+        TryPushError,
+        TryReserveError::{self, CapacityOverflow},
+    },
+    handle_reserve_error,
+    iter::{FromIteratorIn, TryExtend},
+};
+use core::{
+    cmp::Ordering,
+    fmt,
+    hash::{self, Hash},
+    iter::{FromIterator, FusedIterator},
+    marker::PhantomData,
+    mem::{self, ManuallyDrop},
+    ops,
+    ptr::{self, NonNull},
+    slice,
+};
+
+/// The header in front of the elements of a `ThinVec`.
+///
+/// `len` and `cap` come first so they can be read without knowing `A`, which is what makes the
+/// shared `EMPTY_HEADER` work for every allocator.
+#[repr(C)]
+struct Header<A> {
+    len: usize,
+    cap: usize,
+    alloc: A,
+}
+
+static EMPTY_HEADER: Header<()> = Header {
+    len: 0,
+    cap: 0,
+    alloc: (),
+};
+
+/// Produces the allocator for a `ThinVec` which still points to `EMPTY_HEADER`.
+///
+/// Only allocators implementing `Default` can be conjured this way, so `ThinVec`s for other
+/// allocators always own a header.
+trait EmptyHeaderAlloc: Sized {
+    fn empty_header_alloc() -> Option<Self>;
+}
+
+impl<A> EmptyHeaderAlloc for A {
+    default fn empty_header_alloc() -> Option<Self> {
+        None
+    }
+}
+
+impl<A: Default> EmptyHeaderAlloc for A {
+    fn empty_header_alloc() -> Option<Self> {
+        Some(A::default())
+    }
+}
+
+/// A contiguous growable array type which is a single pointer wide.
+///
+/// `ThinVec` has the same fallible `try_*` API as [`Vec`], but stores its length, capacity and
+/// allocator on the heap. As the allocator has to live somewhere, [`new_in`] allocates a header
+/// right away, whereas [`new`] uses a shared empty header for allocators implementing
+/// `Default`.
+///
+/// [`Vec`]: crate::vec::Vec
+/// [`new_in`]: Self::new_in()
+/// [`new`]: Self::new()
+pub struct ThinVec<T, A: AllocRef = Global> {
+    ptr: NonNull<Header<A>>,
+    marker: PhantomData<(T, A)>,
+}
+
+unsafe impl<T: Send, A: AllocRef + Send> Send for ThinVec<T, A> {}
+unsafe impl<T: Sync, A: AllocRef + Sync> Sync for ThinVec<T, A> {}
+
+impl<T> ThinVec<T> {
+    /// Constructs a new, empty `ThinVec<T>` with at least the specified capacity.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the requested capacity exceeds `isize::MAX` bytes.
+    #[inline]
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_in(capacity, Global)
+    }
+}
+
+impl<T, A: AllocRef + Default> ThinVec<T, A> {
+    /// Constructs a new, empty `ThinVec`.
+    ///
+    /// This does not allocate, the vector points to a shared empty header until elements are
+    /// pushed onto it.
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            ptr: NonNull::from(&EMPTY_HEADER).cast(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T, A: AllocRef> ThinVec<T, A> {
+    /// Like `new` but parameterized over the choice of allocator for the returned `ThinVec`.
+    ///
+    /// Unlike `Vec::new_in`, this allocates a header to store `a` in.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the allocation fails.
+    #[inline]
+    pub fn new_in(a: A) -> Self {
+        Self::with_capacity_in(0, a)
+    }
+
+    /// Same as `new_in` but returns errors instead of panicking.
+    #[inline]
+    pub fn try_new_in(a: A) -> Result<Self, TryReserveError> {
+        Self::try_with_capacity_in(0, a)
+    }
+
+    /// Like `with_capacity` but parameterized over the choice of allocator for the returned
+    /// `ThinVec`.
+    ///
+    /// # Panics
+    ///
+    /// * if the requested capacity exceeds `usize::MAX` bytes.
+    /// * on 32-bit platforms if the requested capacity exceeds `isize::MAX` bytes.
+    /// * if the allocation fails.
+    pub fn with_capacity_in(capacity: usize, a: A) -> Self {
+        handle_reserve_error(Self::try_with_capacity_in(capacity, a))
+    }
+
+    /// Like `with_capacity` but parameterized over the choice of allocator for the returned
+    /// `ThinVec`.
+    ///
+    /// # Errors
+    ///
+    /// * `InvalidLayout` if the requested capacity exceeds `usize::MAX` bytes.
+    /// * `CapacityOverflow` on 32-bit platforms if the requested capacity exceeds `isize::MAX` bytes.
+    /// * `AllocError` on OOM
+    pub fn try_with_capacity_in(capacity: usize, a: A) -> Result<Self, TryReserveError> {
+        Self::allocate_in(capacity, a).map_err(|(err, _)| err)
+    }
+
+    /// Allocates a header with room for `capacity` elements and moves `alloc` into it. The
+    /// allocator is handed back on failure.
+    fn allocate_in(capacity: usize, alloc: A) -> Result<Self, (TryReserveError, A)> {
+        let layout = match Self::layout(capacity) {
+            Ok(layout) => layout,
+            Err(err) => return Err((err, alloc)),
+        };
+        let memory = match alloc.alloc(layout) {
+            Ok(memory) => memory,
+            Err(err) => {
+                let err = TryReserveError::alloc_error(layout)
+                    .with_capacities(0, capacity)
+                    .with_cause(err);
+                return Err((err, alloc));
+            }
+        };
+        unsafe {
+            let header = memory.as_non_null_ptr().cast::<Header<A>>();
+            ptr::write(
+                header.as_ptr(),
+                Header {
+                    len: 0,
+                    cap: Self::capacity_from_bytes(memory.len()),
+                    alloc,
+                },
+            );
+            Ok(Self {
+                ptr: header,
+                marker: PhantomData,
+            })
+        }
+    }
+
+    /// The layout of a header followed by `capacity` elements.
+    fn layout(capacity: usize) -> Result<Layout, TryReserveError> {
+        let array = Layout::array::<T>(capacity)?;
+        let (layout, _) = Layout::new::<Header<A>>().extend(array)?;
+        let layout = layout.pad_to_align();
+        if mem::size_of::<usize>() < 8 && layout.size() > isize::MAX as usize {
+            Err(CapacityOverflow)
+        } else {
+            Ok(layout)
+        }
+    }
+
+    /// The offset of the first element from the start of the header.
+    fn data_offset() -> usize {
+        let header = Layout::new::<Header<A>>();
+        header.size() + header.padding_needed_for(mem::align_of::<T>())
+    }
+
+    fn capacity_from_bytes(size: usize) -> usize {
+        if mem::size_of::<T>() == 0 {
+            usize::MAX
+        } else {
+            (size - Self::data_offset()) / mem::size_of::<T>()
+        }
+    }
+
+    fn is_empty_header(&self) -> bool {
+        self.ptr.cast::<u8>() == NonNull::from(&EMPTY_HEADER).cast::<u8>()
+    }
+
+    /// Returns `true` if the vector owns a heap allocation, which is the case for every
+    /// `ThinVec` except the ones created by [`new`].
+    ///
+    /// [`new`]: Self::new()
+    #[inline]
+    #[must_use]
+    pub fn has_allocated(&self) -> bool {
+        !self.is_empty_header()
+    }
+
+    /// Returns the number of elements the vector can hold without reallocating.
+    #[inline]
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        // `len` and `cap` are the first fields of a `repr(C)` header
+        unsafe { *self.ptr.cast::<usize>().as_ptr().add(1) }
+    }
+
+    /// Returns the number of elements in the vector.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        unsafe { *self.ptr.cast::<usize>().as_ptr() }
+    }
+
+    /// Returns `true` if the vector contains no elements.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Forces the length of the vector to `new_len`.
+    ///
+    /// # Safety
+    ///
+    /// - `new_len` must be less than or equal to [`capacity()`].
+    /// - The elements at `old_len..new_len` must be initialized.
+    ///
+    /// [`capacity()`]: Self::capacity()
+    #[inline]
+    pub unsafe fn set_len(&mut self, new_len: usize) {
+        debug_assert!(new_len <= self.capacity());
+        if !self.is_empty_header() {
+            (*self.ptr.as_ptr()).len = new_len;
+        }
+    }
+
+    /// Returns a raw pointer to the vector's buffer.
+    #[inline]
+    pub fn as_ptr(&self) -> *const T {
+        if self.is_empty_header() {
+            NonNull::dangling().as_ptr()
+        } else {
+            unsafe { (self.ptr.as_ptr() as *mut u8).add(Self::data_offset()) as *const T }
+        }
+    }
+
+    /// Returns an unsafe mutable pointer to the vector's buffer.
+    #[inline]
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        self.as_ptr() as *mut T
+    }
+
+    /// Extracts a slice containing the entire vector.
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        self
+    }
+
+    /// Extracts a mutable slice of the entire vector.
+    #[inline]
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        self
+    }
+
+    /// Returns a shared reference to the allocator backing this `ThinVec`, or `None` if it
+    /// still points to the shared empty header.
+    #[inline]
+    pub fn alloc_ref(&self) -> Option<&A> {
+        if self.is_empty_header() {
+            None
+        } else {
+            unsafe { Some(&(*self.ptr.as_ptr()).alloc) }
+        }
+    }
+
+    /// Reserves capacity for at least `additional` more elements to be inserted.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity exceeds `isize::MAX` bytes or if the allocation fails.
+    pub fn reserve(&mut self, additional: usize) {
+        handle_reserve_error(self.try_reserve(additional));
+    }
+
+    /// Reserves the minimum capacity for exactly `additional` more elements to be inserted.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity exceeds `isize::MAX` bytes or if the allocation fails.
+    pub fn reserve_exact(&mut self, additional: usize) {
+        handle_reserve_error(self.try_reserve_exact(additional));
+    }
+
+    /// Same as `reserve` but returns errors instead of panicking.
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        let len = self.len();
+        if additional <= self.capacity().wrapping_sub(len) {
+            return Ok(());
+        }
+        let required = len.checked_add(additional).ok_or(CapacityOverflow)?;
+        let cap = Doubling::grow(self.capacity(), required, mem::size_of::<T>());
+        self.grow_to(cap)
+    }
+
+    /// Same as `reserve_exact` but returns errors instead of panicking.
+    pub fn try_reserve_exact(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        let len = self.len();
+        if additional <= self.capacity().wrapping_sub(len) {
+            return Ok(());
+        }
+        let required = len.checked_add(additional).ok_or(CapacityOverflow)?;
+        self.grow_to(required)
+    }
+
+    fn grow_to(&mut self, capacity: usize) -> Result<(), TryReserveError> {
+        if self.is_empty_header() {
+            let alloc = A::empty_header_alloc()
+                .expect("only `ThinVec`s with a default allocator use the empty header");
+            *self = Self::allocate_in(capacity, alloc).map_err(|(err, _)| err)?;
+            return Ok(());
+        }
+
+        let new_layout = Self::layout(capacity)?;
+        unsafe {
+            let old_layout = Self::layout(self.capacity()).unwrap();
+            self.realloc(old_layout, new_layout, capacity, |alloc, ptr| {
+                alloc.grow(ptr, old_layout, new_layout)
+            })
+        }
+    }
+
+    /// Reallocates the header with `f`. The allocator is moved out of the header for the
+    /// duration of the call, as the memory it lives in may be moved.
+    unsafe fn realloc(
+        &mut self,
+        old_layout: Layout,
+        new_layout: Layout,
+        new_capacity: usize,
+        f: impl FnOnce(&A, NonNull<u8>) -> Result<NonNull<[u8]>, crate::alloc::AllocError>,
+    ) -> Result<(), TryReserveError> {
+        debug_assert!(!self.is_empty_header());
+        let header = self.ptr.as_ptr();
+        let alloc = ManuallyDrop::new(ptr::read(&(*header).alloc));
+        let memory = f(&alloc, self.ptr.cast()).map_err(|err| {
+            TryReserveError::alloc_error(new_layout)
+                .with_capacities(self.capacity(), new_capacity)
+                .with_cause(err)
+        })?;
+        debug_assert!(old_layout.align() == new_layout.align());
+
+        let header = memory.as_non_null_ptr().cast::<Header<A>>();
+        // the old header has been copied, so only the allocator has to be put back
+        ptr::write(&mut (*header.as_ptr()).alloc, ManuallyDrop::into_inner(alloc));
+        (*header.as_ptr()).cap = Self::capacity_from_bytes(memory.len());
+        self.ptr = header;
+        Ok(())
+    }
+
+    /// Shrinks the capacity of the vector as much as possible.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the reallocation fails.
+    pub fn shrink_to_fit(&mut self) {
+        handle_reserve_error(self.try_shrink_to_fit());
+    }
+
+    /// Same as `shrink_to_fit` but returns errors instead of panicking.
+    pub fn try_shrink_to_fit(&mut self) -> Result<(), TryReserveError> {
+        let len = self.len();
+        if self.is_empty_header() || mem::size_of::<T>() == 0 || self.capacity() == len {
+            return Ok(());
+        }
+        unsafe {
+            let old_layout = Self::layout(self.capacity()).unwrap();
+            let new_layout = Self::layout(len).unwrap();
+            self.realloc(old_layout, new_layout, len, |alloc, ptr| {
+                alloc.shrink(ptr, old_layout, new_layout)
+            })
+        }
+    }
+
+    /// Appends an element to the back of a collection.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the reallocation fails.
+    #[inline]
+    pub fn push(&mut self, value: T) {
+        handle_reserve_error(self.try_push(value).map_err(TryPushError::into_error));
+    }
+
+    /// Same as `push` but returns errors instead of panicking
+    ///
+    /// On failure, `value` is handed back inside the [`TryPushError`].
+    #[inline]
+    pub fn try_push(&mut self, value: T) -> Result<(), TryPushError<T>> {
+        if self.len() == self.capacity() {
+            if let Err(error) = self.try_reserve(1) {
+                return Err(TryPushError::new(value, error));
+            }
+        }
+        unsafe {
+            self.push_unchecked(value);
+        }
+        Ok(())
+    }
+
+    unsafe fn push_unchecked(&mut self, value: T) {
+        let len = self.len();
+        debug_assert!(self.capacity() > len);
+        ptr::write(self.as_mut_ptr().add(len), value);
+        self.set_len(len + 1);
+    }
+
+    /// Removes the last element from a vector and returns it, or [`None`] if it
+    /// is empty.
+    #[inline]
+    pub fn pop(&mut self) -> Option<T> {
+        let len = self.len();
+        if len == 0 {
+            None
+        } else {
+            unsafe {
+                self.set_len(len - 1);
+                Some(ptr::read(self.as_ptr().add(len - 1)))
+            }
+        }
+    }
+
+    /// Inserts an element at position `index` within the vector, shifting all
+    /// elements after it to the right.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len` or if the reallocation fails.
+    pub fn insert(&mut self, index: usize, element: T) {
+        handle_reserve_error(
+            self.try_insert(index, element)
+                .map_err(TryPushError::into_error),
+        );
+    }
+
+    /// Same as `insert` but returns errors instead of panicking
+    ///
+    /// On failure, `element` is handed back inside the [`TryPushError`].
+    pub fn try_insert(&mut self, index: usize, element: T) -> Result<(), TryPushError<T>> {
+        let len = self.len();
+        assert!(index <= len);
+
+        if len == self.capacity() {
+            if let Err(error) = self.try_reserve(1) {
+                return Err(TryPushError::new(element, error));
+            }
+        }
+
+        unsafe {
+            let p = self.as_mut_ptr().add(index);
+            ptr::copy(p, p.offset(1), len - index);
+            ptr::write(p, element);
+            self.set_len(len + 1);
+        }
+        Ok(())
+    }
+
+    /// Removes and returns the element at position `index` within the vector,
+    /// shifting all elements after it to the left.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn remove(&mut self, index: usize) -> T {
+        let len = self.len();
+        assert!(index < len);
+        unsafe {
+            let ptr = self.as_mut_ptr().add(index);
+            let ret = ptr::read(ptr);
+            ptr::copy(ptr.offset(1), ptr, len - index - 1);
+            self.set_len(len - 1);
+            ret
+        }
+    }
+
+    /// Removes an element from the vector and returns it.
+    ///
+    /// The removed element is replaced by the last element of the vector.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn swap_remove(&mut self, index: usize) -> T {
+        let len = self.len();
+        assert!(index < len);
+        unsafe {
+            let last = ptr::read(self.as_ptr().add(len - 1));
+            let hole = self.as_mut_ptr().add(index);
+            self.set_len(len - 1);
+            ptr::replace(hole, last)
+        }
+    }
+
+    /// Shortens the vector, keeping the first `len` elements and dropping
+    /// the rest.
+    pub fn truncate(&mut self, len: usize) {
+        let old_len = self.len();
+        if len >= old_len {
+            return;
+        }
+        unsafe {
+            let s = ptr::slice_from_raw_parts_mut(self.as_mut_ptr().add(len), old_len - len);
+            self.set_len(len);
+            ptr::drop_in_place(s);
+        }
+    }
+
+    /// Clears the vector, removing all values.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.truncate(0)
+    }
+
+    /// Retains only the elements specified by the predicate.
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let len = self.len();
+        let mut del = 0;
+        {
+            let v = &mut **self;
+
+            for i in 0..len {
+                if !f(&v[i]) {
+                    del += 1;
+                } else if del > 0 {
+                    v.swap(i - del, i);
+                }
+            }
+        }
+        if del > 0 {
+            self.truncate(len - del);
+        }
+    }
+
+    /// Converts the vector into a [`Vec<T, A>`].
+    ///
+    /// The elements are moved to the front of the allocation and the buffer is reused if the
+    /// header and the elements share the same alignment and the allocation is a multiple of the
+    /// element size. Otherwise the elements are copied into a new allocation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the allocation fails.
+    ///
+    /// [`Vec<T, A>`]: crate::vec::Vec
+    pub fn into_vec(self) -> Vec<T, A> {
+        handle_reserve_error(self.try_into_vec())
+    }
+
+    /// Same as `into_vec` but returns errors instead of panicking.
+    pub fn try_into_vec(self) -> Result<Vec<T, A>, TryReserveError> {
+        if self.is_empty_header() {
+            mem::forget(self);
+            let alloc = A::empty_header_alloc()
+                .expect("only `ThinVec`s with a default allocator use the empty header");
+            return Ok(Vec::new_in(alloc));
+        }
+
+        unsafe {
+            let len = self.len();
+            let old_layout = Self::layout(self.capacity()).unwrap();
+            let elem_size = mem::size_of::<T>();
+            let base = self.ptr.cast::<u8>();
+
+            if elem_size == 0 {
+                let this = ManuallyDrop::new(self);
+                let alloc = ptr::read(&(*this.ptr.as_ptr()).alloc);
+                alloc.dealloc(base, old_layout);
+                let mut vec = Vec::new_in(alloc);
+                vec.set_len(len);
+                return Ok(vec);
+            }
+
+            if old_layout.align() == mem::align_of::<T>() && old_layout.size() % elem_size == 0 {
+                // reuse the allocation: the header is overwritten by the elements
+                let this = ManuallyDrop::new(self);
+                let alloc = ptr::read(&(*this.ptr.as_ptr()).alloc);
+                ptr::copy(this.as_ptr(), base.cast::<T>().as_ptr(), len);
+                let capacity = old_layout.size() / elem_size;
+                return Ok(Vec::from_raw_parts_in(
+                    base.cast::<T>().as_ptr(),
+                    len,
+                    capacity,
+                    alloc,
+                ));
+            }
+
+            let new_layout = Layout::array::<T>(len)?;
+            let memory = self
+                .alloc_ref()
+                .unwrap()
+                .alloc(new_layout)
+                .map_err(|err| TryReserveError::alloc_error(new_layout).with_cause(err))?;
+            let this = ManuallyDrop::new(self);
+            let alloc = ptr::read(&(*this.ptr.as_ptr()).alloc);
+            let dst = memory.as_non_null_ptr().cast::<T>().as_ptr();
+            ptr::copy_nonoverlapping(this.as_ptr(), dst, len);
+            alloc.dealloc(base, old_layout);
+            Ok(Vec::from_raw_parts_in(
+                dst,
+                len,
+                memory.len() / elem_size,
+                alloc,
+            ))
+        }
+    }
+}
+
+impl<T: Clone, A: AllocRef> ThinVec<T, A> {
+    /// Clones and appends all elements in a slice to the `ThinVec`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the reallocation fails.
+    pub fn extend_from_slice(&mut self, other: &[T]) {
+        handle_reserve_error(self.try_extend_from_slice(other));
+    }
+
+    /// Same as `extend_from_slice` but returns errors instead of panicking.
+    pub fn try_extend_from_slice(&mut self, other: &[T]) -> Result<(), TryReserveError> {
+        self.try_reserve(other.len())?;
+        for element in other {
+            unsafe { self.push_unchecked(element.clone()) }
+        }
+        Ok(())
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Common trait implementations for ThinVec
+////////////////////////////////////////////////////////////////////////////////
+
+impl<T, A: AllocRef> ops::Deref for ThinVec<T, A> {
+    type Target = [T];
+
+    #[must_use]
+    fn deref(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.as_ptr(), self.len()) }
+    }
+}
+
+impl<T, A: AllocRef> ops::DerefMut for ThinVec<T, A> {
+    #[must_use]
+    fn deref_mut(&mut self) -> &mut [T] {
+        unsafe { slice::from_raw_parts_mut(self.as_mut_ptr(), self.len()) }
+    }
+}
+
+unsafe impl<#[may_dangle] T, A: AllocRef> Drop for ThinVec<T, A> {
+    fn drop(&mut self) {
+        if self.is_empty_header() {
+            return;
+        }
+        unsafe {
+            ptr::drop_in_place(&mut self[..]);
+            let layout = Self::layout(self.capacity()).unwrap();
+            let alloc = ptr::read(&(*self.ptr.as_ptr()).alloc);
+            alloc.dealloc(self.ptr.cast(), layout);
+        }
+    }
+}
+
+impl<T: Clone, A: AllocRef + Clone> Clone for ThinVec<T, A> {
+    fn clone(&self) -> Self {
+        match self.alloc_ref() {
+            Some(alloc) => self.clone_in(alloc.clone()),
+            None => Self {
+                ptr: self.ptr,
+                marker: PhantomData,
+            },
+        }
+    }
+}
+
+#[allow(clippy::use_self)]
+impl<T: Clone, A: AllocRef, B: AllocRef> CloneIn<B> for ThinVec<T, A> {
+    type Cloned = ThinVec<T, B>;
+
+    fn clone_in(&self, a: B) -> Self::Cloned {
+        let mut v = ThinVec::with_capacity_in(self.len(), a);
+        self.iter()
+            .cloned()
+            .for_each(|element| unsafe { v.push_unchecked(element) });
+        v
+    }
+
+    fn try_clone_in(&self, a: B) -> Result<Self::Cloned, TryReserveError> {
+        let mut v = ThinVec::try_with_capacity_in(self.len(), a)?;
+        self.iter()
+            .cloned()
+            .for_each(|element| unsafe { v.push_unchecked(element) });
+        Ok(v)
+    }
+}
+
+impl<T, A: AllocRef + Default> Default for ThinVec<T, A> {
+    /// Creates an empty `ThinVec<T>` pointing to the shared empty header.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: fmt::Debug, A: AllocRef> fmt::Debug for ThinVec<T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<T: Hash, A: AllocRef> Hash for ThinVec<T, A> {
+    #[inline]
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        Hash::hash(&**self, state)
+    }
+}
+
+impl<T, A: AllocRef> AsRef<[T]> for ThinVec<T, A> {
+    fn as_ref(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T, A: AllocRef> AsMut<[T]> for ThinVec<T, A> {
+    fn as_mut(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+macro_rules! __impl_slice_eq1 {
+    ([$($vars:tt)*] $lhs:ty, $rhs:ty, $($constraints:tt)*) => {
+        impl<T, U, $($vars)*> PartialEq<$rhs> for $lhs
+        where
+            T: PartialEq<U>,
+            $($constraints)*
+        {
+            #[inline]
+            fn eq(&self, other: &$rhs) -> bool { self[..] == other[..] }
+        }
+    }
+}
+
+__impl_slice_eq1! { [A, B] ThinVec<T, A>, ThinVec<U, B>, A: AllocRef, B: AllocRef }
+__impl_slice_eq1! { [A, B] ThinVec<T, A>, Vec<U, B>, A: AllocRef, B: AllocRef }
+__impl_slice_eq1! { [A] ThinVec<T, A>, &[U], A: AllocRef }
+__impl_slice_eq1! { [A] ThinVec<T, A>, &mut [U], A: AllocRef }
+__impl_slice_eq1! { [A, const N: usize] ThinVec<T, A>, [U; N], A: AllocRef }
+__impl_slice_eq1! { [A, const N: usize] ThinVec<T, A>, &[U; N], A: AllocRef }
+
+impl<T: Eq, A: AllocRef> Eq for ThinVec<T, A> {}
+
+impl<T: PartialOrd, A: AllocRef> PartialOrd for ThinVec<T, A> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        PartialOrd::partial_cmp(&**self, &**other)
+    }
+}
+
+impl<T: Ord, A: AllocRef> Ord for ThinVec<T, A> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        Ord::cmp(&**self, &**other)
+    }
+}
+
+impl<T, A: AllocRef> From<Vec<T, A>> for ThinVec<T, A> {
+    /// Moves the elements of a `Vec` into a new `ThinVec` using the same allocator.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the allocation fails.
+    fn from(vec: Vec<T, A>) -> Self {
+        let (memory, elements, alloc) = vec.leak_alloc();
+        let len = elements.len();
+        let mut thin = Self::with_capacity_in(len, alloc);
+        unsafe {
+            ptr::copy_nonoverlapping(elements.as_ptr(), thin.as_mut_ptr(), len);
+            thin.set_len(len);
+            if let Some(memory) = memory {
+                let layout = Layout::from_size_align_unchecked(memory.len(), mem::align_of::<T>());
+                thin.alloc_ref()
+                    .unwrap()
+                    .dealloc(memory.as_non_null_ptr(), layout);
+            }
+        }
+        thin
+    }
+}
+
+impl<T, A: AllocRef> From<ThinVec<T, A>> for Vec<T, A> {
+    /// Converts a `ThinVec` into a `Vec`, see [`ThinVec::into_vec`].
+    ///
+    /// [`ThinVec::into_vec`]: ThinVec::into_vec()
+    fn from(vec: ThinVec<T, A>) -> Self {
+        vec.into_vec()
+    }
+}
+
+impl<T> FromIterator<T> for ThinVec<T> {
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut vec = Self::new();
+        vec.extend(iter);
+        vec
+    }
+}
+
+impl<T, A: AllocRef> FromIteratorIn<T, A> for ThinVec<T, A> {
+    fn from_iter_in<I: IntoIterator<Item = T>>(iter: I, a: A) -> Self {
+        let mut vec = Self::new_in(a);
+        vec.extend(iter);
+        vec
+    }
+
+    fn try_from_iter_in<I: IntoIterator<Item = T>>(iter: I, a: A) -> Result<Self, TryReserveError> {
+        let mut vec = Self::try_new_in(a)?;
+        vec.try_extend(iter)?;
+        Ok(vec)
+    }
+}
+
+impl<T, A: AllocRef> Extend<T> for ThinVec<T, A> {
+    #[inline]
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        crate::handle_reserve_error(self.try_extend(iter))
+    }
+}
+
+impl<'a, T: 'a + Copy, A: AllocRef> Extend<&'a T> for ThinVec<T, A> {
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().cloned())
+    }
+}
+
+impl<T, A: AllocRef> TryExtend<T> for ThinVec<T, A> {
+    type Err = TryReserveError;
+
+    fn try_extend<I: IntoIterator<Item = T>>(&mut self, iter: I) -> Result<(), Self::Err> {
+        let mut iterator = iter.into_iter();
+        while let Some(element) = iterator.next() {
+            if self.len() == self.capacity() {
+                let (lower, _) = iterator.size_hint();
+                self.try_reserve(lower.saturating_add(1))?;
+            }
+            unsafe {
+                self.push_unchecked(element);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'a, T: 'a + Copy, A: AllocRef> TryExtend<&'a T> for ThinVec<T, A> {
+    type Err = TryReserveError;
+
+    fn try_extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) -> Result<(), Self::Err> {
+        self.try_extend(iter.into_iter().cloned())
+    }
+}
+
+impl<T, A: AllocRef> IntoIterator for ThinVec<T, A> {
+    type Item = T;
+    type IntoIter = IntoIter<T, A>;
+
+    /// Creates a consuming iterator, that is, one that moves each value out of
+    /// the vector (from start to end).
+    #[inline]
+    fn into_iter(mut self) -> IntoIter<T, A> {
+        let end = self.len();
+        unsafe { self.set_len(0) };
+        IntoIter {
+            vec: self,
+            start: 0,
+            end,
+        }
+    }
+}
+
+impl<'a, T, A: AllocRef> IntoIterator for &'a ThinVec<T, A> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> slice::Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T, A: AllocRef> IntoIterator for &'a mut ThinVec<T, A> {
+    type Item = &'a mut T;
+    type IntoIter = slice::IterMut<'a, T>;
+
+    fn into_iter(self) -> slice::IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+/// An iterator that moves out of a `ThinVec`.
+///
+/// This `struct` is created by the `into_iter` method on [`ThinVec`].
+pub struct IntoIter<T, A: AllocRef = Global> {
+    // the length of `vec` is zero, the remaining elements are `start..end`
+    vec: ThinVec<T, A>,
+    start: usize,
+    end: usize,
+}
+
+impl<T, A: AllocRef> IntoIter<T, A> {
+    /// Returns the remaining items of this iterator as a slice.
+    #[must_use]
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.vec.as_ptr().add(self.start), self.end - self.start) }
+    }
+}
+
+impl<T: fmt::Debug, A: AllocRef> fmt::Debug for IntoIter<T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("IntoIter").field(&self.as_slice()).finish()
+    }
+}
+
+impl<T, A: AllocRef> Iterator for IntoIter<T, A> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        if self.start == self.end {
+            None
+        } else {
+            unsafe {
+                let item = ptr::read(self.vec.as_ptr().add(self.start));
+                self.start += 1;
+                Some(item)
+            }
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let exact = self.end - self.start;
+        (exact, Some(exact))
+    }
+}
+
+impl<T, A: AllocRef> DoubleEndedIterator for IntoIter<T, A> {
+    #[inline]
+    fn next_back(&mut self) -> Option<T> {
+        if self.start == self.end {
+            None
+        } else {
+            unsafe {
+                self.end -= 1;
+                Some(ptr::read(self.vec.as_ptr().add(self.end)))
+            }
+        }
+    }
+}
+
+impl<T, A: AllocRef> ExactSizeIterator for IntoIter<T, A> {}
+
+impl<T, A: AllocRef> FusedIterator for IntoIter<T, A> {}
+
+impl<T, A: AllocRef> Drop for IntoIter<T, A> {
+    fn drop(&mut self) {
+        // destroy the remaining elements
+        for _x in self.by_ref() {}
+    }
+}
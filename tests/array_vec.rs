@@ -0,0 +1,62 @@
+use alloc_wg::{
+    alloc::Global,
+    iter::TryExtend,
+    string::{ArrayString, String},
+    vec::{ArrayVec, Vec},
+};
+use core::fmt::Write;
+
+#[test]
+fn test_try_push_returns_element() {
+    let mut v: ArrayVec<_, 2> = ArrayVec::new();
+    v.try_push(1).unwrap();
+    v.try_push(2).unwrap();
+    assert!(v.is_full());
+    assert_eq!(v.try_push(3).unwrap_err().into_element(), 3);
+    assert_eq!(v.try_insert(0, 4).unwrap_err().into_element(), 4);
+    assert_eq!(v, [1, 2]);
+}
+
+#[test]
+fn test_try_extend_stops_at_capacity() {
+    let mut v: ArrayVec<_, 3> = ArrayVec::new();
+    let mut iter = 1..10;
+    let err = v.try_extend(iter.by_ref()).unwrap_err();
+    assert_eq!(err.into_element(), 4);
+    assert_eq!(v, [1, 2, 3]);
+    assert_eq!(iter.next(), Some(5));
+}
+
+#[test]
+fn test_drain_retain() {
+    let mut v: ArrayVec<_, 8> = (1..=6).collect();
+    let drained: Vec<_> = v.drain(1..3).collect();
+    assert_eq!(drained, [2, 3]);
+    v.retain(|&x| x != 5);
+    assert_eq!(v, [1, 4, 6]);
+}
+
+#[test]
+fn test_into_vec_in() {
+    let v: ArrayVec<_, 4> = (1..=3).collect();
+    let vec: Vec<_, Global> = v.into_vec_in(Global);
+    assert_eq!(vec, [1, 2, 3]);
+    assert_eq!(vec.capacity(), 3);
+}
+
+#[test]
+fn test_array_string() {
+    let mut s = ArrayString::<8>::new();
+    write!(s, "{}", 12345).unwrap();
+    s.insert_str(0, "ab");
+    assert_eq!(s, "ab12345");
+    assert_eq!(s.try_push('é').unwrap_err().into_element(), 'é');
+    assert!(write!(s, "xyz").is_err());
+
+    let drained: String = s.drain(..2).collect();
+    assert_eq!(drained, "ab");
+    assert_eq!(s, "12345");
+
+    let string: String = s.into_string_in(Global);
+    assert_eq!(string, "12345");
+}
@@ -0,0 +1,37 @@
+use alloc_wg::{
+    alloc::Global,
+    borrow::{Cow, ToOwnedIn},
+    string::String,
+    vec::Vec,
+};
+
+#[test]
+fn test_to_owned_in() {
+    let s: String<Global> = "foo".to_owned_in(Global);
+    assert_eq!(s, "foo");
+    let v: Vec<i32, Global> = [1, 2, 3][..].try_to_owned_in(Global).unwrap();
+    assert_eq!(v, [1, 2, 3]);
+}
+
+#[test]
+fn test_cow_to_mut() {
+    let mut cow: Cow<'_, str, Global> = Cow::from("foo");
+    assert!(cow.is_borrowed());
+    cow.try_to_mut_in(Global).unwrap().push_str("bar");
+    assert!(cow.is_owned());
+    assert_eq!(cow, "foobar");
+
+    let owned: String = cow.into_owned();
+    assert_eq!(owned, "foobar");
+}
+
+#[test]
+fn test_from_utf8_lossy_in_borrows_valid_input() {
+    let valid = String::from_utf8_lossy_in(b"hello", Global);
+    assert!(valid.is_borrowed());
+    assert_eq!(valid, "hello");
+
+    let invalid = String::from_utf8_lossy_in(b"he\xFFllo", Global);
+    assert!(invalid.is_owned());
+    assert_eq!(invalid, "he\u{FFFD}llo");
+}
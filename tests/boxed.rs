@@ -1,3 +1,12 @@
+#![feature(
+    allocator_api,
+    arbitrary_self_types,
+    maybe_uninit_extra,
+    maybe_uninit_ref
+)]
+
+mod common;
+
 // use alloc_wg::boxed::Box;
 // use core::{mem::MaybeUninit, ptr::NonNull};
 //
@@ -16,3 +25,724 @@
 //         NonNull::<MaybeUninit<String>>::dangling().as_ptr(),
 //     );
 // }
+
+mod try_push {
+    use crate::common::Failing;
+    use alloc_wg::{alloc::Global, boxed::Box, vec::Vec};
+
+    struct NotClone(u32);
+
+    #[test]
+    fn test_hands_back_value() {
+        let err = Box::try_new_in(NotClone(3), Failing).unwrap_err();
+        let mut v: Vec<_, _> = Vec::new_in(Global);
+        v.push(err.into_element());
+        assert_eq!(v[0].0, 3);
+    }
+}
+
+mod collect_in {
+    use alloc_wg::{
+        alloc::Global,
+        boxed::Box,
+        clone::CloneIn,
+        iter::{IteratorExt, TryExtend},
+        string::String,
+        vec,
+    };
+
+    #[test]
+    fn test_collect() {
+        let b: Box<[u32]> = (1..4).try_collect_in(Global).unwrap();
+        assert_eq!(&*b, &[1, 2, 3]);
+
+        let s: Box<str> = ['h', 'i'].iter().collect_in(Global);
+        assert_eq!(&*s, "hi");
+    }
+
+    #[test]
+    fn test_slice_try_extend() {
+        let mut b: Box<[String]> = Box::from(vec![String::from("a")]);
+        b.try_extend(vec![String::from("b"), String::from("c")])
+            .unwrap();
+        assert_eq!(b.len(), 3);
+        assert_eq!(b[2], "c");
+
+        b.try_extend(std::iter::empty()).unwrap();
+        assert_eq!(b.len(), 3);
+    }
+
+    #[test]
+    fn test_clone_in() {
+        let b: Box<[u8]> = Box::from(&[1, 2][..]);
+        assert_eq!(b.try_clone_in(Global).unwrap(), b);
+
+        let s: Box<str> = Box::from("hello");
+        assert_eq!(&*s.clone_in(Global), "hello");
+    }
+}
+
+mod transfer_in {
+    use crate::common::Counting;
+    use alloc_wg::{alloc::Global, boxed::Box, string::String};
+
+    #[test]
+    fn test_transfer_in() {
+        let arena = Counting::default();
+        let s = String::from_str_in("hello", &arena);
+        let b: Box<str, _> = Box::transfer_in(s.into_boxed_str(), Global).unwrap();
+        assert_eq!(&*b, "hello");
+        assert_eq!(arena.live.get(), 0);
+
+        let b = Box::transfer_in(Box::new([1_u64, 2]), &arena).unwrap();
+        assert_eq!(*b, [1, 2]);
+        assert_eq!(arena.live.get(), 1);
+    }
+
+    #[test]
+    fn test_error() {
+        let arena = Counting::default();
+        arena.fail.set(true);
+        let b = Box::new(1);
+        assert_eq!(*Box::transfer_in(b, &arena).unwrap_err().into_element(), 1);
+    }
+}
+
+mod dyn_fn {
+    use crate::common::{Bump, Counting};
+    use alloc_wg::{boxed::Box, string::String, vec::Vec};
+    use core::cell::Cell;
+    use std::alloc::{GlobalAlloc, Layout, System};
+
+    thread_local! {
+        static GLOBAL_FORBIDDEN: Cell<bool> = Cell::new(false);
+    }
+
+    /// Forwards to `System`, but panics if the current thread allocates while it is forbidden.
+    struct Guarded;
+
+    unsafe impl GlobalAlloc for Guarded {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            // re-allow before panicking, as the panic allocates itself
+            if GLOBAL_FORBIDDEN.with(|forbidden| forbidden.replace(false)) {
+                panic!("allocated {:?} from the global allocator", layout);
+            }
+            System.alloc(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout)
+        }
+    }
+
+    #[global_allocator]
+    static GLOBAL: Guarded = Guarded;
+
+    fn without_global<R>(f: impl FnOnce() -> R) -> R {
+        GLOBAL_FORBIDDEN.with(|forbidden| forbidden.set(true));
+        let result = f();
+        GLOBAL_FORBIDDEN.with(|forbidden| forbidden.set(false));
+        result
+    }
+
+    #[test]
+    fn call_once_global() {
+        let s = String::from("hello");
+        let f: Box<dyn FnOnce(&str) -> String + Send> = Box::new(move |x: &str| s + x);
+        assert_eq!(f(" world"), "hello world");
+    }
+
+    #[test]
+    fn call_once_in() {
+        let arena = Counting::default();
+        let s = String::from("hello");
+        let f: Box<dyn FnOnce() -> String, _> = Box::new_in(move || s, &arena);
+        assert_eq!(arena.live.get(), 1);
+        assert_eq!(f(), "hello");
+        assert_eq!(arena.live.get(), 0);
+    }
+
+    #[test]
+    fn call_once_in_without_global() {
+        let bump = Bump::new();
+        let values = [1_u64, 2, 3, 4];
+        let f: Box<dyn FnOnce() -> u64, _> = Box::new_in(move || values.iter().sum(), &bump);
+        assert_eq!(without_global(f), 10);
+    }
+
+    #[test]
+    fn call_once_zero_sized() {
+        let arena = Counting::default();
+        let f: Box<dyn FnOnce() -> i32, _> = Box::new_in(|| 42, &arena);
+        assert_eq!(f(), 42);
+        assert_eq!(arena.live.get(), 0);
+    }
+
+    #[test]
+    fn call_mut_and_fn() {
+        let arena = Counting::default();
+        let mut count = 0;
+        let mut f: Box<dyn FnMut() -> i32, _> = Box::new_in(
+            move || {
+                count += 1;
+                count
+            },
+            &arena,
+        );
+        assert_eq!(f(), 1);
+        assert_eq!(f(), 2);
+
+        let g: Box<dyn Fn(i32) -> i32, _> = Box::new_in(|x| x * 2, &arena);
+        assert_eq!(g(21), 42);
+        drop((f, g));
+        assert_eq!(arena.live.get(), 0);
+    }
+
+    #[test]
+    fn callback_queue() {
+        let arena = Counting::default();
+        let log = Cell::new(0);
+        let mut queue: Vec<Box<dyn FnOnce() + '_, _>, _> = Vec::new_in(&arena);
+        for i in 1..=4 {
+            let log = &log;
+            queue.push(Box::new_in(move || log.set(log.get() * 10 + i), &arena));
+        }
+        for callback in queue.drain(..) {
+            callback();
+        }
+        assert_eq!(log.get(), 1234);
+        drop(queue);
+        assert_eq!(arena.live.get(), 0);
+    }
+}
+
+mod dyn_dispatch {
+    use crate::common::Bump;
+    use alloc_wg::{
+        boxed::{Box, BoxedDyn},
+        string::String,
+        vec::Vec,
+    };
+    use core::cell::Cell;
+
+    trait Shape {
+        fn area(&self) -> u32;
+        fn into_name(self: BoxedDyn<'_, Self>) -> String;
+    }
+
+    struct Square(u32, String);
+
+    impl Shape for Square {
+        fn area(&self) -> u32 {
+            self.0 * self.0
+        }
+
+        fn into_name(self: BoxedDyn<'_, Self>) -> String {
+            BoxedDyn::into_inner(self).1
+        }
+    }
+
+    struct Dropped<'a>(&'a Cell<bool>);
+
+    impl Drop for Dropped<'_> {
+        fn drop(&mut self) {
+            self.0.set(true);
+        }
+    }
+
+    impl Shape for Dropped<'_> {
+        fn area(&self) -> u32 {
+            0
+        }
+
+        fn into_name(self: BoxedDyn<'_, Self>) -> String {
+            String::from("dropped")
+        }
+    }
+
+    #[test]
+    fn dispatch_by_value() {
+        let bump = Bump::new();
+        let shape: Box<dyn Shape, &Bump> = Box::new_in(Square(3, String::from("square")), &bump);
+        assert_eq!(shape.area(), 9);
+        assert_eq!(bump.live.get(), 1);
+
+        assert_eq!(Box::dispatch(shape, |s| s.into_name()), "square");
+        assert_eq!(bump.live.get(), 0);
+    }
+
+    #[test]
+    fn dispatch_drops_value() {
+        let bump = Bump::new();
+        let dropped = Cell::new(false);
+        let shape: Box<dyn Shape + '_, &Bump> = Box::new_in(Dropped(&dropped), &bump);
+
+        assert_eq!(Box::dispatch(shape, |s| s.into_name()), "dropped");
+        assert!(dropped.get());
+        assert_eq!(bump.live.get(), 0);
+    }
+
+    #[test]
+    fn dispatch_queue() {
+        let bump = Bump::new();
+        let mut shapes: Vec<Box<dyn Shape, &Bump>, _> = Vec::with_capacity_in(3, &bump);
+        for (side, name) in [(1, "a"), (2, "b"), (3, "c")].iter() {
+            shapes.push(Box::new_in(Square(*side, String::from(*name)), &bump));
+        }
+        let names: Vec<String> = shapes
+            .into_iter()
+            .map(|s| Box::dispatch(s, |s| s.into_name()))
+            .collect();
+        assert_eq!(names, ["a", "b", "c"]);
+        assert_eq!(bump.live.get(), 0);
+    }
+}
+
+mod zeroed {
+    use crate::common::Counting;
+    use alloc_wg::{alloc::Layout, boxed::Box};
+
+    #[test]
+    fn new_zeroed() {
+        let alloc = Counting::default();
+        let b = Box::<[u64; 4], _>::new_zeroed_in(&alloc);
+        assert_eq!(unsafe { *b.assume_init() }, [0; 4]);
+
+        let b = Box::<u32, _>::try_new_zeroed_in(&alloc).unwrap();
+        assert_eq!(unsafe { *b.assume_init() }, 0);
+        assert_eq!(alloc.zeroed.get(), 2);
+
+        alloc.fail.set(true);
+        let err = Box::<u32, _>::try_new_zeroed_in(&alloc).unwrap_err();
+        assert_eq!(err.layout(), Some(Layout::new::<u32>()));
+        alloc.fail.set(false);
+
+        assert_eq!(unsafe { *Box::<u16>::new_zeroed().assume_init() }, 0);
+    }
+
+    #[test]
+    fn new_zeroed_slice() {
+        let alloc = Counting::default();
+        let b = Box::<[u32], _>::new_zeroed_slice_in(100, &alloc);
+        assert_eq!(unsafe { b.assume_init() }.iter().sum::<u32>(), 0);
+
+        let b = Box::<[u32], _>::try_new_zeroed_slice_in(100, &alloc).unwrap();
+        assert_eq!(b.len(), 100);
+        assert_eq!(alloc.zeroed.get(), 2);
+
+        assert!(Box::<[u64], _>::try_new_zeroed_slice_in(usize::MAX, &alloc).is_err());
+        assert_eq!(
+            unsafe { &*Box::<[u8]>::new_zeroed_slice(3).assume_init() },
+            [0, 0, 0]
+        );
+    }
+}
+
+mod conversions {
+    use alloc_wg::{
+        alloc::{AllocError, AllocRef, Global, Layout},
+        boxed::Box,
+        iter::FromIteratorIn,
+        vec::Vec,
+    };
+    use core::{convert::TryFrom, fmt, ptr::NonNull};
+    use std::error::Error;
+
+    /// A stateless allocator, which can be created by `Default`.
+    #[derive(Default, Debug, Copy, Clone, PartialEq)]
+    struct Arena;
+
+    unsafe impl AllocRef for Arena {
+        fn alloc(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+            Global.alloc(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: NonNull<u8>, layout: Layout) {
+            Global.dealloc(ptr, layout)
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct MyError(u32);
+
+    impl fmt::Display for MyError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "error {}", self.0)
+        }
+    }
+
+    impl Error for MyError {}
+
+    #[test]
+    fn from_iterator() {
+        let b: Box<[u32], Arena> = (1..=3).collect();
+        assert_eq!(&*b, [1, 2, 3]);
+
+        let s: Box<str, Arena> = vec!['a', 'b'].into_iter().collect();
+        assert_eq!(&*s, "ab");
+
+        let s: Box<str> = Box::try_from_iter_in(vec!["ab", "cd"], Global).unwrap();
+        assert_eq!(&*s, "abcd");
+    }
+
+    #[test]
+    fn from_vec_and_array() {
+        let v = Vec::from_iter_in(0..4, Arena);
+        let b: Box<[i32], Arena> = Box::from(v);
+        assert_eq!(&*b, [0, 1, 2, 3]);
+
+        let b: Box<[i32], Arena> = Box::from([5, 6]);
+        assert_eq!(&*b, [5, 6]);
+    }
+
+    #[test]
+    fn try_from_boxed_slice() {
+        let b: Box<[u8], Arena> = Box::from([1, 2, 3]);
+        let b = <Box<[u8; 2], Arena>>::try_from(b).unwrap_err();
+        let a = <Box<[u8; 3], Arena>>::try_from(b).unwrap();
+        assert_eq!(*a, [1, 2, 3]);
+    }
+
+    #[test]
+    fn from_error() {
+        let err: Box<dyn Error, Arena> = Box::from(MyError(1));
+        assert_eq!(err.to_string(), "error 1");
+
+        let err: Box<dyn Error + Send + Sync, Arena> = MyError(2).into();
+        assert_eq!(err.to_string(), "error 2");
+
+        fn inner() -> Result<(), MyError> {
+            Err(MyError(3))
+        }
+        fn outer() -> Result<(), Box<dyn Error + Send + Sync, Arena>> {
+            inner()?;
+            Ok(())
+        }
+        assert_eq!(outer().unwrap_err().to_string(), "error 3");
+    }
+
+    #[test]
+    fn downcast_error() {
+        let err: Box<dyn Error, Arena> = Box::from(MyError(4));
+        let err = err.downcast::<fmt::Error>().unwrap_err();
+        assert_eq!(*err.downcast::<MyError>().unwrap(), MyError(4));
+
+        let err: Box<dyn Error + Send, Arena> = Box::new_in(MyError(5), Arena);
+        assert_eq!(*err.downcast::<MyError>().unwrap(), MyError(5));
+
+        let err: Box<dyn Error + Send + Sync, Arena> = Box::from(MyError(6));
+        let err: Box<MyError, Arena> = err.downcast().unwrap();
+        assert_eq!(err.0, 6);
+    }
+}
+
+mod try_clone {
+    use crate::common::Counting;
+    use alloc_wg::{
+        boxed::Box,
+        clone::{CloneIn, TryClone},
+        iter::FromIteratorIn,
+        string::String,
+        vec,
+    };
+    use core::cell::Cell;
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    /// Panics when the `n`th clone is made and counts the drops.
+    struct PanicOnClone<'a> {
+        clones: &'a Cell<usize>,
+        drops: &'a Cell<usize>,
+        panic_at: &'a Cell<usize>,
+    }
+
+    impl Clone for PanicOnClone<'_> {
+        fn clone(&self) -> Self {
+            self.clones.set(self.clones.get() + 1);
+            if self.clones.get() == self.panic_at.get() {
+                panic!("clone failed");
+            }
+            Self {
+                clones: self.clones,
+                drops: self.drops,
+                panic_at: self.panic_at,
+            }
+        }
+    }
+
+    impl Drop for PanicOnClone<'_> {
+        fn drop(&mut self) {
+            self.drops.set(self.drops.get() + 1);
+        }
+    }
+
+    #[test]
+    fn clone_str() {
+        let alloc = Counting::default();
+        let s: Box<str, _> = String::from_str_in("hello", &alloc).into_boxed_str();
+        let c = s.clone();
+        assert_eq!(c, s);
+        assert_ne!(c.as_ptr(), s.as_ptr());
+        assert_eq!(alloc.live.get(), 2);
+    }
+
+    #[test]
+    fn try_clone_ok() {
+        let b = Box::new(5);
+        assert_eq!(*b.try_clone().unwrap(), 5);
+
+        let b: Box<[u32]> = Box::from(&[1, 2][..]);
+        assert_eq!(&*b.try_clone().unwrap(), [1, 2]);
+
+        let s: Box<str> = Box::from("abc");
+        assert_eq!(&*s.try_clone().unwrap(), "abc");
+    }
+
+    #[test]
+    fn try_clone_err() {
+        let alloc = Counting::default();
+        let b = Box::new_in(1, &alloc);
+        let s: Box<[u8], _> = Box::try_from_iter_in(vec![1, 2, 3], &alloc).unwrap();
+        let boxed_str = String::from_str_in("abc", &alloc).into_boxed_str();
+
+        alloc.fail.set(true);
+        assert!(b.try_clone().is_err());
+        assert!(s.try_clone().is_err());
+        assert!(boxed_str.try_clone().is_err());
+    }
+
+    #[test]
+    fn panic_safety() {
+        let clones = Cell::new(0);
+        let drops = Cell::new(0);
+        let panic_at = Cell::new(usize::MAX);
+        let alloc = Counting::default();
+        let item = PanicOnClone {
+            clones: &clones,
+            drops: &drops,
+            panic_at: &panic_at,
+        };
+        let v = vec![item.clone(), item.clone(), item.clone(), item];
+        let b = Box::<[_], _>::from_iter_in(v, &alloc);
+        clones.set(0);
+        panic_at.set(3);
+        assert_eq!(alloc.live.get(), 1);
+
+        let result = catch_unwind(AssertUnwindSafe(|| b.try_clone_in(&alloc)));
+        assert!(result.is_err());
+        assert_eq!(drops.get(), 2);
+        assert_eq!(alloc.live.get(), 1);
+    }
+}
+
+mod map {
+    use crate::common::Counting;
+    use alloc_wg::{boxed::Box, string::String};
+    use core::any::Any;
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    #[test]
+    fn map_same_layout() {
+        let alloc = Counting::default();
+        let b = Box::new_in(-1_i64, &alloc);
+        let ptr = &*b as *const i64 as usize;
+
+        let b = Box::map_in_place(b, |x| x as u64);
+        assert_eq!(*b, u64::MAX);
+        assert_eq!(&*b as *const u64 as usize, ptr);
+        assert_eq!(alloc.resized.get(), 0);
+        drop(b);
+        assert_eq!(alloc.live.get(), 0);
+    }
+
+    #[test]
+    fn map_resize() {
+        let alloc = Counting::default();
+        let b = Box::new_in(3_u32, &alloc);
+
+        let b = Box::map_in_place(b, |x| [x; 4]);
+        assert_eq!(*b, [3; 4]);
+        assert_eq!(alloc.resized.get(), 1);
+
+        let b = Box::map_in_place(b, |[a, b, ..]| (a, b));
+        assert_eq!(*b, (3, 3));
+        assert_eq!(alloc.resized.get(), 2);
+        assert_eq!(alloc.live.get(), 1);
+    }
+
+    #[test]
+    fn map_realloc() {
+        let alloc = Counting::default();
+        let b = Box::new_in(7_u8, &alloc);
+
+        let b = Box::map_in_place(b, |x| [u64::from(x); 2]);
+        assert_eq!(*b, [7, 7]);
+        assert_eq!(alloc.resized.get(), 0);
+        assert_eq!(alloc.live.get(), 1);
+    }
+
+    #[test]
+    fn map_panic_frees() {
+        let alloc = Counting::default();
+        let b = Box::new_in(String::from("dropped"), &alloc);
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            Box::map_in_place(b, |s| -> u32 { panic!("{}", s) })
+        }));
+        assert!(result.is_err());
+        assert_eq!(alloc.live.get(), 0);
+    }
+
+    #[test]
+    fn into_inner() {
+        let alloc = Counting::default();
+        let b = Box::new_in(String::from("hello"), &alloc);
+        let (s, a) = Box::into_inner_alloc(b);
+        assert_eq!(s, "hello");
+        assert!(core::ptr::eq(a, &alloc));
+        assert_eq!(alloc.live.get(), 0);
+
+        assert_eq!(Box::into_inner(Box::new(5)), 5);
+    }
+
+    #[test]
+    fn unsize_and_downcast() {
+        let alloc = Counting::default();
+        let b: Box<dyn Any + Send + Sync, &Counting> = Box::new_in(42_u16, &alloc);
+        let b = b.downcast::<u32>().unwrap_err();
+        let b: Box<u16, _> = b.downcast().unwrap();
+        assert_eq!(*b, 42);
+
+        let b: Box<dyn Any, _> = b;
+        assert_eq!(*b.downcast::<u16>().unwrap(), 42);
+        assert_eq!(alloc.live.get(), 0);
+    }
+}
+
+mod pinned_init {
+    use crate::common::Counting;
+    use alloc_wg::boxed::Box;
+    use core::{cell::Cell, marker::PhantomPinned, mem::MaybeUninit, pin::Pin, ptr};
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    /// A node of an intrusive, circular list, which points to itself when it is alone.
+    struct Node {
+        value: u32,
+        next: *const Node,
+        _pin: PhantomPinned,
+    }
+
+    unsafe fn init_node(slot: Pin<&mut MaybeUninit<Node>>, value: u32) {
+        let this = slot.get_unchecked_mut().as_mut_ptr();
+        ptr::write(this, Node {
+            value,
+            next: this,
+            _pin: PhantomPinned,
+        });
+    }
+
+    #[test]
+    fn new_pinned_with() {
+        let alloc = Counting::default();
+        let node = unsafe { Box::new_pinned_with(&alloc, |slot| init_node(slot, 1)) };
+        assert_eq!(node.next, &*node as *const Node);
+        assert_eq!(unsafe { (*node.next).value }, 1);
+        assert_eq!(alloc.live.get(), 1);
+        drop(node);
+        assert_eq!(alloc.live.get(), 0);
+    }
+
+    #[test]
+    fn init_not_called_on_failure() {
+        let alloc = Counting::default();
+        alloc.fail.set(true);
+        let called = Cell::new(false);
+        let result = unsafe {
+            Box::try_new_pinned_with(&alloc, |slot| {
+                called.set(true);
+                init_node(slot, 3)
+            })
+        };
+        assert!(result.is_err());
+        assert!(!called.get());
+    }
+
+    #[test]
+    fn panic_in_init_frees() {
+        let alloc = Counting::default();
+        let result = catch_unwind(AssertUnwindSafe(|| unsafe {
+            Box::<Node, _>::new_pinned_with(&alloc, |_| panic!("init failed"))
+        }));
+        assert!(result.is_err());
+        assert_eq!(alloc.live.get(), 0);
+    }
+}
+
+mod in_place_init {
+    use crate::common::Counting;
+    use alloc_wg::{alloc::Global, boxed::Box};
+    use core::{cell::Cell, mem::MaybeUninit, ptr};
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    const LARGE: usize = 16 << 20;
+
+    /// Initializes a large array in place, which would overflow the stack in debug builds if it
+    /// was built there first.
+    fn fill_large(slot: &mut MaybeUninit<[u8; LARGE]>) -> &mut [u8; LARGE] {
+        unsafe {
+            ptr::write_bytes(slot.as_mut_ptr(), 1, 1);
+            slot.assume_init_mut()
+        }
+    }
+
+    #[test]
+    fn new_with() {
+        let alloc = Counting::default();
+        let b = Box::new_with_in(|| [3_u32; 64], &alloc);
+        assert_eq!(b[63], 3);
+        let b = Box::try_new_with_in(|| 5, &alloc).unwrap();
+        assert_eq!(*b, 5);
+        assert_eq!(alloc.live.get(), 2);
+    }
+
+    #[test]
+    fn init_large() {
+        let b = Box::<[u8; LARGE], _>::init_in(Global, fill_large);
+        assert_eq!(b[LARGE - 1], 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "has to return the initialized slot")]
+    fn init_wrong_slot() {
+        let other = Box::leak(Box::new(1_u32));
+        let _ = Box::<u32, _>::init_in(Global, |_slot: &mut MaybeUninit<u32>| other);
+    }
+
+    #[test]
+    fn not_called_on_failure() {
+        let alloc = Counting::default();
+        alloc.fail.set(true);
+        let called = Cell::new(false);
+        assert!(Box::try_new_with_in(|| called.set(true), &alloc).is_err());
+        assert!(
+            Box::<u32, _>::try_init_in(&alloc, |slot| {
+                called.set(true);
+                unsafe { slot.assume_init_mut() }
+            })
+            .is_err()
+        );
+        assert!(!called.get());
+    }
+
+    #[test]
+    fn panic_frees() {
+        let alloc = Counting::default();
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            Box::<u32, _>::new_with_in(|| panic!("init failed"), &alloc)
+        }));
+        assert!(result.is_err());
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            Box::<u32, _>::init_in(&alloc, |_| panic!("init failed"))
+        }));
+        assert!(result.is_err());
+        assert_eq!(alloc.live.get(), 0);
+    }
+}
@@ -0,0 +1,160 @@
+//! Allocators shared by the integration tests.
+
+// Every test crate includes this module but only uses some of it.
+#![allow(dead_code)]
+
+use alloc_wg::alloc::{AllocError, AllocRef, Global, Layout, ReallocInPlace};
+use core::{
+    cell::{Cell, UnsafeCell},
+    ptr::{self, NonNull},
+};
+
+/// Fails every allocation.
+#[derive(Copy, Clone, Default)]
+pub struct Failing;
+
+unsafe impl AllocRef for Failing {
+    fn alloc(&self, _layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        Err(AllocError)
+    }
+
+    unsafe fn dealloc(&self, _ptr: NonNull<u8>, _layout: Layout) {
+        unreachable!("nothing was allocated")
+    }
+}
+
+/// Forwards to `Global`, counts the live allocations, zeroed allocations and resizes, and fails
+/// once `fail` is set.
+#[derive(Default)]
+pub struct Counting {
+    pub live: Cell<usize>,
+    pub zeroed: Cell<usize>,
+    pub resized: Cell<usize>,
+    pub fail: Cell<bool>,
+}
+
+unsafe impl AllocRef for Counting {
+    fn alloc(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        if self.fail.get() {
+            return Err(AllocError);
+        }
+        let ptr = Global.alloc(layout)?;
+        self.live.set(self.live.get() + 1);
+        Ok(ptr)
+    }
+
+    fn alloc_zeroed(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        if self.fail.get() {
+            return Err(AllocError);
+        }
+        let ptr = Global.alloc_zeroed(layout)?;
+        self.live.set(self.live.get() + 1);
+        self.zeroed.set(self.zeroed.get() + 1);
+        Ok(ptr)
+    }
+
+    unsafe fn dealloc(&self, ptr: NonNull<u8>, layout: Layout) {
+        self.live.set(self.live.get() - 1);
+        Global.dealloc(ptr, layout)
+    }
+
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        if self.fail.get() {
+            return Err(AllocError);
+        }
+        self.resized.set(self.resized.get() + 1);
+        Global.grow(ptr, old_layout, new_layout)
+    }
+
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        if self.fail.get() {
+            return Err(AllocError);
+        }
+        self.resized.set(self.resized.get() + 1);
+        Global.shrink(ptr, old_layout, new_layout)
+    }
+}
+
+/// A bump allocator on an inline buffer, which can only resize its most recent allocation.
+///
+/// It never touches the global allocator. Freed memory is not reused, but `live` counts the
+/// allocations which have not been freed yet.
+pub struct Bump {
+    memory: UnsafeCell<[u64; 64]>,
+    top: Cell<usize>,
+    pub live: Cell<usize>,
+}
+
+impl Bump {
+    pub fn new() -> Self {
+        Self {
+            memory: UnsafeCell::new([0; 64]),
+            top: Cell::new(0),
+            live: Cell::new(0),
+        }
+    }
+
+    fn start(&self) -> usize {
+        self.memory.get() as usize
+    }
+
+    fn block(&self, ptr: NonNull<u8>, size: usize) -> Result<NonNull<[u8]>, AllocError> {
+        let end = ptr.as_ptr() as usize + size;
+        if end > self.start() + 64 * 8 {
+            return Err(AllocError);
+        }
+        self.top.set(end - self.start());
+        Ok(NonNull::new(ptr::slice_from_raw_parts_mut(ptr.as_ptr(), size)).unwrap())
+    }
+}
+
+unsafe impl AllocRef for Bump {
+    fn alloc(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        let addr = (self.start() + self.top.get() + layout.align() - 1) & !(layout.align() - 1);
+        let block = self.block(NonNull::new(addr as *mut u8).unwrap(), layout.size())?;
+        self.live.set(self.live.get() + 1);
+        Ok(block)
+    }
+
+    unsafe fn dealloc(&self, _ptr: NonNull<u8>, _layout: Layout) {
+        self.live.set(self.live.get() - 1);
+    }
+}
+
+unsafe impl ReallocInPlace for Bump {
+    unsafe fn grow_in_place(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        if ptr.as_ptr() as usize + old_layout.size() != self.start() + self.top.get() {
+            return Err(AllocError);
+        }
+        self.block(ptr, new_layout.size())
+    }
+
+    unsafe fn shrink_in_place(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        if ptr.as_ptr() as usize + old_layout.size() == self.start() + self.top.get() {
+            return self.block(ptr, new_layout.size());
+        }
+        // only the most recent allocation can give its tail back
+        let block = ptr::slice_from_raw_parts_mut(ptr.as_ptr(), new_layout.size());
+        Ok(NonNull::new(block).unwrap())
+    }
+}
@@ -0,0 +1,59 @@
+use alloc_wg::{
+    alloc::Global,
+    ffi::{CString, CStringError, OsString},
+    path::PathBuf,
+};
+use std::path::Path;
+
+#[test]
+fn test_cstring_nul() {
+    let s = CString::new_in(b"foo", Global).unwrap();
+    assert_eq!(s.as_bytes(), b"foo");
+    assert_eq!(s.as_bytes_with_nul(), b"foo\0");
+    assert_eq!(s.as_c_str().to_bytes(), b"foo");
+
+    let err = CString::new_in(b"fo\0o", Global).unwrap_err();
+    assert_eq!(err.nul_position(), 2);
+    assert_eq!(err.into_vec(), b"fo\0o");
+
+    match CString::try_new_in(b"\0", Global) {
+        Err(CStringError::Nul(err)) => assert_eq!(err.nul_position(), 0),
+        _ => panic!("expected an interior NUL error"),
+    }
+}
+
+#[test]
+fn test_cstring_raw_round_trip() {
+    let s = CString::new("hello").unwrap();
+    let (ptr, alloc) = s.into_raw_alloc();
+    let s = unsafe { CString::from_raw_in(ptr, alloc) };
+    assert_eq!(s.into_bytes_with_nul(), b"hello\0");
+}
+
+#[test]
+fn test_os_string_push() {
+    let mut s = OsString::new_in(Global);
+    s.try_push("foo").unwrap();
+    s.push("bar");
+    assert_eq!(s, *"foobar");
+    assert_eq!(s.into_string().unwrap(), "foobar");
+}
+
+#[test]
+fn test_path_buf() {
+    let mut path = PathBuf::try_from_path_in("/usr", Global).unwrap();
+    path.try_push("lib").unwrap();
+    assert_eq!(path, *Path::new("/usr/lib"));
+
+    let joined = path.try_join("libc.so").unwrap();
+    assert_eq!(joined, *Path::new("/usr/lib/libc.so"));
+
+    path.try_set_file_name("bin").unwrap();
+    assert_eq!(path, *Path::new("/usr/bin"));
+    assert!(path.pop());
+    path.try_push("/etc").unwrap();
+    assert_eq!(path, *Path::new("/etc"));
+
+    assert!(path.try_set_extension("d").unwrap());
+    assert_eq!(path.as_path(), Path::new("/etc.d"));
+}
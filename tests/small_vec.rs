@@ -0,0 +1,107 @@
+use alloc_wg::vec::{SmallVec, Vec};
+
+struct DropCounter<'a> {
+    count: &'a mut u32,
+}
+
+impl Drop for DropCounter<'_> {
+    fn drop(&mut self) {
+        *self.count += 1;
+    }
+}
+
+#[test]
+fn test_push_spills() {
+    let mut v: SmallVec<u32, 2> = SmallVec::new();
+    v.push(1);
+    v.push(2);
+    assert!(!v.spilled());
+    assert_eq!(v.capacity(), 2);
+
+    v.try_push(3).unwrap();
+    assert!(v.spilled());
+    assert_eq!(v, [1, 2, 3]);
+
+    v.truncate(1);
+    v.shrink_to_fit();
+    assert!(!v.spilled());
+    assert_eq!(v, [1]);
+}
+
+#[test]
+fn test_drop_inline_and_spilled() {
+    let mut count = 0;
+    {
+        let mut v: SmallVec<_, 4> = SmallVec::new();
+        v.push(DropCounter { count: &mut count });
+    }
+    assert_eq!(count, 1);
+
+    let mut counts = [0; 3];
+    {
+        let mut v: SmallVec<_, 1> = SmallVec::new();
+        for count in counts.iter_mut() {
+            v.push(DropCounter { count });
+        }
+        assert!(v.spilled());
+    }
+    assert_eq!(counts, [1, 1, 1]);
+}
+
+#[test]
+fn test_drain_splice() {
+    let mut v: SmallVec<_, 4> = (1..=4).collect();
+    let drained: Vec<_> = v.drain(1..3).collect();
+    assert_eq!(drained, [2, 3]);
+    assert_eq!(v, [1, 4]);
+
+    // the tail has to be moved past the inline capacity
+    let removed: Vec<_> = v.splice(..1, 5..9).collect();
+    assert_eq!(removed, [1]);
+    assert_eq!(v, [5, 6, 7, 8, 4]);
+    assert!(v.spilled());
+}
+
+#[test]
+fn test_drain_filter_retain_dedup() {
+    let mut v: SmallVec<_, 8> = [1, 1, 2, 3, 3, 4, 5, 6].iter().copied().collect();
+    v.dedup();
+    assert_eq!(v, [1, 2, 3, 4, 5, 6]);
+
+    let evens: Vec<_> = v.drain_filter(|x| *x % 2 == 0).collect();
+    assert_eq!(evens, [2, 4, 6]);
+    assert_eq!(v, [1, 3, 5]);
+
+    v.retain(|&x| x != 3);
+    assert_eq!(v, [1, 5]);
+}
+
+#[test]
+fn test_vec_conversions() {
+    let vec: Vec<u32> = (0..16).collect();
+    let ptr = vec.as_ptr();
+    let small: SmallVec<_, 4> = SmallVec::from(vec);
+    assert!(small.spilled());
+    assert_eq!(small.as_ptr(), ptr);
+
+    let vec = small.into_vec();
+    assert_eq!(vec.as_ptr(), ptr);
+
+    let small: SmallVec<_, 4> = (0..3).collect();
+    let vec: Vec<u32> = small.into();
+    assert_eq!(vec, [0, 1, 2]);
+
+    let small: SmallVec<_, 4> = (0..3).collect();
+    let boxed = small.into_boxed_slice();
+    assert_eq!(&*boxed, [0, 1, 2]);
+}
+
+#[test]
+fn test_zero_sized() {
+    let mut v: SmallVec<(), 0> = SmallVec::new();
+    for _ in 0..10 {
+        v.push(());
+    }
+    assert_eq!(v.len(), 10);
+    assert_eq!(v.into_iter().count(), 10);
+}
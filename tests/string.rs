@@ -1,3 +1,7 @@
+#![feature(allocator_api)]
+
+mod common;
+
 // #![feature(try_reserve)]
 // // use alloc_wg::{borrow::Cow, collections::CollectionAllocErr::*, string::String, vec, vec::Vec};
 // use core::{isize, mem::size_of, usize};
@@ -715,3 +719,178 @@
 //         }
 //     }
 // }
+
+mod macros {
+    use crate::common::Failing;
+    use alloc_wg::{
+        alloc::Global,
+        collections::TryReserveError,
+        string,
+        string::String,
+        try_format,
+    };
+
+    #[test]
+    fn test_string() {
+        let s: String = string![];
+        assert!(s.is_empty());
+        assert_eq!(string!["foo"], "foo");
+        assert_eq!(string![in Global; "bar"], "bar");
+        assert_eq!(string![try "baz"].unwrap(), "baz");
+        assert_eq!(string![try in Global; "qux"].unwrap(), "qux");
+    }
+
+    #[test]
+    fn test_try_format() {
+        let s = try_format!("{}-{:?}", 1, "two").unwrap();
+        assert_eq!(s, "1-\"two\"");
+        let s = try_format!(in Global, "{:>4}", 5).unwrap();
+        assert_eq!(s, "   5");
+    }
+
+    #[test]
+    fn test_format() {
+        let s: String = alloc_wg::format!("plain");
+        assert_eq!(s, "plain");
+        let s = alloc_wg::format!(in Global, "{}{}", 'a', 1);
+        assert_eq!(s, "a1");
+
+        let mut s = string!["x = "];
+        s.try_write_fmt(format_args!("{:02}", 7)).unwrap();
+        assert_eq!(s, "x = 07");
+    }
+
+    #[test]
+    #[should_panic(expected = "a formatting trait implementation returned an error")]
+    fn test_try_format_failing_display() {
+        struct Failing;
+
+        impl core::fmt::Display for Failing {
+            fn fmt(&self, _: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                Err(core::fmt::Error)
+            }
+        }
+
+        let _ = try_format!("{}", Failing);
+    }
+
+    #[test]
+    fn test_try_format_alloc_error() {
+        let err = try_format!(in Failing, "{}", 1).unwrap_err();
+        assert!(matches!(err, TryReserveError::AllocError { .. }));
+    }
+
+    #[test]
+    fn test_try_format_ignored_write_error() {
+        // Drops the result of `write!`, so `fmt::write` itself succeeds.
+        struct Ignoring;
+
+        impl core::fmt::Display for Ignoring {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                let _ = write!(f, "ignored");
+                Ok(())
+            }
+        }
+
+        let err = try_format!(in Failing, "{}", Ignoring).unwrap_err();
+        assert!(matches!(err, TryReserveError::AllocError { .. }));
+    }
+}
+
+mod try_push {
+    use crate::common::Failing;
+    use alloc_wg::string::String;
+
+    #[test]
+    fn test_hands_back_char() {
+        let mut s = String::new_in(Failing);
+        let (ch, _) = s.try_push('ä').unwrap_err().into_parts();
+        assert_eq!(ch, 'ä');
+        assert_eq!(s.try_insert(0, 'x').unwrap_err().into_element(), 'x');
+    }
+}
+
+mod collect_in {
+    use alloc_wg::{alloc::Global, iter::IteratorExt, string::String, vec};
+
+    #[test]
+    fn test_collect() {
+        let s: String = "abc".chars().collect_in(Global);
+        assert_eq!(s, "abc");
+
+        let s: String = ["ab", "cd"].iter().copied().try_collect_in(Global).unwrap();
+        assert_eq!(s, "abcd");
+
+        let parts = vec![String::from("x"), String::from("yz")];
+        let s: String = parts.into_iter().try_collect_in(Global).unwrap();
+        assert_eq!(s, "xyz");
+    }
+}
+
+mod transfer_in {
+    use crate::common::Counting;
+    use alloc_wg::string::String;
+
+    #[test]
+    fn test_transfer_in() {
+        let arena = Counting::default();
+        let s = String::from("hello").transfer_in(&arena).unwrap();
+        assert_eq!(s, "hello");
+        assert_eq!(arena.live.get(), 1);
+    }
+}
+
+mod realloc_in_place {
+    use crate::common::Bump;
+    use alloc_wg::string::String;
+
+    #[test]
+    fn test_reserve_in_place() {
+        let bump = Bump::new();
+        let mut s = String::with_capacity_in(2, &bump);
+        s.push_str("ab");
+        s.try_reserve_in_place(6).unwrap();
+        assert_eq!(s.capacity(), 8);
+        assert_eq!(s, "ab");
+    }
+
+    #[test]
+    fn test_unallocated() {
+        let mut s = String::new();
+        assert!(s.try_reserve_in_place(1).is_err());
+    }
+}
+
+mod zeroed {
+    use crate::common::Counting;
+    use alloc_wg::string::String;
+
+    #[test]
+    fn with_capacity_zeroed() {
+        let alloc = Counting::default();
+        let mut s = String::with_capacity_zeroed_in(3, &alloc);
+        unsafe { s.as_mut_vec().set_len(3) };
+        assert_eq!(s, "\0\0\0");
+        assert!(String::try_with_capacity_zeroed_in(8, &alloc).is_ok());
+        assert_eq!(alloc.zeroed.get(), 2);
+    }
+}
+
+mod try_clone {
+    use crate::common::Counting;
+    use alloc_wg::{clone::TryClone, string::String};
+
+    #[test]
+    fn try_clone_ok() {
+        let s = String::from("abc");
+        assert_eq!(s.try_clone().unwrap(), "abc");
+    }
+
+    #[test]
+    fn try_clone_err() {
+        let alloc = Counting::default();
+        let s = String::from_str_in("abc", &alloc);
+        alloc.fail.set(true);
+        assert!(s.try_clone().is_err());
+    }
+}
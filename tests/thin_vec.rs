@@ -0,0 +1,71 @@
+use alloc_wg::{
+    alloc::Global,
+    iter::{FromIteratorIn, TryExtend},
+    vec::{ThinVec, Vec},
+};
+use core::mem::size_of;
+
+#[test]
+fn test_size_and_empty_header() {
+    assert_eq!(size_of::<ThinVec<u64>>(), size_of::<usize>());
+    assert_eq!(size_of::<Option<ThinVec<u64>>>(), size_of::<usize>());
+
+    let v: ThinVec<u64> = ThinVec::new();
+    assert!(!v.has_allocated());
+    assert_eq!(v.capacity(), 0);
+    assert!(v.alloc_ref().is_none());
+    let cloned = v.clone();
+    assert!(!cloned.has_allocated());
+
+    let v: ThinVec<u64> = ThinVec::new_in(Global);
+    assert!(v.has_allocated());
+    assert!(v.alloc_ref().is_some());
+}
+
+#[test]
+fn test_push_insert_remove() {
+    let mut v = ThinVec::new();
+    for i in 0..10 {
+        v.try_push(i).unwrap();
+    }
+    v.insert(0, 42);
+    assert_eq!(v.remove(1), 0);
+    assert_eq!(v.swap_remove(0), 42);
+    v.retain(|&x| x % 2 == 1);
+    assert_eq!(v, [9, 1, 3, 5, 7]);
+    v.shrink_to_fit();
+    assert_eq!(v.capacity(), 5);
+    assert_eq!(v.pop(), Some(7));
+}
+
+#[test]
+fn test_vec_round_trip() {
+    let v: ThinVec<u32, Global> = ThinVec::from_iter_in(0..8, Global);
+    let vec: Vec<u32> = v.into_vec();
+    assert_eq!(vec, [0, 1, 2, 3, 4, 5, 6, 7]);
+
+    let v: ThinVec<u32> = ThinVec::from(vec);
+    assert_eq!(v, [0, 1, 2, 3, 4, 5, 6, 7]);
+
+    let v: ThinVec<String> = ThinVec::new();
+    assert!(v.into_vec().is_empty());
+}
+
+#[test]
+fn test_into_iter_and_extend() {
+    let mut v: ThinVec<String> = ThinVec::new();
+    v.try_extend(vec!["a".to_owned(), "b".to_owned(), "c".to_owned()])
+        .unwrap();
+    let mut iter = v.into_iter();
+    assert_eq!(iter.next_back().as_deref(), Some("c"));
+    assert_eq!(iter.as_slice(), ["a", "b"]);
+}
+
+#[test]
+fn test_zst() {
+    let mut v = ThinVec::new();
+    v.extend(core::iter::repeat(()).take(5));
+    assert_eq!(v.len(), 5);
+    assert_eq!(v.capacity(), usize::MAX);
+    assert_eq!(v.into_vec().len(), 5);
+}
@@ -1,10 +1,20 @@
-#![feature(try_reserve, drain_filter)]
+#![feature(
+    allocator_api,
+    drain_filter,
+    maybe_uninit_extra,
+    maybe_uninit_ref,
+    slice_ptr_get,
+    try_reserve
+)]
 // use alloc_wg::{
 //     boxed::Box,
 //     collections::CollectionAllocErr::*,
 //     vec,
 //     vec::{Drain, IntoIter, Vec},
 // };
+
+mod common;
+
 use core::{isize, mem::size_of, usize};
 use std::{
     boxed::Box,
@@ -1369,3 +1379,554 @@ fn vec_macro_repeating_null_raw_fat_pointer() {
         vtable: *mut (),
     }
 }
+
+mod macros {
+    use alloc_wg::{alloc::Global, vec, vec::Vec};
+
+    #[test]
+    fn test_vec_try() {
+        let v: Vec<i32> = vec![try 1, 2, 3].unwrap();
+        assert_eq!(v, [1, 2, 3]);
+        assert_eq!(v.capacity(), 3);
+
+        let v = vec![try in Global; "a", "b",].unwrap();
+        assert_eq!(v, ["a", "b"]);
+        assert_eq!(v.capacity(), 2);
+
+        let v = vec![try in Global; 7u8; 4].unwrap();
+        assert_eq!(v, [7, 7, 7, 7]);
+
+        let v: Vec<u8> = vec![try 0; 5].unwrap();
+        assert_eq!(v, [0; 5]);
+
+        let v: Vec<u8> = vec![try].unwrap();
+        assert!(v.is_empty());
+
+        // no `unused_mut` warning for an empty list
+        #[deny(unused_mut)]
+        let v: Vec<u8> = vec![try in Global;].unwrap();
+        assert!(v.is_empty());
+    }
+
+    #[test]
+    fn test_vec_try_capacity_overflow() {
+        assert!(vec![try in Global; 0u64; usize::MAX].is_err());
+    }
+}
+
+mod try_push {
+    use crate::common::Failing;
+    use alloc_wg::{collections::TryReserveError, vec::Vec};
+
+    struct NotClone(u32);
+
+    #[test]
+    fn test_hands_back_value() {
+        let mut v: Vec<_, _> = Vec::new_in(Failing);
+        let err = v.try_push(NotClone(1)).unwrap_err();
+        assert!(matches!(err.error(), TryReserveError::AllocError { .. }));
+        let NotClone(x) = err.into_element();
+        assert_eq!(x, 1);
+
+        let err = v.try_insert(0, NotClone(2)).unwrap_err();
+        assert_eq!(err.into_element().0, 2);
+    }
+
+    #[test]
+    fn test_question_mark_converts() {
+        fn push(v: &mut Vec<u8, Failing>) -> Result<(), TryReserveError> {
+            v.try_push(1)?;
+            Ok(())
+        }
+        assert!(push(&mut Vec::new_in(Failing)).is_err());
+    }
+}
+
+mod try_reserve_error {
+    use crate::common::Failing;
+    use alloc_wg::{
+        alloc::{AllocError, Layout},
+        collections::TryReserveError,
+        vec::Vec,
+    };
+    use std::error::Error;
+
+    #[test]
+    fn test_alloc_error_details() {
+        let mut v: Vec<u32, Failing> = Vec::new_in(Failing);
+        let err = v.try_reserve_exact(10).unwrap_err();
+        assert_eq!(err.layout(), Some(Layout::array::<u32>(10).unwrap()));
+        assert_eq!(err.old_capacity(), Some(0));
+        assert_eq!(err.requested_capacity(), Some(10));
+        assert_eq!(err.cause(), Some(&AllocError));
+        assert!(err.source().is_some());
+        assert!(err.to_string().contains("capacity: 0 -> 10"));
+    }
+
+    #[test]
+    fn test_capacity_overflow() {
+        let mut v: Vec<u32> = Vec::new();
+        v.push(1);
+        let err = v.try_reserve(usize::MAX).unwrap_err();
+        assert_eq!(err, TryReserveError::CapacityOverflow);
+        assert_eq!(err.layout(), None);
+        assert!(err.source().is_none());
+    }
+
+    #[test]
+    fn test_invalid_layout() {
+        let err = Vec::<u32, _>::try_with_capacity_in(usize::MAX, Failing).unwrap_err();
+        assert!(matches!(err, TryReserveError::InvalidLayout(_)));
+        assert_eq!(err.layout(), None);
+        assert!(err.source().is_some());
+
+        let mut v: Vec<u32, Failing> = Vec::new_in(Failing);
+        let err = v.try_reserve(usize::MAX).unwrap_err();
+        assert!(matches!(err, TryReserveError::InvalidLayout(_)));
+    }
+
+    #[test]
+    #[should_panic(expected = "capacity overflow")]
+    fn test_handle_capacity_overflow() {
+        TryReserveError::CapacityOverflow.handle();
+    }
+}
+
+mod in_place_collect {
+    use alloc_wg::{alloc::Global, iter::IteratorExt, vec, vec::Vec};
+    use std::{cell::Cell, rc::Rc};
+
+    #[test]
+    fn test_collect_reuses_allocation() {
+        let v: Vec<u32> = (0..10).collect();
+        let (ptr, cap) = (v.as_ptr(), v.capacity());
+
+        let out: Vec<i32> = v
+            .into_iter()
+            .map(|x| x as i32 * 2)
+            .filter(|x| x % 3 != 0)
+            .collect();
+        assert_eq!(out, [2, 4, 8, 10, 14, 16]);
+        assert_eq!(out.as_ptr() as *const u32, ptr);
+        assert_eq!(out.capacity(), cap);
+    }
+
+    #[test]
+    fn test_collect_in_reuses_allocation() {
+        let v = vec![1_u64, 2, 3, 4];
+        let ptr = v.as_ptr();
+
+        let mut iter = v.into_iter();
+        iter.next();
+        let out: Vec<f64, Global> = iter.map(|x| x as f64).collect_in(Global);
+        assert_eq!(out, [2.0, 3.0, 4.0]);
+        assert_eq!(out.as_ptr() as *const u64, ptr);
+
+        let out: Vec<u64> = out
+            .into_iter()
+            .map(|x| x as u64)
+            .try_collect_in(Global)
+            .unwrap();
+        assert_eq!(out, [2, 3, 4]);
+        assert_eq!(out.as_ptr(), ptr);
+    }
+
+    #[test]
+    fn test_collect_different_layout() {
+        let v = vec![1_u8, 2, 3];
+        let out: Vec<u32> = v.into_iter().map(u32::from).collect();
+        assert_eq!(out, [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_collect_drops_remaining() {
+        let drops = Rc::new(Cell::new(0));
+        struct Counted(Rc<Cell<usize>>);
+        impl Drop for Counted {
+            fn drop(&mut self) {
+                self.0.set(self.0.get() + 1);
+            }
+        }
+
+        let v: Vec<_> = (0..6).map(|_| Counted(drops.clone())).collect();
+        let out: Vec<_> = v.into_iter().take(2).collect();
+        assert_eq!(drops.get(), 4);
+        drop(out);
+        assert_eq!(drops.get(), 6);
+    }
+}
+
+mod transfer_in {
+    use crate::common::Counting;
+    use alloc_wg::{alloc::Global, string::String, vec, vec::Vec};
+
+    #[test]
+    fn test_copy() {
+        let arena = Counting::default();
+        let mut v: Vec<_, _> = Vec::with_capacity_in(10, &arena);
+        v.extend_from_slice(&[1, 2, 3]);
+        let ptr = v.as_ptr();
+
+        let v = v.transfer_in(Global).unwrap();
+        assert_eq!(v, [1, 2, 3]);
+        assert_ne!(v.as_ptr(), ptr);
+        assert_eq!(v.capacity(), 3);
+        assert_eq!(arena.live.get(), 0);
+    }
+
+    #[test]
+    fn test_adopt() {
+        let arena = Counting::default();
+        let v = vec![in &arena; String::from("a"), String::from("b")];
+        let (ptr, cap) = (v.as_ptr(), v.capacity());
+
+        let v = v.transfer_in(&arena).unwrap();
+        assert_eq!(v, ["a", "b"]);
+        assert_eq!(v.as_ptr(), ptr);
+        assert_eq!(v.capacity(), cap);
+        assert_eq!(arena.live.get(), 1);
+
+        let other = Counting::default();
+        let v = v.transfer_in(&other).unwrap();
+        assert_ne!(v.as_ptr(), ptr);
+        assert_eq!(arena.live.get(), 0);
+        assert_eq!(other.live.get(), 1);
+    }
+
+    #[test]
+    fn test_error() {
+        let arena = Counting::default();
+        arena.fail.set(true);
+        let v = vec![String::from("a")];
+        let ptr = v.as_ptr();
+        let v = v.transfer_in(&arena).unwrap_err().into_element();
+        assert_eq!(v, ["a"]);
+        assert_eq!(v.as_ptr(), ptr);
+        assert_eq!(arena.live.get(), 0);
+    }
+}
+
+mod realloc_in_place {
+    use crate::common::Bump;
+    use alloc_wg::{
+        alloc::{AllocRef, Layout, ReallocInPlace, ReallocPlacement},
+        vec::Vec,
+    };
+
+    #[test]
+    fn test_reserve_in_place() {
+        let bump = Bump::new();
+        let mut v: Vec<_, _> = Vec::with_capacity_in(4, &bump);
+        v.extend_from_slice(&[1_u32, 2, 3, 4]);
+        let ptr = v.as_ptr();
+
+        v.try_reserve_in_place(8).unwrap();
+        assert_eq!(v.as_ptr(), ptr);
+        assert_eq!(v.capacity(), 12);
+
+        // the vector's block is no longer the most recent one
+        let _other = Vec::<u8, _>::with_capacity_in(1, &bump);
+        assert!(v.try_reserve_in_place(20).is_err());
+        assert_eq!(v.as_ptr(), ptr);
+        assert_eq!(v.capacity(), 12);
+        assert_eq!(v, [1, 2, 3, 4]);
+
+        // enough capacity is always available in place
+        v.try_reserve_in_place(8).unwrap();
+    }
+
+    #[test]
+    fn test_unallocated() {
+        let bump = Bump::new();
+        let mut v = Vec::<u8, _>::new_in(&bump);
+        assert!(v.try_reserve_in_place(1).is_err());
+        assert_eq!(v.capacity(), 0);
+    }
+
+    #[test]
+    fn test_placement() {
+        let bump = Bump::new();
+        let layout = Layout::new::<[u8; 8]>();
+        let grown = Layout::new::<[u8; 16]>();
+        unsafe {
+            let a = bump.alloc(layout).unwrap().as_non_null_ptr();
+            let _b = bump.alloc(layout).unwrap();
+
+            assert!(
+                bump.grow_with(a, layout, grown, ReallocPlacement::InPlace)
+                    .is_err()
+            );
+            let moved = bump
+                .grow_with(a, layout, grown, ReallocPlacement::MayMove)
+                .unwrap();
+            assert_ne!(moved.as_non_null_ptr(), a);
+            assert_eq!(moved.len(), 16);
+
+            // shrinking the most recent block in place frees its tail for the next allocation
+            let moved = moved.as_non_null_ptr();
+            let shrunk = bump
+                .shrink_with(moved, grown, layout, ReallocPlacement::InPlace)
+                .unwrap();
+            assert_eq!(shrunk.as_non_null_ptr(), moved);
+            assert_eq!(shrunk.len(), 8);
+            let next = bump.alloc(layout).unwrap();
+            assert_eq!(next.as_mut_ptr(), moved.as_ptr().add(8));
+        }
+    }
+}
+
+mod growth_policy {
+    use alloc_wg::{
+        alloc::Global,
+        vec::{Doubling, Exact, Factor, Fibonacci, GrowthPolicy, PageRounded, Vec},
+    };
+
+    fn capacities<G: GrowthPolicy>(pushes: usize) -> Vec<usize> {
+        let mut v: Vec<u32, _, G> = Vec::new_in(Global);
+        let mut caps = Vec::new();
+        for i in 0..pushes {
+            v.push(i as u32);
+            if caps.last() != Some(&v.capacity()) {
+                caps.push(v.capacity());
+            }
+        }
+        caps
+    }
+
+    #[test]
+    fn doubling() {
+        assert_eq!(capacities::<Doubling>(20), [4, 8, 16, 32]);
+    }
+
+    #[test]
+    fn exact() {
+        assert_eq!(capacities::<Exact>(4), [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn factor() {
+        assert_eq!(capacities::<Factor<3, 2>>(20), [
+            1, 2, 3, 4, 6, 9, 13, 19, 28
+        ]);
+
+        let mut v: Vec<u8, _, Factor<3, 2>> = Vec::with_capacity_in(10, Global);
+        v.extend(0..11);
+        assert_eq!(v.capacity(), 15);
+
+        // a ratio of at most 1 grows exactly
+        assert_eq!(capacities::<Factor<1, 2>>(4), [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn fibonacci() {
+        assert_eq!(capacities::<Fibonacci>(30), [1, 2, 3, 5, 8, 13, 21, 34]);
+    }
+
+    #[test]
+    fn page_rounded() {
+        assert_eq!(capacities::<PageRounded>(2000), [1024, 2048]);
+
+        let mut v: Vec<u64, _, PageRounded> = Vec::new().with_policy();
+        v.push(1);
+        assert_eq!(v.capacity(), 512);
+        v.extend(0..1000);
+        assert_eq!(v.capacity(), 1024);
+    }
+
+    #[test]
+    fn shrink_to_fit_uses_policy() {
+        let mut v: Vec<u64, _, PageRounded> = Vec::new().with_policy();
+        v.extend(0..1000);
+        v.truncate(10);
+        v.shrink_to_fit();
+        assert_eq!(v.capacity(), 512);
+
+        let mut v: Vec<u64, _, Exact> = Vec::with_capacity(100).with_policy();
+        v.extend(0..10);
+        v.shrink_to_fit();
+        assert_eq!(v.capacity(), 10);
+    }
+
+    #[test]
+    fn into_boxed_slice_is_exact() {
+        let mut v: Vec<u64, _, PageRounded> = Vec::new().with_policy();
+        v.extend(0..10);
+        assert_eq!(v.capacity(), 512);
+        assert_eq!(v.into_boxed_slice().len(), 10);
+    }
+
+    #[test]
+    fn drain_and_retain() {
+        let mut v: Vec<i32, _, Fibonacci> = Vec::new().with_policy();
+        v.extend(0..10);
+        let drained: Vec<i32> = v.drain(..5).collect();
+        assert_eq!(drained, [0, 1, 2, 3, 4]);
+        v.retain(|&x| x % 2 == 0);
+        assert_eq!(v, [6, 8]);
+    }
+}
+
+mod zeroed {
+    use crate::common::Counting;
+    use alloc_wg::vec::Vec;
+
+    #[test]
+    fn with_capacity_zeroed() {
+        let alloc = Counting::default();
+        let mut v = Vec::<u64, _>::with_capacity_zeroed_in(64, &alloc);
+        assert!(v.is_empty());
+        assert_eq!(v.capacity(), 64);
+        unsafe { v.set_len(64) };
+        assert!(v.iter().all(|&x| x == 0));
+
+        let v = Vec::<u8, _>::try_with_capacity_zeroed_in(16, &alloc).unwrap();
+        assert_eq!(v.capacity(), 16);
+        assert_eq!(alloc.zeroed.get(), 2);
+
+        assert!(Vec::<u64, _>::try_with_capacity_zeroed_in(usize::MAX, &alloc).is_err());
+    }
+
+    #[test]
+    fn spare_capacity_mut() {
+        let mut v = Vec::with_capacity_zeroed(8);
+        v.push(1_u32);
+        let spare = v.spare_capacity_mut();
+        assert_eq!(spare.len(), 7);
+        assert_eq!(unsafe { spare[0].assume_init() }, 0);
+        spare[0] = core::mem::MaybeUninit::new(2);
+        unsafe { v.set_len(2) };
+        assert_eq!(v, [1, 2]);
+        assert!(v.spare_capacity_mut().iter().count() == 6);
+    }
+}
+
+mod try_clone {
+    use crate::common::Counting;
+    use alloc_wg::{clone::TryClone, vec};
+    use core::cell::Cell;
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    /// Panics when the `n`th clone is made and counts the drops.
+    struct PanicOnClone<'a> {
+        clones: &'a Cell<usize>,
+        drops: &'a Cell<usize>,
+        panic_at: &'a Cell<usize>,
+    }
+
+    impl Clone for PanicOnClone<'_> {
+        fn clone(&self) -> Self {
+            self.clones.set(self.clones.get() + 1);
+            if self.clones.get() == self.panic_at.get() {
+                panic!("clone failed");
+            }
+            Self {
+                clones: self.clones,
+                drops: self.drops,
+                panic_at: self.panic_at,
+            }
+        }
+    }
+
+    impl Drop for PanicOnClone<'_> {
+        fn drop(&mut self) {
+            self.drops.set(self.drops.get() + 1);
+        }
+    }
+
+    #[test]
+    fn try_clone_ok() {
+        let v = vec![1, 2, 3];
+        assert_eq!(v.try_clone().unwrap(), [1, 2, 3]);
+    }
+
+    #[test]
+    fn try_clone_err() {
+        let alloc = Counting::default();
+        let v = vec![in &alloc; 1, 2, 3];
+        alloc.fail.set(true);
+        assert!(v.try_clone().is_err());
+    }
+
+    #[test]
+    fn panic_safety() {
+        let clones = Cell::new(0);
+        let drops = Cell::new(0);
+        let panic_at = Cell::new(usize::MAX);
+        let alloc = Counting::default();
+        let item = PanicOnClone {
+            clones: &clones,
+            drops: &drops,
+            panic_at: &panic_at,
+        };
+        let v = vec![in &alloc; item.clone(), item.clone(), item.clone(), item.clone()];
+        clones.set(0);
+        panic_at.set(3);
+        assert_eq!(alloc.live.get(), 1);
+
+        let result = catch_unwind(AssertUnwindSafe(|| v.try_clone()));
+        assert!(result.is_err());
+        assert_eq!(drops.get(), 2);
+        assert_eq!(alloc.live.get(), 1);
+    }
+}
+
+mod in_place_init {
+    use crate::common::Counting;
+    use alloc_wg::{boxed::Box, vec::Vec};
+    use core::{cell::Cell, mem::MaybeUninit, ptr};
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    const LARGE: usize = 16 << 20;
+
+    /// Initializes a large array in place, which would overflow the stack in debug builds if it
+    /// was built there first.
+    fn fill_large(slot: &mut MaybeUninit<[u8; LARGE]>) -> &mut [u8; LARGE] {
+        unsafe {
+            ptr::write_bytes(slot.as_mut_ptr(), 1, 1);
+            slot.assume_init_mut()
+        }
+    }
+
+    #[test]
+    fn push_with_large() {
+        let mut v = Vec::<[u8; LARGE]>::new();
+        v.push_with(fill_large);
+        assert_eq!(v[0][LARGE - 1], 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "has to return the initialized slot")]
+    fn push_with_wrong_slot() {
+        let other = Box::leak(Box::new(1_u32));
+        Vec::new().push_with(|_slot: &mut MaybeUninit<u32>| other);
+    }
+
+    #[test]
+    fn not_called_on_failure() {
+        let alloc = Counting::default();
+        alloc.fail.set(true);
+        let called = Cell::new(false);
+        let mut v = Vec::<u8, _>::new_in(&alloc);
+        assert!(
+            v.try_emplace_with(|slot| {
+                called.set(true);
+                slot.write(1)
+            })
+            .is_err()
+        );
+        assert!(!called.get());
+    }
+
+    #[test]
+    fn push_with() {
+        let mut v = Vec::new();
+        v.push_with(|slot| slot.write([1_u16; 128]));
+        *v.try_emplace_with(|slot| slot.write([2; 128])).unwrap() = [3; 128];
+        assert_eq!(v.len(), 2);
+        assert_eq!(v[1][0], 3);
+
+        let result = catch_unwind(AssertUnwindSafe(|| v.push_with(|_| panic!("init failed"))));
+        assert!(result.is_err());
+        assert_eq!(v.len(), 2);
+    }
+}